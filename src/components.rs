@@ -79,6 +79,21 @@ pub enum UnitType {
 #[derive(Component, Debug, Deref, DerefMut, Serialize)]
 pub struct Target(pub Vec2);
 
+/// Next waypoint on the path towards an entity's [`Target`].
+///
+/// Maintained by the pathfinding systems and fed into the DBSP circuit, where
+/// it takes the place of the target when deciding movement.
+///
+/// # Examples
+/// ```
+/// use bevy::math::Vec2;
+/// use lille::components::PathGoal;
+/// let waypoint = PathGoal(Vec2::new(2.5, 0.5));
+/// assert_eq!(waypoint.0.x, 2.5);
+/// ```
+#[derive(Component, Debug, Clone, Copy, PartialEq, Deref, DerefMut, Serialize)]
+pub struct PathGoal(pub Vec2);
+
 use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 use size_of::SizeOf;
 
@@ -144,6 +159,62 @@ pub struct BlockSlope {
     /// Partial derivative of the block surface along the Y axis.
     pub grad_y: OrderedFloat<f64>,
 }
+
+/// Door or other toggleable block, authored in Tiled on a tile.
+///
+/// The entity also carries the `Block` it occupies. While closed the block is
+/// solid, so units collide with it and can stand on it; opening the door
+/// retracts the block from the circuit and floor heights and wall collisions
+/// follow on the same tick.
+///
+/// # Examples
+/// ```
+/// use lille::components::Door;
+/// assert!(!Door::default().open, "doors start closed");
+/// ```
+#[derive(
+    Component, Reflect, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[reflect(Component, Default)]
+pub struct Door {
+    /// Whether units can pass through the door.
+    pub open: bool,
+}
+
+/// Group of blocks moving together at a constant velocity, such as a lift or
+/// a ferry.
+///
+/// The entity's `Transform` locates the platform, and each entry of `blocks`
+/// offsets one unit cube from it in whole blocks. The blocks are mirrored into
+/// the circuit as `PlatformBlock` records each tick, so entities standing on
+/// the platform are carried along, and the transform advances by the velocity
+/// once the circuit has stepped. Platform entities carry no `DdlogId`.
+///
+/// Units:
+/// - `vx`, `vy`, `vz` are blocks per tick.
+///
+/// # Examples
+/// ```
+/// use lille::components::MovingPlatform;
+/// let lift = MovingPlatform {
+///     blocks: vec![[0, 0, 0], [1, 0, 0]],
+///     vz: 0.25,
+///     ..MovingPlatform::default()
+/// };
+/// assert_eq!(lift.blocks.len(), 2);
+/// ```
+#[derive(Component, Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MovingPlatform {
+    /// Offsets of the platform's blocks from its transform.
+    pub blocks: Vec<[i32; 3]>,
+    /// Velocity along the X axis.
+    pub vx: f64,
+    /// Velocity along the Y axis.
+    pub vy: f64,
+    /// Velocity along the Z axis.
+    pub vz: f64,
+}
 /// Linear velocity measured in metres per second.
 ///
 /// Updated each tick from DBSP outputs and consumed by rendering and physics
@@ -196,3 +267,88 @@ pub struct ForceComp {
     /// Optional explicit mass overriding the global default.
     pub mass: Option<f64>,
 }
+
+/// One-shot change of velocity, such as a jump or a knock-back.
+///
+/// Mirrored into the circuit as an `Impulse` record and removed once the
+/// circuit has stepped, so each component applies for exactly one tick. An
+/// upward impulse lifts a standing entity off the ground; it then falls back
+/// through the unsupported path and takes fall damage like any other landing.
+///
+/// Units:
+/// - `dvx`, `dvy`, `dvz` are blocks per tick and are not scaled by mass.
+///
+/// # Examples
+/// ```
+/// use lille::components::ImpulseComp;
+/// let jump = ImpulseComp { dvz: 2.0, ..ImpulseComp::default() };
+/// assert_eq!(jump.dvx, 0.0);
+/// ```
+#[derive(Component, Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImpulseComp {
+    /// Change of velocity along the X axis.
+    pub dvx: f64,
+    /// Change of velocity along the Y axis.
+    pub dvy: f64,
+    /// Change of velocity along the Z axis.
+    pub dvz: f64,
+}
+
+/// Horizontal collision radius used to keep units from stacking.
+///
+/// Mirrored into the circuit as an `Extent` record each tick. Entities without
+/// this component never push or get pushed by their neighbours.
+///
+/// Units:
+/// - `radius` is in blocks (1.0 == one block).
+///
+/// # Examples
+/// ```
+/// use lille::components::ExtentComp;
+/// use lille::DEFAULT_UNIT_RADIUS;
+/// assert_eq!(ExtentComp::default().radius, DEFAULT_UNIT_RADIUS);
+/// ```
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct ExtentComp {
+    /// Radius of the entity's collision circle.
+    pub radius: f64,
+}
+
+impl Default for ExtentComp {
+    fn default() -> Self {
+        Self {
+            radius: crate::DEFAULT_UNIT_RADIUS,
+        }
+    }
+}
+
+/// Air drag coefficient overriding the configured default for one entity.
+///
+/// Mirrored into the circuit as a `Drag` record each tick. Entities without
+/// this component use `PhysicsConfig::air_friction`, so a heavy unit can keep
+/// its momentum through a fall while a light one drifts to a stop.
+///
+/// Units:
+/// - `coefficient` is the unitless fraction of horizontal velocity lost per
+///   airborne tick.
+///
+/// # Examples
+/// ```
+/// use lille::components::DragComp;
+/// use lille::AIR_FRICTION;
+/// assert_eq!(DragComp::default().coefficient, AIR_FRICTION);
+/// ```
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct DragComp {
+    /// Fraction of horizontal velocity lost per airborne tick.
+    pub coefficient: f64,
+}
+
+impl Default for DragComp {
+    fn default() -> Self {
+        Self {
+            coefficient: crate::AIR_FRICTION,
+        }
+    }
+}
@@ -1,27 +1,53 @@
 //! Game physics constants shared across systems.
 //!
-//! Distances are measured in block units, time in simulation ticks, and mass
+//! Distances are measured in block units, time in seconds, and mass
 //! in kilograms. Values use `f64` to align with the Database Stream Processor
 //! (DBSP) circuit's numeric type and minimize rounding error.
 /// Distance from the floor considered standing, in block units.
 pub const GRACE_DISTANCE: f64 = 0.1;
-/// Coefficient of ground friction, unitless.
+/// Default height an entity can step up or down in one move, in block units.
+///
+/// Destination cells whose floor exceeds the entity's current height by more
+/// than this allowance are treated as walls; cells whose floor lies further
+/// below are ledges the entity falls from. Maps override it through
+/// [`PhysicsConfig::max_step_height`](crate::PhysicsConfig::max_step_height).
+pub const MAX_STEP_HEIGHT: f64 = 1.0;
+/// Height of an entity's body above its feet, in block units.
+///
+/// Rising entities stop once their head reaches the ceiling above their floor.
+pub const ENTITY_HEIGHT: f64 = 1.0;
+/// Collision radius given to spawned units, in block units.
+pub const DEFAULT_UNIT_RADIUS: f64 = 0.25;
+/// Separation below which two entities are treated as coincident, in block
+/// units. Coincident pairs are pushed apart along the X axis, ordered by
+/// entity id, so the outcome stays deterministic.
+pub const SEPARATION_EPSILON: f64 = 1e-6;
+/// Fraction of horizontal speed ground friction removes per second, unitless.
 pub const GROUND_FRICTION: f64 = 0.1;
-/// Coefficient of air friction, unitless.
+/// Fraction of horizontal speed air friction removes per second, unitless.
 pub const AIR_FRICTION: f64 = 0.02;
-/// Maximum downward speed in block units per tick.
+/// Maximum downward speed in block units per second.
 pub const TERMINAL_VELOCITY: f64 = 12.0;
-/// Downward acceleration in block units per tick squared.
+/// Downward acceleration in block units per second squared.
 pub const GRAVITY_PULL: f64 = -1.0;
-/// Safe landing speed in block units per tick.
+/// Default height below which entities falling through the void are killed,
+/// in block units.
+///
+/// Maps override it through
+/// [`PhysicsConfig::kill_plane_z`](crate::PhysicsConfig::kill_plane_z).
+pub const KILL_PLANE_Z: f64 = -32.0;
+/// Safe landing speed in block units per second.
 pub const SAFE_LANDING_SPEED: f64 = 6.0;
 /// Damage scaling applied to speed beyond the safe landing threshold, in
-/// health points per block per tick.
+/// health points per block per second.
 pub const FALL_DAMAGE_SCALE: f64 = 4.0;
-/// Minimum interval between fall damage applications, in ticks. One tick equals
-/// `DELTA_TIME` seconds.
-pub const LANDING_COOLDOWN_TICKS: u32 = 6;
-/// Simulation time step in seconds.
+/// Minimum interval between fall damage applications, in seconds.
+pub const LANDING_COOLDOWN: f64 = 6.0;
+/// Default simulation time step in seconds.
+///
+/// Every tick advances the simulation by this much game time unless
+/// [`PhysicsConfig::delta_time`](crate::PhysicsConfig::delta_time) says
+/// otherwise.
 pub const DELTA_TIME: f64 = 1.0;
 /// Default entity mass in kilograms.
 pub const DEFAULT_MASS: f64 = 70.0;
@@ -32,11 +58,22 @@ pub const FEAR_THRESHOLD: f64 = 0.2;
 /// Minimum squared distance added to fear calculations to avoid division by
 /// zero when threats coincide with the actor, in block units.
 pub const FEAR_DISTANCE_EPSILON: f64 = 0.001;
-/// Normalised offset used to sample slopes within a block, unitless.
+/// Default distance from a target within which approaching entities slow
+/// down, in block units.
+///
+/// Inside the radius an entity moves at `distance / radius` of full speed, so
+/// it eases onto its target instead of overshooting.
+pub const ARRIVAL_RADIUS: f64 = 1.0;
+/// Distance from a target at which an entity counts as having arrived, in
+/// block units.
+pub const ARRIVAL_TOLERANCE: f64 = 1e-3;
+/// Default weight of the obstacle-avoidance term in a blended steering
+/// decision, unitless.
+pub const AVOIDANCE_WEIGHT: f64 = 0.5;
+/// Normalised offset of a block's centre within its cell, unitless.
 ///
-/// The `FloorHeightAt` calculation currently evaluates the slope at the
-/// centre of each block because entity-specific offsets are not yet
-/// available.
+/// `FloorHeightAt::z` holds the slope evaluated at this offset, and
+/// `FloorHeightAt::sample` measures an entity's position relative to it.
 pub const BLOCK_CENTRE_OFFSET: f64 = 0.5;
 /// Offset from a block's base to its top face, in block units.
 pub const BLOCK_TOP_OFFSET: f64 = 1.0;
@@ -18,21 +18,30 @@
 use anyhow::Error as AnyError;
 use dbsp::circuit::Circuit;
 use dbsp::{
-    operator::Generator, typed_batch::OrdZSet, CircuitHandle, OutputHandle, RootCircuit, ZSetHandle,
+    operator::Generator, typed_batch::OrdZSet, CircuitHandle, DBSPHandle, InputHandle,
+    OutputHandle, RootCircuit, Runtime, Stream, ZSetHandle,
 };
 
 use crate::components::{Block, BlockSlope};
+use crate::PhysicsConfig;
 
 use super::helpers::{advance_tick, within_grace};
+use super::input::{StagedInput, StagedZSet};
+use super::pipeline::StepThread;
 use super::streams::{
-    apply_movement, fall_damage_stream, fear_level_stream, floor_height_stream,
-    health_delta_stream, highest_block_pair, movement_decision_streams, new_position_stream,
-    new_velocity_stream, position_floor_stream, standing_motion_stream, PositionFloor,
+    air_drag_stream, apply_separation, fall_damage_stream, fear_level_stream, fleeing_goal_stream,
+    health_delta_stream, highest_block_pair, impulse_velocity_stream, kill_plane_damage_stream,
+    layered_floor_height_stream, movement_decision_streams, movement_steps, new_velocity_stream,
+    platform_floor_stream, platform_rider_stream, position_floor_stream, separation_stream,
+    standing_motion_stream, steering_goal_stream, steering_streams, swept_fall_stream,
+    threat_fear_stream, void_position_stream, wall_collision_stream, PositionFloor,
+    SteeringContext, SweptFall,
 };
 use super::types::{
-    DamageEvent, FearLevel, FloorHeightAt, Force, HealthDelta, HealthState, HighestBlockAt,
-    MovementAggregation, NewPosition, NewVelocity, PlayerSpawnLocation, Position, SpawnPointRecord,
-    Target, Tick, Velocity,
+    Arrived, Baddie, Civvy, DamageEvent, Drag, Extent, FearLevel, FloorHeightAt, Force,
+    HealthDelta, HealthState, HighestBlockAt, Impulse, MovementAggregation, MovementDecision,
+    NewPosition, NewVelocity, PathGoal, PlatformBlock, PlayerSpawnLocation, Position,
+    SpawnPointRecord, Target, ThreatFear, Tick, Velocity,
 };
 
 /// Authoritative DBSP dataflow for Lille's world simulation.
@@ -54,10 +63,17 @@ use super::types::{
 /// // circuit.position_in().push(Position { /* ... */ }, 1);
 /// // circuit.velocity_in().push(Velocity { /* ... */ }, 1);
 /// // circuit.force_in().push(Force { /* ... */ }, 1);
+/// // circuit.impulse_in().push(Impulse { /* ... */ }, 1);
+/// // circuit.extent_in().push(Extent { /* ... */ }, 1);
+/// // circuit.drag_in().push(Drag { /* ... */ }, 1);
 /// // circuit.fear_in().push(FearLevel { /* ... */ }, 1);
+/// // circuit.civvy_in().push(Civvy { /* ... */ }, 1);
+/// // circuit.baddie_in().push(Baddie { /* ... */ }, 1);
 /// // circuit.target_in().push(Target { /* ... */ }, 1);
+/// // circuit.path_goal_in().push(PathGoal { /* ... */ }, 1);
 /// // circuit.block_in().push(Block { /* ... */ }, 1);
 /// // circuit.block_slope_in().push(BlockSlope { /* ... */ }, 1);
+/// // circuit.platform_block_in().push(PlatformBlock { /* ... */ }, 1);
 ///
 /// // 2) Advance the circuit.
 /// lille::dbsp_circuit::step(&mut circuit);
@@ -69,18 +85,28 @@ use super::types::{
 /// circuit.clear_inputs();
 /// ```
 pub struct DbspCircuit {
-    pub(crate) circuit: CircuitHandle,
-    position_in: ZSetHandle<Position>,
-    velocity_in: ZSetHandle<Velocity>,
-    force_in: ZSetHandle<Force>,
-    fear_in: ZSetHandle<FearLevel>,
-    target_in: ZSetHandle<Target>,
-    health_state_in: ZSetHandle<HealthState>,
-    damage_in: ZSetHandle<DamageEvent>,
-    block_in: ZSetHandle<Block>,
-    block_slope_in: ZSetHandle<BlockSlope>,
-    player_spawn_in: ZSetHandle<PlayerSpawnLocation>,
-    spawn_point_in: ZSetHandle<SpawnPointRecord>,
+    runner: CircuitRunner,
+    status: StepStatus,
+    workers: usize,
+    position_in: StagedZSet<Position>,
+    velocity_in: StagedZSet<Velocity>,
+    force_in: StagedZSet<Force>,
+    impulse_in: StagedZSet<Impulse>,
+    extent_in: StagedZSet<Extent>,
+    drag_in: StagedZSet<Drag>,
+    fear_in: StagedZSet<FearLevel>,
+    civvy_in: StagedZSet<Civvy>,
+    baddie_in: StagedZSet<Baddie>,
+    target_in: StagedZSet<Target>,
+    path_goal_in: StagedZSet<PathGoal>,
+    health_state_in: StagedZSet<HealthState>,
+    damage_in: StagedZSet<DamageEvent>,
+    block_in: StagedZSet<Block>,
+    block_slope_in: StagedZSet<BlockSlope>,
+    platform_block_in: StagedZSet<PlatformBlock>,
+    player_spawn_in: StagedZSet<PlayerSpawnLocation>,
+    spawn_point_in: StagedZSet<SpawnPointRecord>,
+    physics_config_in: InputHandle<PhysicsConfig>,
     new_position_out: OutputHandle<OrdZSet<NewPosition>>,
     new_velocity_out: OutputHandle<OrdZSet<NewVelocity>>,
     highest_block_out: OutputHandle<OrdZSet<HighestBlockAt>>,
@@ -88,20 +114,52 @@ pub struct DbspCircuit {
     position_floor_out: OutputHandle<OrdZSet<PositionFloor>>,
     health_delta_out: OutputHandle<OrdZSet<HealthDelta>>,
     movement_aggregation_out: OutputHandle<OrdZSet<MovementAggregation>>,
+    arrived_out: OutputHandle<OrdZSet<Arrived>>,
+    /// Configuration applied from the next step onwards.
+    physics_config: PhysicsConfig,
+}
+
+/// Executor stepping the circuit.
+enum CircuitRunner {
+    /// Single circuit evaluated on the calling thread.
+    Local(CircuitHandle),
+    /// Identical circuits evaluated by the worker threads of a DBSP runtime.
+    Workers(DBSPHandle),
+    /// Runtime stepped on a dedicated thread, so the caller does not wait for
+    /// the step to finish.
+    Pipelined(StepThread),
+}
+
+/// Progress of the step most recently started.
+enum StepStatus {
+    /// No step awaits collection.
+    Idle,
+    /// The step thread is still computing.
+    Running,
+    /// The step has finished with this outcome.
+    Finished(Result<(), dbsp::Error>),
 }
 
 struct BuildHandles {
     position_in: ZSetHandle<Position>,
     velocity_in: ZSetHandle<Velocity>,
     force_in: ZSetHandle<Force>,
+    impulse_in: ZSetHandle<Impulse>,
+    extent_in: ZSetHandle<Extent>,
+    drag_in: ZSetHandle<Drag>,
     fear_in: ZSetHandle<FearLevel>,
+    civvy_in: ZSetHandle<Civvy>,
+    baddie_in: ZSetHandle<Baddie>,
     target_in: ZSetHandle<Target>,
+    path_goal_in: ZSetHandle<PathGoal>,
     health_state_in: ZSetHandle<HealthState>,
     damage_in: ZSetHandle<DamageEvent>,
     block_in: ZSetHandle<Block>,
     block_slope_in: ZSetHandle<BlockSlope>,
+    platform_block_in: ZSetHandle<PlatformBlock>,
     player_spawn_in: ZSetHandle<PlayerSpawnLocation>,
     spawn_point_in: ZSetHandle<SpawnPointRecord>,
+    physics_config_in: InputHandle<PhysicsConfig>,
     new_position_out: OutputHandle<OrdZSet<NewPosition>>,
     new_velocity_out: OutputHandle<OrdZSet<NewVelocity>>,
     highest_block_out: OutputHandle<OrdZSet<HighestBlockAt>>,
@@ -109,6 +167,205 @@ struct BuildHandles {
     position_floor_out: OutputHandle<OrdZSet<PositionFloor>>,
     health_delta_out: OutputHandle<OrdZSet<HealthDelta>>,
     movement_aggregation_out: OutputHandle<OrdZSet<MovementAggregation>>,
+    arrived_out: OutputHandle<OrdZSet<Arrived>>,
+}
+
+/// Entities partitioned by how the floor grid supports them.
+struct FloorPartition {
+    /// Entities within [`GRACE_DISTANCE`](crate::GRACE_DISTANCE) of their
+    /// floor that are not being launched by an [`Impulse`].
+    standing: Stream<RootCircuit, OrdZSet<PositionFloor>>,
+    /// Entities above the floor of their cell, and grounded entities launched
+    /// off it this tick.
+    unsupported: Stream<RootCircuit, OrdZSet<PositionFloor>>,
+    /// Entities over cells without a floor.
+    void: Stream<RootCircuit, OrdZSet<Position>>,
+}
+
+impl FloorPartition {
+    fn new(
+        positions: &Stream<RootCircuit, OrdZSet<Position>>,
+        pos_floor: &Stream<RootCircuit, OrdZSet<PositionFloor>>,
+        floor_height: &Stream<RootCircuit, OrdZSet<FloorHeightAt>>,
+        impulses: &Stream<RootCircuit, OrdZSet<Impulse>>,
+    ) -> Self {
+        let launched = impulses
+            .filter(Impulse::launches)
+            .map_index(|i| (i.entity, ()))
+            .distinct();
+        let grounded = pos_floor
+            .filter(within_grace)
+            .map_index(|pf| (pf.position.entity, pf.clone()));
+        Self {
+            standing: grounded.antijoin(&launched).map(|(_, pf)| pf.clone()),
+            unsupported: pos_floor
+                .filter(|pf| !within_grace(pf))
+                .plus(&grounded.join(&launched, |_, pf, ()| pf.clone())),
+            void: void_position_stream(positions, floor_height),
+        }
+    }
+
+    /// Positions of every entity that falls this tick: those above their
+    /// floor and those over the void.
+    fn falling_positions(&self) -> Stream<RootCircuit, OrdZSet<Position>> {
+        self.unsupported.map(|pf| pf.position).plus(&self.void)
+    }
+
+    /// Velocities of the falling entities after air drag.
+    fn falling_velocities(
+        &self,
+        velocities: &Stream<RootCircuit, OrdZSet<Velocity>>,
+        drags: &Stream<RootCircuit, OrdZSet<Drag>>,
+        config: &Stream<RootCircuit, PhysicsConfig>,
+    ) -> Stream<RootCircuit, OrdZSet<Velocity>> {
+        let falling = velocities.map_index(|v| (v.entity, *v)).join(
+            &self.falling_positions().map_index(|p| (p.entity, ())),
+            |_, vel, ()| *vel,
+        );
+        air_drag_stream(&falling, drags, config)
+    }
+
+    /// Sweeps the falling entities against the floor along their velocities.
+    fn fall(
+        &self,
+        falling_velocities: &Stream<RootCircuit, OrdZSet<Velocity>>,
+        floor_height: &Stream<RootCircuit, OrdZSet<FloorHeightAt>>,
+        config: &Stream<RootCircuit, PhysicsConfig>,
+    ) -> SweptFall {
+        swept_fall_stream(
+            &self.falling_positions(),
+            falling_velocities,
+            floor_height,
+            config,
+        )
+    }
+
+    /// Damage derived from the partition: fall damage for landings and lethal
+    /// damage for entities dropping through the kill plane.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "Fall damage needs both velocity sources, the tick and the physics configuration."
+    )]
+    fn damage(
+        &self,
+        falling_velocities: &Stream<RootCircuit, OrdZSet<Velocity>>,
+        swept_landings: &Stream<RootCircuit, OrdZSet<Velocity>>,
+        ticks: &Stream<RootCircuit, Tick>,
+        config: &Stream<RootCircuit, PhysicsConfig>,
+    ) -> Stream<RootCircuit, OrdZSet<DamageEvent>> {
+        let fall = fall_damage_stream(
+            &self.standing,
+            &self.unsupported,
+            falling_velocities,
+            swept_landings,
+            ticks,
+            config,
+        );
+        fall.plus(&kill_plane_damage_stream(
+            &self.void,
+            falling_velocities,
+            ticks,
+            config,
+        ))
+    }
+}
+
+/// Input streams steering entities towards or away from their goals.
+struct SteeringInputs {
+    fears: Stream<RootCircuit, OrdZSet<FearLevel>>,
+    civvies: Stream<RootCircuit, OrdZSet<Civvy>>,
+    baddies: Stream<RootCircuit, OrdZSet<Baddie>>,
+    targets: Stream<RootCircuit, OrdZSet<Target>>,
+    path_goals: Stream<RootCircuit, OrdZSet<PathGoal>>,
+}
+
+/// Handles feeding [`SteeringInputs`].
+struct SteeringHandles {
+    fears: ZSetHandle<FearLevel>,
+    civvies: ZSetHandle<Civvy>,
+    baddies: ZSetHandle<Baddie>,
+    targets: ZSetHandle<Target>,
+    path_goals: ZSetHandle<PathGoal>,
+}
+
+impl SteeringInputs {
+    fn add(circuit: &mut RootCircuit) -> (Self, SteeringHandles) {
+        let (fears, fear_in) = circuit.add_input_zset::<FearLevel>();
+        let (civvies, civvy_in) = circuit.add_input_zset::<Civvy>();
+        let (baddies, baddie_in) = circuit.add_input_zset::<Baddie>();
+        let (targets, target_in) = circuit.add_input_zset::<Target>();
+        let (path_goals, path_goal_in) = circuit.add_input_zset::<PathGoal>();
+        (
+            Self {
+                fears,
+                civvies,
+                baddies,
+                targets,
+                path_goals,
+            },
+            SteeringHandles {
+                fears: fear_in,
+                civvies: civvy_in,
+                baddies: baddie_in,
+                targets: target_in,
+                path_goals: path_goal_in,
+            },
+        )
+    }
+
+    /// Movement decisions towards each entity's waypoint, or its target when
+    /// the pathfinder has not supplied one, blended by the steering stage.
+    /// Civilians frightened by nearby baddies flee them instead; explicit
+    /// fear levels override the fear derived from threats.
+    fn decisions(
+        &self,
+        positions: &Stream<RootCircuit, OrdZSet<Position>>,
+        floor_height: &Stream<RootCircuit, OrdZSet<FloorHeightAt>>,
+        config: &Stream<RootCircuit, PhysicsConfig>,
+    ) -> SteeredMovement {
+        let threats = threat_fear_stream(positions, &self.civvies, &self.baddies, &self.fears);
+        let fear = fear_level_stream(
+            positions,
+            &self.fears.plus(&threats.map(ThreatFear::fear_level)),
+        );
+        let goals = fleeing_goal_stream(
+            &steering_goal_stream(&self.targets, &self.path_goals),
+            &threats,
+            config,
+        );
+        let (seek, aggregations) = movement_decision_streams(&fear, &goals, positions, config);
+        let context = SteeringContext {
+            fear: &fear,
+            goals: &goals,
+            positions,
+            floor_height,
+            config,
+        };
+        let (decisions, arrivals) = steering_streams(&seek, context);
+        SteeredMovement {
+            decisions,
+            aggregations,
+            arrivals,
+        }
+    }
+}
+
+/// Streams derived from [`SteeringInputs`].
+struct SteeredMovement {
+    /// One blended movement decision per steering entity.
+    decisions: Stream<RootCircuit, OrdZSet<MovementDecision>>,
+    /// Diagnostics for entities whose seek decisions were collapsed.
+    aggregations: Stream<RootCircuit, OrdZSet<MovementAggregation>>,
+    /// Diagnostics for entities standing on their target.
+    arrivals: Stream<RootCircuit, OrdZSet<Arrived>>,
+}
+
+/// Counts simulation ticks from zero, one value per step on every worker.
+fn tick_source(circuit: &mut RootCircuit) -> Stream<RootCircuit, Tick> {
+    circuit.add_source(Generator::new({
+        let mut tick: Tick = 0;
+        move || advance_tick(&mut tick)
+    }))
 }
 
 impl DbspCircuit {
@@ -136,20 +393,110 @@ impl DbspCircuit {
     pub fn new() -> Result<Self, dbsp::Error> {
         let (circuit, handles) =
             RootCircuit::build(|circuit| Self::build_streams(circuit).map_err(AnyError::from))?;
+        Ok(Self::from_handles(
+            CircuitRunner::Local(circuit),
+            1,
+            handles,
+        ))
+    }
+
+    /// Constructs a `DbspCircuit` evaluated by `workers` threads.
+    ///
+    /// Each worker runs an identical copy of the dataflow, and DBSP shards the
+    /// records between them at every join and aggregation, so a step yields
+    /// the same outputs as [`DbspCircuit::new`] while spreading the work of
+    /// large scenes across cores. Inputs and outputs are used exactly as for a
+    /// single-threaded circuit. A `workers` count below two builds the
+    /// single-threaded circuit of [`DbspCircuit::new`], which steps on the
+    /// calling thread.
+    ///
+    /// # Errors
+    /// Returns a DBSP error if the worker runtime or the circuit fails to
+    /// build.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use lille::prelude::*;
+    /// let mut circuit = DbspCircuit::with_workers(4).expect("circuit construction failed");
+    /// assert_eq!(circuit.workers(), 4);
+    /// circuit.step().expect("circuit evaluation failed");
+    /// ```
+    pub fn with_workers(workers: usize) -> Result<Self, dbsp::Error> {
+        if workers < 2 {
+            return Self::new();
+        }
+        let (handle, handles) = Runtime::init_circuit(workers, |circuit| {
+            Self::build_streams(circuit).map_err(AnyError::from)
+        })?;
+        Ok(Self::from_handles(
+            CircuitRunner::Workers(handle),
+            workers,
+            handles,
+        ))
+    }
+
+    /// Constructs a pipelined `DbspCircuit` evaluated by `workers` threads.
+    ///
+    /// The circuit steps on a dedicated thread: [`DbspCircuit::start_step`]
+    /// returns as soon as the step is requested, and
+    /// [`DbspCircuit::poll_step`] reports its outcome once it is ready.
+    /// Records pushed in the meantime are staged for the following step, so
+    /// the caller can gather the next tick's inputs while the current one
+    /// computes. [`DbspCircuit::step`] still waits for each step, yielding
+    /// the same outputs as [`DbspCircuit::with_workers`]. A `workers` count
+    /// of zero is treated as one.
+    ///
+    /// # Errors
+    /// Returns a DBSP error if the worker runtime, the circuit or the step
+    /// thread fails to build.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use lille::prelude::*;
+    /// let mut circuit = DbspCircuit::pipelined(1).expect("circuit construction failed");
+    /// circuit.start_step();
+    /// // Stage the next tick's inputs while the step computes.
+    /// let outcome = circuit.finish_step();
+    /// assert!(outcome.is_ok());
+    /// ```
+    pub fn pipelined(workers: usize) -> Result<Self, dbsp::Error> {
+        let worker_count = workers.max(1);
+        let (handle, handles) = Runtime::init_circuit(worker_count, |circuit| {
+            Self::build_streams(circuit).map_err(AnyError::from)
+        })?;
+        Ok(Self::from_handles(
+            CircuitRunner::Pipelined(StepThread::spawn(handle)?),
+            worker_count,
+            handles,
+        ))
+    }
 
-        Ok(Self {
-            circuit,
-            position_in: handles.position_in,
-            velocity_in: handles.velocity_in,
-            force_in: handles.force_in,
-            fear_in: handles.fear_in,
-            target_in: handles.target_in,
-            health_state_in: handles.health_state_in,
-            damage_in: handles.damage_in,
-            block_in: handles.block_in,
-            block_slope_in: handles.block_slope_in,
-            player_spawn_in: handles.player_spawn_in,
-            spawn_point_in: handles.spawn_point_in,
+    fn from_handles(runner: CircuitRunner, workers: usize, handles: BuildHandles) -> Self {
+        Self {
+            runner,
+            status: StepStatus::Idle,
+            workers,
+            position_in: StagedZSet::new(handles.position_in),
+            velocity_in: StagedZSet::new(handles.velocity_in),
+            force_in: StagedZSet::new(handles.force_in),
+            impulse_in: StagedZSet::new(handles.impulse_in),
+            extent_in: StagedZSet::new(handles.extent_in),
+            drag_in: StagedZSet::new(handles.drag_in),
+            fear_in: StagedZSet::new(handles.fear_in),
+            civvy_in: StagedZSet::new(handles.civvy_in),
+            baddie_in: StagedZSet::new(handles.baddie_in),
+            target_in: StagedZSet::new(handles.target_in),
+            path_goal_in: StagedZSet::new(handles.path_goal_in),
+            health_state_in: StagedZSet::new(handles.health_state_in),
+            damage_in: StagedZSet::new(handles.damage_in),
+            block_in: StagedZSet::new(handles.block_in),
+            block_slope_in: StagedZSet::new(handles.block_slope_in),
+            platform_block_in: StagedZSet::new(handles.platform_block_in),
+            player_spawn_in: StagedZSet::new(handles.player_spawn_in),
+            spawn_point_in: StagedZSet::new(handles.spawn_point_in),
+            physics_config_in: handles.physics_config_in,
             new_position_out: handles.new_position_out,
             new_velocity_out: handles.new_velocity_out,
             highest_block_out: handles.highest_block_out,
@@ -157,7 +504,29 @@ impl DbspCircuit {
             position_floor_out: handles.position_floor_out,
             health_delta_out: handles.health_delta_out,
             movement_aggregation_out: handles.movement_aggregation_out,
-        })
+            arrived_out: handles.arrived_out,
+            physics_config: PhysicsConfig::default(),
+        }
+    }
+
+    /// Returns the number of worker threads evaluating the circuit.
+    #[must_use]
+    pub const fn workers(&self) -> usize {
+        self.workers
+    }
+
+    /// Returns `true` when the circuit steps on a dedicated thread, as built
+    /// by [`DbspCircuit::pipelined`].
+    #[must_use]
+    pub const fn is_pipelined(&self) -> bool {
+        matches!(self.runner, CircuitRunner::Pipelined(_))
+    }
+
+    /// Returns `true` while a started step has not been collected with
+    /// [`DbspCircuit::poll_step`] or [`DbspCircuit::finish_step`].
+    #[must_use]
+    pub const fn is_stepping(&self) -> bool {
+        !matches!(self.status, StepStatus::Idle)
     }
 
     /// Advances the DBSP circuit by one tick.
@@ -167,7 +536,10 @@ impl DbspCircuit {
     /// handles with derived positions, velocities, and terrain queries for this
     /// tick. This method does not clear inputs; input collections persist across
     /// steps. Invoke [`DbspCircuit::clear_inputs`] after processing outputs to avoid
-    /// stale state carrying into the next frame.
+    /// stale state carrying into the next frame. A configuration passed to
+    /// [`DbspCircuit::set_physics_config`] since the previous step is applied
+    /// from this step onwards. Equivalent to [`DbspCircuit::start_step`]
+    /// followed by [`DbspCircuit::finish_step`].
     ///
     /// # Errors
     ///
@@ -181,69 +553,155 @@ impl DbspCircuit {
     /// circuit.step().expect("circuit evaluation failed");
     /// ```
     pub fn step(&mut self) -> Result<(), dbsp::Error> {
-        self.circuit.step()
+        self.start_step();
+        self.finish_step()
+    }
+
+    /// Starts advancing the DBSP circuit by one tick.
+    ///
+    /// Hands the staged input records and the physics configuration to the
+    /// circuit. A pipelined circuit computes the step on its own thread and
+    /// this returns at once; any other circuit computes it before returning.
+    /// Either way the outcome is collected with [`DbspCircuit::poll_step`] or
+    /// [`DbspCircuit::finish_step`], and the output handles must not be read
+    /// before then. Does nothing while a started step awaits collection.
+    pub fn start_step(&mut self) {
+        if self.is_stepping() {
+            return;
+        }
+        self.physics_config_in.set_for_all(self.physics_config);
+        self.flush_inputs();
+        self.status = match &mut self.runner {
+            CircuitRunner::Local(handle) => StepStatus::Finished(handle.step()),
+            CircuitRunner::Workers(handle) => StepStatus::Finished(handle.step()),
+            CircuitRunner::Pipelined(thread) => match thread.request() {
+                Ok(()) => StepStatus::Running,
+                Err(error) => StepStatus::Finished(Err(error)),
+            },
+        };
+    }
+
+    /// Collects the outcome of the started step if it has finished.
+    ///
+    /// Returns `None` while the step is still computing, and when no step was
+    /// started.
+    pub fn poll_step(&mut self) -> Option<Result<(), dbsp::Error>> {
+        match std::mem::replace(&mut self.status, StepStatus::Idle) {
+            StepStatus::Idle => None,
+            StepStatus::Finished(result) => Some(result),
+            StepStatus::Running => {
+                let result = match &self.runner {
+                    CircuitRunner::Pipelined(thread) => thread.try_result(),
+                    CircuitRunner::Local(_) | CircuitRunner::Workers(_) => None,
+                };
+                if result.is_none() {
+                    self.status = StepStatus::Running;
+                }
+                result
+            }
+        }
+    }
+
+    /// Waits for the started step to finish and returns its outcome.
+    ///
+    /// Returns `Ok(())` when no step was started.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any error reported by the underlying DBSP circuit.
+    pub fn finish_step(&mut self) -> Result<(), dbsp::Error> {
+        match std::mem::replace(&mut self.status, StepStatus::Idle) {
+            StepStatus::Idle => Ok(()),
+            StepStatus::Finished(result) => result,
+            StepStatus::Running => match &self.runner {
+                CircuitRunner::Pipelined(thread) => thread.wait(),
+                CircuitRunner::Local(_) | CircuitRunner::Workers(_) => Ok(()),
+            },
+        }
+    }
+
+    /// Replaces the physics configuration used by the circuit.
+    ///
+    /// The change takes effect on the next call to [`DbspCircuit::step`].
+    /// Unlike the other inputs, the configuration persists across steps and
+    /// is unaffected by [`DbspCircuit::clear_inputs`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use lille::prelude::*;
+    /// let mut circuit = DbspCircuit::new().expect("circuit construction failed");
+    /// circuit.set_physics_config(PhysicsConfig {
+    ///     gravity_pull: OrderedFloat(-0.5),
+    ///     ..PhysicsConfig::default()
+    /// });
+    /// circuit.step().expect("circuit evaluation failed");
+    /// ```
+    pub const fn set_physics_config(&mut self, config: PhysicsConfig) {
+        self.physics_config = config;
+    }
+
+    /// Returns the physics configuration the next step will use.
+    #[must_use]
+    pub const fn physics_config(&self) -> &PhysicsConfig {
+        &self.physics_config
     }
 
     #[expect(
         clippy::unnecessary_wraps,
-        reason = "RootCircuit::build expects constructors that return Result."
+        reason = "Circuit builders expect constructors that return Result."
     )]
     fn build_streams(circuit: &mut RootCircuit) -> Result<BuildHandles, dbsp::Error> {
-        let (positions, position_in) = circuit.add_input_zset::<Position>();
+        let (entity_positions, position_in) = circuit.add_input_zset::<Position>();
         let (velocities, velocity_in) = circuit.add_input_zset::<Velocity>();
         let (forces, force_in) = circuit.add_input_zset::<Force>();
-        let (fears, fear_in) = circuit.add_input_zset::<FearLevel>();
-        let (targets, target_in) = circuit.add_input_zset::<Target>();
+        let (impulses, impulse_in) = circuit.add_input_zset::<Impulse>();
+        let (extents, extent_in) = circuit.add_input_zset::<Extent>();
+        let (drags, drag_in) = circuit.add_input_zset::<Drag>();
+        let (steering, steering_handles) = SteeringInputs::add(circuit);
         let (health_states, health_state_in) = circuit.add_input_zset::<HealthState>();
         let (damage_events, damage_in) = circuit.add_input_zset::<DamageEvent>();
         let (blocks, block_in) = circuit.add_input_zset::<Block>();
         let (slopes, block_slope_in) = circuit.add_input_zset::<BlockSlope>();
+        let (platform_blocks, platform_block_in) = circuit.add_input_zset::<PlatformBlock>();
         let (_player_spawns, player_spawn_in) = circuit.add_input_zset::<PlayerSpawnLocation>();
         let (_spawn_points, spawn_point_in) = circuit.add_input_zset::<SpawnPointRecord>();
+        let (config, physics_config_in) = circuit.add_input_stream::<PhysicsConfig>();
 
-        let tick_source = circuit.add_source(Generator::new({
-            let mut tick: Tick = 0;
-            move || advance_tick(&mut tick)
-        }));
-        let current_tick = tick_source;
+        let current_tick = tick_source(circuit);
 
-        let highest_pair = highest_block_pair(&blocks);
-        let highest = highest_pair.map(|(hb, _)| *hb);
-        let floor_height = floor_height_stream(&highest_pair, &slopes);
+        let highest = highest_block_pair(&blocks).map(|(hb, _)| *hb);
+        let platform_floor = platform_floor_stream(&platform_blocks);
+        let floor_height = layered_floor_height_stream(&blocks, &slopes, &platform_floor);
+        let positions = platform_rider_stream(&entity_positions, &platform_blocks);
 
         let pos_floor = position_floor_stream(&positions, &floor_height);
 
-        let unsupported = pos_floor.filter(|pf| !within_grace(pf));
-        let standing = pos_floor.filter(within_grace);
+        let partition = FloorPartition::new(&positions, &pos_floor, &floor_height, &impulses);
 
-        let unsupported_positions = unsupported.map(|pf| pf.position);
-        let all_new_vel = new_velocity_stream(&velocities, &forces);
-        let unsupported_velocities = all_new_vel.map_index(|v| (v.entity, *v)).join(
-            &unsupported.map_index(|pf| (pf.position.entity, ())),
-            |_, vel, ()| *vel,
-        );
-        let new_pos_unsupported =
-            new_position_stream(&unsupported_positions, &unsupported_velocities);
+        let kicked = impulse_velocity_stream(&velocities, &impulses);
+        let all_new_vel = new_velocity_stream(&kicked, &forces, &config);
+        let unsupported_velocities = partition.falling_velocities(&all_new_vel, &drags, &config);
+        let fall = partition.fall(&unsupported_velocities, &floor_height, &config);
 
         let (new_pos_standing, new_vel_standing) =
-            standing_motion_stream(&standing, &floor_height, &all_new_vel);
+            standing_motion_stream(&partition.standing, &floor_height, &all_new_vel, &config);
 
-        let fall_damage = fall_damage_stream(
-            &standing,
-            &unsupported,
+        let damage_with_fall = damage_events.plus(&partition.damage(
             &unsupported_velocities,
+            &fall.landings,
             &current_tick,
-        );
-        let damage_with_fall = damage_events.plus(&fall_damage);
+            &config,
+        ));
 
-        let base_pos = new_pos_unsupported.plus(&new_pos_standing);
-        let new_vel = unsupported_velocities.plus(&new_vel_standing);
+        let base_pos = fall.positions.plus(&new_pos_standing);
+        let new_vel = fall.velocities.plus(&new_vel_standing);
 
-        let fear = fear_level_stream(&positions, &fears);
-        let (decisions, movement_aggregations) =
-            movement_decision_streams(&fear, &targets, &positions);
+        let steered = steering.decisions(&positions, &floor_height, &config);
 
-        let moved_pos = apply_movement(&base_pos, &decisions);
+        let steps = movement_steps(&base_pos, &steered.decisions, &config);
+        let separated = apply_separation(&steps, &separation_stream(&positions, &extents));
+        let moved_pos = wall_collision_stream(&separated, &floor_height, &config).map(|(p, _)| *p);
 
         let health_deltas = health_delta_stream(&health_states, &damage_with_fall);
 
@@ -251,76 +709,123 @@ impl DbspCircuit {
             position_in,
             velocity_in,
             force_in,
-            fear_in,
-            target_in,
+            impulse_in,
+            extent_in,
+            drag_in,
+            fear_in: steering_handles.fears,
+            civvy_in: steering_handles.civvies,
+            baddie_in: steering_handles.baddies,
+            target_in: steering_handles.targets,
+            path_goal_in: steering_handles.path_goals,
             health_state_in,
             damage_in,
             block_in,
             block_slope_in,
+            platform_block_in,
             player_spawn_in,
             spawn_point_in,
+            physics_config_in,
             new_position_out: moved_pos.output(),
             new_velocity_out: new_vel.output(),
             highest_block_out: highest.output(),
             floor_height_out: floor_height.output(),
             position_floor_out: pos_floor.output(),
             health_delta_out: health_deltas.output(),
-            movement_aggregation_out: movement_aggregations.output(),
+            movement_aggregation_out: steered.aggregations.output(),
+            arrived_out: steered.arrivals.output(),
         })
     }
 
     /// Returns a reference to the input handle for feeding position records into the circuit.
-    pub const fn position_in(&self) -> &ZSetHandle<Position> {
+    pub const fn position_in(&self) -> &StagedZSet<Position> {
         &self.position_in
     }
 
     /// Returns a reference to the input handle for feeding velocity records into the circuit.
-    pub const fn velocity_in(&self) -> &ZSetHandle<Velocity> {
+    pub const fn velocity_in(&self) -> &StagedZSet<Velocity> {
         &self.velocity_in
     }
 
     /// Returns a reference to the input handle for feeding force records into the circuit.
-    pub const fn force_in(&self) -> &ZSetHandle<Force> {
+    pub const fn force_in(&self) -> &StagedZSet<Force> {
         &self.force_in
     }
 
+    /// Returns a reference to the input handle for one-tick velocity impulses.
+    pub const fn impulse_in(&self) -> &StagedZSet<Impulse> {
+        &self.impulse_in
+    }
+
+    /// Returns a reference to the input handle for entity collision extents.
+    pub const fn extent_in(&self) -> &StagedZSet<Extent> {
+        &self.extent_in
+    }
+
+    /// Returns a reference to the input handle for per-entity air drag.
+    pub const fn drag_in(&self) -> &StagedZSet<Drag> {
+        &self.drag_in
+    }
+
     /// Returns a reference to the input handle for entity fear levels.
-    pub const fn fear_in(&self) -> &ZSetHandle<FearLevel> {
+    pub const fn fear_in(&self) -> &StagedZSet<FearLevel> {
         &self.fear_in
     }
 
+    /// Returns a reference to the input handle for civilians whose fear is
+    /// derived from nearby baddies.
+    pub const fn civvy_in(&self) -> &StagedZSet<Civvy> {
+        &self.civvy_in
+    }
+
+    /// Returns a reference to the input handle for baddies that frighten
+    /// civilians.
+    pub const fn baddie_in(&self) -> &StagedZSet<Baddie> {
+        &self.baddie_in
+    }
+
     /// Returns a reference to the input handle for entity targets.
-    pub const fn target_in(&self) -> &ZSetHandle<Target> {
+    pub const fn target_in(&self) -> &StagedZSet<Target> {
         &self.target_in
     }
 
+    /// Returns a reference to the input handle for pathfinder waypoints, which
+    /// supersede an entity's target while present.
+    pub const fn path_goal_in(&self) -> &StagedZSet<PathGoal> {
+        &self.path_goal_in
+    }
+
     /// Returns a reference to the input handle for entity health snapshots.
-    pub const fn health_state_in(&self) -> &ZSetHandle<HealthState> {
+    pub const fn health_state_in(&self) -> &StagedZSet<HealthState> {
         &self.health_state_in
     }
 
     /// Returns a reference to the damage/healing input stream.
-    pub const fn damage_in(&self) -> &ZSetHandle<DamageEvent> {
+    pub const fn damage_in(&self) -> &StagedZSet<DamageEvent> {
         &self.damage_in
     }
 
     /// Returns a reference to the input handle for feeding block records into the circuit.
-    pub const fn block_in(&self) -> &ZSetHandle<Block> {
+    pub const fn block_in(&self) -> &StagedZSet<Block> {
         &self.block_in
     }
 
     /// Returns a reference to the input handle for feeding block slope records into the circuit.
-    pub const fn block_slope_in(&self) -> &ZSetHandle<BlockSlope> {
+    pub const fn block_slope_in(&self) -> &StagedZSet<BlockSlope> {
         &self.block_slope_in
     }
 
+    /// Returns a reference to the input handle for the blocks of moving platforms.
+    pub const fn platform_block_in(&self) -> &StagedZSet<PlatformBlock> {
+        &self.platform_block_in
+    }
+
     /// Returns a reference to the input handle for player spawn locations.
-    pub const fn player_spawn_in(&self) -> &ZSetHandle<PlayerSpawnLocation> {
+    pub const fn player_spawn_in(&self) -> &StagedZSet<PlayerSpawnLocation> {
         &self.player_spawn_in
     }
 
     /// Returns a reference to the input handle for NPC spawn points.
-    pub const fn spawn_point_in(&self) -> &ZSetHandle<SpawnPointRecord> {
+    pub const fn spawn_point_in(&self) -> &StagedZSet<SpawnPointRecord> {
         &self.spawn_point_in
     }
 
@@ -364,18 +869,55 @@ impl DbspCircuit {
         &self.movement_aggregation_out
     }
 
+    /// Returns a reference to the arrival diagnostic output.
+    ///
+    /// Carries an [`Arrived`] record for each entity the steering stage found
+    /// on its target this tick; the record is retracted once the entity leaves
+    /// or its target changes. The command layer logs arrivals and drains the
+    /// handle alongside the other outputs.
+    pub const fn arrived_out(&self) -> &OutputHandle<OrdZSet<Arrived>> {
+        &self.arrived_out
+    }
+
+    /// Every staged input, in declaration order.
+    fn inputs(&self) -> [&dyn StagedInput; 18] {
+        [
+            &self.position_in,
+            &self.velocity_in,
+            &self.force_in,
+            &self.impulse_in,
+            &self.extent_in,
+            &self.drag_in,
+            &self.fear_in,
+            &self.civvy_in,
+            &self.baddie_in,
+            &self.target_in,
+            &self.path_goal_in,
+            &self.health_state_in,
+            &self.damage_in,
+            &self.block_in,
+            &self.block_slope_in,
+            &self.platform_block_in,
+            &self.player_spawn_in,
+            &self.spawn_point_in,
+        ]
+    }
+
+    /// Hands every staged record to the circuit for the next step.
+    fn flush_inputs(&self) {
+        for input in self.inputs() {
+            input.flush();
+        }
+    }
+
     /// Clears all input collections to remove accumulated records.
+    ///
+    /// Records staged while a pipelined step computes are discarded, while
+    /// those already handed to that step are left to it.
     pub fn clear_inputs(&mut self) {
-        self.position_in.clear_input();
-        self.velocity_in.clear_input();
-        self.force_in.clear_input();
-        self.fear_in.clear_input();
-        self.target_in.clear_input();
-        self.health_state_in.clear_input();
-        self.damage_in.clear_input();
-        self.block_in.clear_input();
-        self.block_slope_in.clear_input();
-        self.player_spawn_in.clear_input();
-        self.spawn_point_in.clear_input();
+        let flushed = !self.is_stepping();
+        for input in self.inputs() {
+            input.clear(flushed);
+        }
     }
 }
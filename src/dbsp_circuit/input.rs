@@ -0,0 +1,78 @@
+//! Staged circuit inputs.
+//!
+//! Records pushed into a [`StagedZSet`] wait in a buffer until
+//! [`DbspCircuit::start_step`](super::DbspCircuit::start_step) hands them to
+//! the circuit. A pipelined circuit steps on its own thread, so records pushed
+//! while a step runs must not leak into that step; staging keeps every push
+//! out of the circuit until the next step begins.
+
+use std::cell::RefCell;
+use std::mem;
+
+use dbsp::utils::Tup2;
+use dbsp::{DBData, ZSetHandle, ZWeight};
+
+/// Input handle buffering weighted records until the next step.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use lille::dbsp_circuit::{DbspCircuit, Position};
+///
+/// let mut circuit = DbspCircuit::new().expect("circuit construction failed");
+/// circuit.position_in().push(
+///     Position { entity: 1, x: 0.0.into(), y: 0.0.into(), z: 1.0.into() },
+///     1,
+/// );
+/// circuit.step().expect("circuit evaluation failed");
+/// ```
+pub struct StagedZSet<K> {
+    handle: ZSetHandle<K>,
+    staged: RefCell<Vec<Tup2<K, ZWeight>>>,
+}
+
+impl<K: DBData> StagedZSet<K> {
+    pub(super) const fn new(handle: ZSetHandle<K>) -> Self {
+        Self {
+            handle,
+            staged: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Stages `record` with `weight` for the next step.
+    pub fn push(&self, record: K, weight: ZWeight) {
+        self.staged.borrow_mut().push(Tup2(record, weight));
+    }
+
+    /// Discards the records staged since the last step.
+    pub fn clear_input(&self) {
+        self.staged.borrow_mut().clear();
+    }
+}
+
+/// Staged input of any record type, so the circuit can flush and clear all
+/// of its inputs alike.
+pub(super) trait StagedInput {
+    /// Hands the staged records to the circuit input.
+    fn flush(&self);
+
+    /// Discards the staged records and, when `flushed` is set, the records
+    /// already handed to the circuit but not yet consumed by a step.
+    fn clear(&self, flushed: bool);
+}
+
+impl<K: DBData> StagedInput for StagedZSet<K> {
+    fn flush(&self) {
+        let mut staged = mem::take(&mut *self.staged.borrow_mut());
+        if !staged.is_empty() {
+            self.handle.append(&mut staged);
+        }
+    }
+
+    fn clear(&self, flushed: bool) {
+        self.clear_input();
+        if flushed {
+            self.handle.clear_input();
+        }
+    }
+}
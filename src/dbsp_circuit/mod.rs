@@ -2,7 +2,7 @@
 //!
 //! This module defines [`DbspCircuit`], the authoritative dataflow program for
 //! Lille's game world. Callers feed [`Position`], [`Velocity`], [`Force`],
-//! [`Target`], [`FearLevel`], and [`Block`](crate::components::Block) records into the circuit. Each tick
+//! [`Target`], [`PathGoal`], [`FearLevel`], [`Civvy`], [`Baddie`], and [`Block`](crate::components::Block) records into the circuit. Each tick
 //! [`DbspCircuit::step`] derives movement decisions that yield updated
 //! [`NewPosition`] and [`NewVelocity`] outputs alongside terrain queries like
 //! [`HighestBlockAt`]. Input collections persist across steps—invoke
@@ -11,22 +11,30 @@
 
 mod circuit;
 mod helpers;
+mod input;
+mod pipeline;
 mod step;
 pub mod streams;
 pub mod types;
 
 pub use circuit::DbspCircuit;
+pub use input::StagedZSet;
 pub use step::{step, step_named, try_step};
 pub use streams::{
-    apply_movement, fall_damage_stream, fear_level_stream, floor_height_stream,
-    health_delta_stream, highest_block_pair, movement_decision_stream, movement_decision_streams,
-    new_position_stream, new_velocity_stream, position_floor_stream, standing_motion_stream,
-    PositionFloor,
+    air_drag_stream, apply_movement, apply_separation, fall_damage_stream, fear_level_stream,
+    fleeing_goal_stream, floor_height_stream, health_delta_stream, highest_block_pair,
+    impulse_velocity_stream, kill_plane_damage_stream, layered_floor_height_stream,
+    movement_decision_stream, movement_decision_streams, movement_steps, new_position_stream,
+    new_velocity_stream, platform_floor_stream, platform_rider_stream, position_floor_stream,
+    separation_stream, standing_motion_stream, steering_goal_stream, steering_streams,
+    swept_fall_stream, threat_fear_stream, void_position_stream, wall_collision_stream,
+    PositionFloor, SteeringContext, SweptFall,
 };
 pub use types::{
-    DamageEvent, DamageSource, EntityId, FearLevel, FloorHeightAt, Force, HealthDelta, HealthState,
-    HighestBlockAt, MovementAggregation, MovementDecision, NewPosition, NewVelocity,
-    PlayerSpawnLocation, Position, SpawnPointRecord, Target, Tick, Velocity,
+    Arrived, Baddie, Civvy, DamageEvent, DamageSource, Drag, EntityId, Extent, FearLevel,
+    FloorHeightAt, Force, HealthDelta, HealthState, HighestBlockAt, Impulse, MovementAggregation,
+    MovementDecision, NewPosition, NewVelocity, PathGoal, PlatformBlock, PlayerSpawnLocation,
+    Position, Separation, SpawnPointRecord, Target, ThreatFear, Tick, Velocity,
 };
 
 #[cfg(test)]
@@ -0,0 +1,86 @@
+//! Dedicated thread stepping a pipelined circuit.
+//!
+//! The thread owns the DBSP runtime handle. Each step is requested over one
+//! channel and its outcome reported over another, so the caller keeps working
+//! while the circuit computes and collects the outcome when it is ready.
+
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread::{self, JoinHandle};
+
+use anyhow::Error as AnyError;
+use dbsp::{DBSPHandle, RuntimeError};
+use log::error;
+
+/// Step thread of a pipelined circuit.
+pub(super) struct StepThread {
+    requests: Option<Sender<()>>,
+    results: Receiver<Result<(), dbsp::Error>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl StepThread {
+    /// Moves `handle` onto a new thread that steps it once per request.
+    pub(super) fn spawn(handle: DBSPHandle) -> Result<Self, dbsp::Error> {
+        let (requests, pending) = mpsc::channel::<()>();
+        let (reply, results) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("dbsp-step".into())
+            .spawn(move || serve(handle, &pending, &reply))
+            .map_err(|error| dbsp::Error::Constructor(AnyError::from(error)))?;
+        Ok(Self {
+            requests: Some(requests),
+            results,
+            thread: Some(thread),
+        })
+    }
+
+    /// Asks the thread to step the circuit.
+    pub(super) fn request(&self) -> Result<(), dbsp::Error> {
+        self.requests
+            .as_ref()
+            .ok_or_else(terminated)?
+            .send(())
+            .map_err(|_| terminated())
+    }
+
+    /// Returns the outcome of the requested step once it has finished.
+    pub(super) fn try_result(&self) -> Option<Result<(), dbsp::Error>> {
+        match self.results.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(terminated())),
+        }
+    }
+
+    /// Blocks until the requested step has finished.
+    pub(super) fn wait(&self) -> Result<(), dbsp::Error> {
+        self.results.recv().unwrap_or_else(|_| Err(terminated()))
+    }
+}
+
+impl Drop for StepThread {
+    fn drop(&mut self) {
+        // Closing the request channel ends the loop, and joining lets the
+        // runtime shut down before the circuit handles go away.
+        self.requests.take();
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                error!("dbsp step thread panicked");
+            }
+        }
+    }
+}
+
+/// Steps `handle` once per request until the requests or the replies close.
+fn serve(mut handle: DBSPHandle, pending: &Receiver<()>, reply: &Sender<Result<(), dbsp::Error>>) {
+    for () in pending {
+        if reply.send(handle.step()).is_err() {
+            break;
+        }
+    }
+}
+
+/// Error reported once the step thread has stopped.
+const fn terminated() -> dbsp::Error {
+    dbsp::Error::Runtime(RuntimeError::Terminated)
+}
@@ -1,27 +1,33 @@
 //! Application of movement decisions to base positions.
 //!
 //! Joins movement decisions with base positions to produce moved positions,
-//! passing unmoved entities through unchanged.
+//! passing unmoved entities through unchanged. Decisions are speeds, so the
+//! join scales them by the tick length into the per-tick displacements that
+//! wall collision resolves against terrain.
 
 use dbsp::{typed_batch::OrdZSet, RootCircuit, Stream};
 use log::warn;
 use ordered_float::OrderedFloat;
 
-use crate::dbsp_circuit::{MovementDecision, Position};
+use crate::dbsp_circuit::streams::config::with_physics_config;
+use crate::dbsp_circuit::{MovementDecision, Position, Velocity};
+use crate::PhysicsConfig;
 
 /// Applies movement decisions to base positions.
 ///
-/// Expects the movement decisions to already be deduplicated per entity (the
+/// Each entity moves by its decision scaled by the
+/// [`PhysicsConfig::delta_time`] in force on the tick. Expects the movement decisions to already be deduplicated per entity (the
 /// decision stream folds duplicates before this stage). Panics in debug builds
 /// if more than one movement record still exists for the same entity in a tick.
 ///
 /// # Examples
 /// ```rust,no_run
 /// # use anyhow::Result;
-/// # use dbsp::RootCircuit;
+/// # use dbsp::{operator::Generator, Circuit, RootCircuit};
 /// # use ordered_float::OrderedFloat;
 /// # use lille::dbsp_circuit::{MovementDecision, Position};
 /// # use lille::dbsp_circuit::apply_movement;
+/// # use lille::PhysicsConfig;
 /// # fn main() -> Result<()> {
 /// let (mut circuit, (base_in, movement_in, mut moved_out)) =
 ///     RootCircuit::build(|circuit| {
@@ -29,8 +35,9 @@ use crate::dbsp_circuit::{MovementDecision, Position};
 ///             circuit.add_input_zset::<Position>();
 ///         let (movement_stream, movement_handle) =
 ///             circuit.add_input_zset::<MovementDecision>();
+///         let config = circuit.add_source(Generator::new(PhysicsConfig::default));
 ///         let output =
-///             apply_movement(&base_stream, &movement_stream).output();
+///             apply_movement(&base_stream, &movement_stream, &config).output();
 ///         Ok((base_handle, movement_handle, output))
 ///     })?;
 ///
@@ -69,12 +76,41 @@ use crate::dbsp_circuit::{MovementDecision, Position};
 pub fn apply_movement(
     base: &Stream<RootCircuit, OrdZSet<Position>>,
     movement: &Stream<RootCircuit, OrdZSet<MovementDecision>>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
 ) -> Stream<RootCircuit, OrdZSet<Position>> {
+    movement_steps(base, movement, config).map(|(p, step)| Position {
+        entity: p.entity,
+        x: OrderedFloat(p.x.into_inner() + step.vx.into_inner()),
+        y: OrderedFloat(p.y.into_inner() + step.vy.into_inner()),
+        z: p.z,
+    })
+}
+
+/// Pairs each base position with the displacement its movement decision
+/// requests this tick.
+///
+/// The decision is a speed in blocks per second, so the displacement covers
+/// [`PhysicsConfig::delta_time`] seconds of it and walking speed does not
+/// depend on the tick rate. Entities without a decision receive a zero
+/// displacement. The pairs feed
+/// [`wall_collision_stream`](crate::dbsp_circuit::wall_collision_stream), which
+/// resolves the displacement against terrain before it is applied. The same
+/// deduplication expectations as [`apply_movement`] hold.
+#[must_use]
+pub fn movement_steps(
+    base: &Stream<RootCircuit, OrdZSet<Position>>,
+    movement: &Stream<RootCircuit, OrdZSet<MovementDecision>>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
+) -> Stream<RootCircuit, OrdZSet<(Position, Velocity)>> {
     let base_idx = base.map_index(|p| (p.entity, *p));
     // The decision stream already folds duplicate decisions per entity, so index
     // the movements directly rather than aggregating a second time here. The
     // inspect below still flags any duplicate that slips through as a bug.
-    let mv_base = movement.map_index(|m| (m.entity, (m.dx, m.dy)));
+    let mv_base = with_physics_config(movement, config).map_index(|(m, params)| {
+        let displace =
+            |speed: OrderedFloat<f64>| OrderedFloat(params.displacement(speed.into_inner()));
+        (m.entity, (displace(m.dx), displace(m.dy)))
+    });
 
     let mv = mv_base.inspect(|batch| {
         // Accumulate counts per entity to catch duplicates reaching the join.
@@ -97,14 +133,18 @@ pub fn apply_movement(
         }
     });
 
-    let moved = base_idx.join(&mv, |_, p, &(dx, dy)| Position {
-        entity: p.entity,
-        x: OrderedFloat(p.x.into_inner() + dx.into_inner()),
-        y: OrderedFloat(p.y.into_inner() + dy.into_inner()),
-        z: p.z,
-    });
+    let step = |entity, dx, dy| Velocity {
+        entity,
+        vx: dx,
+        vy: dy,
+        vz: OrderedFloat(0.0),
+    };
+    let moved = base_idx.join(&mv, move |_, p, &(dx, dy)| (*p, step(p.entity, dx, dy)));
     let mv_entities = mv.map(|(e, _)| *e).map_index(|e| (*e, ()));
-    let unmoved = base_idx.antijoin(&mv_entities).map(|(_, p)| *p);
+    let unmoved = base_idx.antijoin(&mv_entities).map(move |(_, p)| {
+        let zero = OrderedFloat(0.0);
+        (*p, step(p.entity, zero, zero))
+    });
     unmoved.plus(&moved)
 }
 
@@ -117,8 +157,9 @@ mod tests {
 
     use super::apply_movement;
     use crate::dbsp_circuit::{MovementDecision, Position};
+    use crate::PhysicsConfig;
     use approx::relative_eq;
-    use dbsp::RootCircuit;
+    use dbsp::{operator::Generator, Circuit, RootCircuit};
     use ordered_float::OrderedFloat;
     use rstest::rstest;
 
@@ -131,11 +172,13 @@ mod tests {
         ),
     );
 
-    fn build_apply_circuit() -> Result<ApplyCircuit, dbsp::Error> {
-        RootCircuit::build(|circuit| {
+    fn build_apply_circuit(config: PhysicsConfig) -> Result<ApplyCircuit, dbsp::Error> {
+        RootCircuit::build(move |circuit| {
             let (base_stream, base_handle) = circuit.add_input_zset::<Position>();
             let (movement_stream, movement_handle) = circuit.add_input_zset::<MovementDecision>();
-            let output_handle = apply_movement(&base_stream, &movement_stream).output();
+            let config_source = circuit.add_source(Generator::new(move || config));
+            let output_handle =
+                apply_movement(&base_stream, &movement_stream, &config_source).output();
             Ok((base_handle, movement_handle, output_handle))
         })
     }
@@ -189,7 +232,7 @@ mod tests {
         #[case] expected: Position,
     ) {
         let (circuit, (base_in, movement_in, out)) =
-            build_apply_circuit().expect("failed to build apply circuit");
+            build_apply_circuit(PhysicsConfig::default()).expect("failed to build apply circuit");
         base_in.push(base, 1);
         if let Some(decision) = movement {
             movement_in.push(decision, 1);
@@ -219,4 +262,29 @@ mod tests {
             actual.z.into_inner()
         );
     }
+
+    // Decisions are speeds, so halving the tick length halves the distance an
+    // entity covers in one tick.
+    #[rstest]
+    #[case::full_tick(1.0, 1.0)]
+    #[case::half_tick(0.5, 0.5)]
+    fn movement_scales_with_delta_time(#[case] delta_time: f64, #[case] expected_x: f64) {
+        let config = PhysicsConfig {
+            delta_time: OrderedFloat(delta_time),
+            ..PhysicsConfig::default()
+        };
+        let (circuit, (base_in, movement_in, out)) =
+            build_apply_circuit(config).expect("failed to build apply circuit");
+        base_in.push(position_at(1, 0.0, 0.0, 1.0), 1);
+        movement_in.push(movement(1, 1.0, 0.0), 1);
+
+        circuit.step().expect("dbsp step");
+
+        let (actual, _) = single_position(&collect_positions(&out));
+        assert!(
+            relative_eq!(actual.x.into_inner(), expected_x),
+            "x (expected {expected_x}, got {})",
+            actual.x.into_inner()
+        );
+    }
 }
@@ -9,8 +9,9 @@ use ordered_float::OrderedFloat;
 use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 use size_of::SizeOf;
 
-use crate::FEAR_THRESHOLD;
+use crate::PhysicsConfig;
 
+use crate::dbsp_circuit::streams::config::with_physics_config;
 use crate::dbsp_circuit::{FearLevel, MovementAggregation, MovementDecision, Position, Target};
 
 #[derive(
@@ -37,8 +38,8 @@ pub(super) struct PositionTarget {
 }
 
 #[inline]
-fn should_flee(level: OrderedFloat<f64>) -> bool {
-    level.into_inner() > FEAR_THRESHOLD
+fn should_flee(level: OrderedFloat<f64>, threshold: OrderedFloat<f64>) -> bool {
+    level > threshold
 }
 
 /// Threshold below which displacement is treated as zero when normalizing.
@@ -46,14 +47,23 @@ fn should_flee(level: OrderedFloat<f64>) -> bool {
 /// The value `1e-12` avoids division by near-zero magnitudes. It suppresses
 /// floating-point noise while remaining negligible for typical movement
 /// distances.
-const MIN_DIRECTION_MAGNITUDE: f64 = 1e-12;
+pub(super) const MIN_DIRECTION_MAGNITUDE: f64 = 1e-12;
 
-pub(super) fn decide_movement(level: OrderedFloat<f64>, pt: &PositionTarget) -> MovementDecision {
+pub(super) fn decide_movement(
+    level: OrderedFloat<f64>,
+    threshold: OrderedFloat<f64>,
+    pt: &PositionTarget,
+) -> MovementDecision {
     let displacement = DVec2::new(
         pt.tx.into_inner() - pt.px.into_inner(),
         pt.ty.into_inner() - pt.py.into_inner(),
     );
-    let scaled = displacement * if should_flee(level) { -1.0 } else { 1.0 };
+    let scaled = displacement
+        * if should_flee(level, threshold) {
+            -1.0
+        } else {
+            1.0
+        };
     let magnitude = scaled.length();
     let direction = if magnitude > MIN_DIRECTION_MAGNITUDE {
         scaled / magnitude
@@ -70,8 +80,9 @@ pub(super) fn decide_movement(level: OrderedFloat<f64>, pt: &PositionTarget) ->
 
 /// Converts fear levels and targets into simple movement decisions.
 ///
-/// Entities with a target move one unit towards it when their fear is below
-/// [`FEAR_THRESHOLD`]; otherwise, they flee one unit away. Vectors are
+/// Entities with a target move one unit towards it when their fear is at or
+/// below the [`PhysicsConfig::fear_threshold`] in force on the tick;
+/// otherwise, they flee one unit away. Vectors are
 /// normalized to ensure consistent speed in all directions.
 ///
 /// # Examples
@@ -79,7 +90,9 @@ pub(super) fn decide_movement(level: OrderedFloat<f64>, pt: &PositionTarget) ->
 /// # use anyhow::Result;
 /// # use dbsp::RootCircuit;
 /// # use ordered_float::OrderedFloat;
+/// # use dbsp::{operator::Generator, Circuit};
 /// # use lille::dbsp_circuit::{FearLevel, MovementDecision, Position, Target};
+/// # use lille::PhysicsConfig;
 /// # use lille::dbsp_circuit::{fear_level_stream, movement_decision_stream};
 /// # use std::f64::consts::FRAC_1_SQRT_2;
 /// # fn main() -> Result<()> {
@@ -92,11 +105,13 @@ pub(super) fn decide_movement(level: OrderedFloat<f64>, pt: &PositionTarget) ->
 ///         let (position_stream, position_handle) =
 ///             circuit.add_input_zset::<Position>();
 ///
+///         let config = circuit.add_source(Generator::new(PhysicsConfig::default));
 ///         let fear = fear_level_stream(&position_stream, &fear_stream);
 ///         let output = movement_decision_stream(
 ///             &fear,
 ///             &target_stream,
 ///             &position_stream,
+///             &config,
 ///         )
 ///         .output();
 ///         Ok((fear_handle, target_handle, position_handle, output))
@@ -146,8 +161,9 @@ pub fn movement_decision_stream(
     fear: &Stream<RootCircuit, OrdZSet<FearLevel>>,
     targets: &Stream<RootCircuit, OrdZSet<Target>>,
     positions: &Stream<RootCircuit, OrdZSet<Position>>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
 ) -> Stream<RootCircuit, OrdZSet<MovementDecision>> {
-    movement_decision_streams(fear, targets, positions).0
+    movement_decision_streams(fear, targets, positions, config).0
 }
 
 /// As [`movement_decision_stream`], but also returning the aggregation
@@ -164,6 +180,7 @@ pub fn movement_decision_streams(
     fear: &Stream<RootCircuit, OrdZSet<FearLevel>>,
     targets: &Stream<RootCircuit, OrdZSet<Target>>,
     positions: &Stream<RootCircuit, OrdZSet<Position>>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
 ) -> (
     Stream<RootCircuit, OrdZSet<MovementDecision>>,
     Stream<RootCircuit, OrdZSet<MovementAggregation>>,
@@ -181,11 +198,11 @@ pub fn movement_decision_streams(
         })
         .map_index(|pt| (pt.entity, pt.clone()));
 
-    let raw = fear
+    let afraid = fear
         .map_index(|f| (f.entity, f.level))
-        .join(&pos_target, |_entity, &level, pt| {
-            decide_movement(level, pt)
-        });
+        .join(&pos_target, |_entity, &level, pt| (level, pt.clone()));
+    let raw = with_physics_config(&afraid, config)
+        .map(|((level, pt), params)| decide_movement(*level, params.fear_threshold, pt));
     dedupe_movement_decisions(&raw)
 }
 
@@ -1,12 +1,237 @@
 //! Fear-level derivation streams.
 //!
-//! Merges explicit fear inputs with entity positions so every positioned
-//! entity carries a fear level, defaulting to zero when none is supplied.
+//! Derives each civilian's fear from the baddies around it, and merges fear
+//! levels with entity positions so every positioned entity carries a fear
+//! level, defaulting to zero when none is supplied.
 
-use dbsp::{typed_batch::OrdZSet, RootCircuit, Stream};
+use dbsp::{algebra::Semigroup, operator::Fold, typed_batch::OrdZSet, RootCircuit, Stream};
 use ordered_float::OrderedFloat;
+use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
+use size_of::SizeOf;
 
-use crate::dbsp_circuit::{FearLevel, Position};
+use crate::dbsp_circuit::{Baddie, Civvy, FearLevel, Position, ThreatFear};
+use crate::numeric::floor_to_i32;
+use crate::{FEAR_DISTANCE_EPSILON, FEAR_RADIUS_MULTIPLIER};
+
+/// Cell of the fear grid holding the point `(x, y)`.
+///
+/// Cells are [`FEAR_RADIUS_MULTIPLIER`] blocks wide, so a civilian with a
+/// fraidiness of at most one notices baddies in at most a 3x3 block of them.
+fn fear_cell(x: OrderedFloat<f64>, y: OrderedFloat<f64>) -> (i32, i32) {
+    (
+        floor_to_i32(x / FEAR_RADIUS_MULTIPLIER),
+        floor_to_i32(y / FEAR_RADIUS_MULTIPLIER),
+    )
+}
+
+/// A civilian's position paired with its fraidiness.
+type Watcher = (Position, OrderedFloat<f64>);
+
+/// Fear-grid cells covered by the square around `civvy` that bounds its fear
+/// radius, each paired with the civilian.
+///
+/// A civilian that can notice nothing covers no cells.
+fn watched_cells(civvy: Position, fraidiness: OrderedFloat<f64>) -> Vec<((i32, i32), Watcher)> {
+    let radius = FEAR_RADIUS_MULTIPLIER * fraidiness.into_inner();
+    if !(radius.is_finite() && radius > 0.0) {
+        return Vec::new();
+    }
+    let (x0, y0) = fear_cell(civvy.x - radius, civvy.y - radius);
+    let (x1, y1) = fear_cell(civvy.x + radius, civvy.y + radius);
+    (x0..=x1)
+        .flat_map(|cx| (y0..=y1).map(move |cy| ((cx, cy), (civvy, fraidiness))))
+        .collect()
+}
+
+/// Fear a baddie with `meanness` at `threat` causes a civilian with
+/// `fraidiness` at `civvy`, as described on [`threat_fear_stream`].
+pub(super) fn threat_level(
+    civvy: &Position,
+    fraidiness: f64,
+    threat: &Position,
+    meanness: f64,
+) -> f64 {
+    let radius = FEAR_RADIUS_MULTIPLIER * fraidiness;
+    if !(radius.is_finite() && radius > 0.0 && meanness.is_finite() && meanness > 0.0) {
+        return 0.0;
+    }
+    let dx = threat.x.into_inner() - civvy.x.into_inner();
+    let dy = threat.y.into_inner() - civvy.y.into_inner();
+    let distance_sq = dx.mul_add(dx, dy * dy);
+    if distance_sq >= radius * radius {
+        return 0.0;
+    }
+    fraidiness * meanness / (distance_sq + FEAR_DISTANCE_EPSILON)
+}
+
+/// Derives the fear of each [`Civvy`] from the [`Baddie`]s near it.
+///
+/// A civilian notices baddies within [`FEAR_RADIUS_MULTIPLIER`] times its
+/// fraidiness, measured in the horizontal plane. Each baddie in range causes
+/// fear equal to the product of fraidiness and meanness divided by the squared
+/// distance between them, with [`FEAR_DISTANCE_EPSILON`] added to keep it
+/// finite when the two coincide. The fear from every baddie in range is summed
+/// and clamped to one, and the baddies' positions are averaged, weighted by
+/// the fear each causes, into the point the civilian flees from. Non-positive
+/// or non-finite fraidiness and meanness cause no fear.
+///
+/// Baddies are bucketed into a grid of [`FEAR_RADIUS_MULTIPLIER`]-wide cells
+/// and each civilian is joined only with the cells its fear radius reaches,
+/// so distant pairs are never compared.
+///
+/// Civilians with an explicit [`FearLevel`] in `fears` are skipped so the
+/// explicit level wins, as are civilians with no baddie in range;
+/// [`fear_level_stream`] gives those a level of zero.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use anyhow::Result;
+/// # use dbsp::RootCircuit;
+/// # use lille::dbsp_circuit::{threat_fear_stream, Baddie, Civvy, FearLevel, Position};
+/// # fn main() -> Result<()> {
+/// let (mut circuit, (pos_in, civvy_in, baddie_in, out)) = RootCircuit::build(|circuit| {
+///     let (positions, pos_in) = circuit.add_input_zset::<Position>();
+///     let (civvies, civvy_in) = circuit.add_input_zset::<Civvy>();
+///     let (baddies, baddie_in) = circuit.add_input_zset::<Baddie>();
+///     let (fears, _fear_in) = circuit.add_input_zset::<FearLevel>();
+///     let out = threat_fear_stream(&positions, &civvies, &baddies, &fears).output();
+///     Ok((pos_in, civvy_in, baddie_in, out))
+/// })?;
+///
+/// for (entity, x) in [(1, 0.0), (2, 1.0)] {
+///     pos_in.push(Position { entity, x: x.into(), y: 0.0.into(), z: 0.0.into() }, 1);
+/// }
+/// civvy_in.push(Civvy { entity: 1, fraidiness: 0.8.into() }, 1);
+/// baddie_in.push(Baddie { entity: 2, meanness: 0.5.into() }, 1);
+/// circuit.step()?;
+///
+/// let threats: Vec<_> = out.consolidate().iter().map(|(threat, (), _)| threat).collect();
+/// assert_eq!(threats.len(), 1);
+/// assert!(threats[0].level.into_inner() > 0.2);
+/// assert_eq!(threats[0].x.into_inner(), 1.0);
+/// # Ok(())
+/// # }
+/// ```
+#[must_use]
+pub fn threat_fear_stream(
+    positions: &Stream<RootCircuit, OrdZSet<Position>>,
+    civvies: &Stream<RootCircuit, OrdZSet<Civvy>>,
+    baddies: &Stream<RootCircuit, OrdZSet<Baddie>>,
+    fears: &Stream<RootCircuit, OrdZSet<FearLevel>>,
+) -> Stream<RootCircuit, OrdZSet<ThreatFear>> {
+    let pos_idx = positions.map_index(|p| (p.entity, *p));
+    let frightened = civvies
+        .map_index(|c| (c.entity, c.fraidiness))
+        .antijoin(&fears.map_index(|f| (f.entity, ())))
+        .join(&pos_idx, |_, &fraidiness, p| (*p, fraidiness))
+        .flat_map(|&(civvy, fraidiness)| watched_cells(civvy, fraidiness))
+        .map_index(|(cell, pair)| (*cell, *pair));
+    let threats = baddies
+        .map_index(|b| (b.entity, b.meanness))
+        .join(&pos_idx, |_, &meanness, p| (*p, meanness))
+        .map_index(|&(p, meanness)| (fear_cell(p.x, p.y), (p, meanness)));
+
+    frightened
+        .join(
+            &threats,
+            |_cell, (civvy, fraidiness), (threat, meanness)| ThreatFear {
+                entity: civvy.entity,
+                level: OrderedFloat(threat_level(
+                    civvy,
+                    fraidiness.into_inner(),
+                    threat,
+                    meanness.into_inner(),
+                )),
+                x: threat.x,
+                y: threat.y,
+            },
+        )
+        .filter(|sighting| sighting.level.into_inner() > 0.0)
+        .map_index(|sighting| (sighting.entity, *sighting))
+        .aggregate(Fold::<
+            ThreatFear,
+            ThreatAccumulator,
+            ThreatAccumulatorSemigroup,
+            _,
+            _,
+        >::with_output(
+            ThreatAccumulator::default(),
+            |acc: &mut ThreatAccumulator, sighting: &ThreatFear, weight: i64| {
+                acc.apply(sighting, weight);
+            },
+            |acc: ThreatAccumulator| acc,
+        ))
+        .flat_map(|(entity, acc)| acc.to_threat(*entity))
+}
+
+/// Fear summed over the baddies a civilian can see, with their positions
+/// weighted by the fear each causes.
+#[derive(
+    Archive,
+    RkyvSerialize,
+    RkyvDeserialize,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    SizeOf,
+)]
+#[archive_attr(derive(Eq, PartialEq, Ord, PartialOrd, Hash))]
+struct ThreatAccumulator {
+    level: OrderedFloat<f64>,
+    sum_x: OrderedFloat<f64>,
+    sum_y: OrderedFloat<f64>,
+}
+
+impl ThreatAccumulator {
+    /// Folds one sighting in, counted `weight` times.
+    fn apply(&mut self, sighting: &ThreatFear, weight: i64) {
+        #[expect(
+            clippy::cast_precision_loss,
+            reason = "Weights count coincident sightings, which are far too few to lose precision"
+        )]
+        let scaled = sighting.level.into_inner() * weight as f64;
+        self.merge(&Self {
+            level: OrderedFloat(scaled),
+            sum_x: OrderedFloat(scaled * sighting.x.into_inner()),
+            sum_y: OrderedFloat(scaled * sighting.y.into_inner()),
+        });
+    }
+
+    /// Combines two partial accumulators.
+    fn merge(&mut self, other: &Self) {
+        self.level = OrderedFloat(self.level.into_inner() + other.level.into_inner());
+        self.sum_x = OrderedFloat(self.sum_x.into_inner() + other.sum_x.into_inner());
+        self.sum_y = OrderedFloat(self.sum_y.into_inner() + other.sum_y.into_inner());
+    }
+
+    /// The civilian's clamped fear and the centre of its threats, or `None`
+    /// when the sightings cancel out.
+    fn to_threat(&self, entity: i64) -> Option<ThreatFear> {
+        let level = self.level.into_inner();
+        (level > 0.0).then(|| ThreatFear {
+            entity,
+            level: OrderedFloat(level.min(1.0)),
+            x: OrderedFloat(self.sum_x.into_inner() / level),
+            y: OrderedFloat(self.sum_y.into_inner() / level),
+        })
+    }
+}
+
+#[derive(Clone)]
+struct ThreatAccumulatorSemigroup;
+
+impl Semigroup<ThreatAccumulator> for ThreatAccumulatorSemigroup {
+    fn combine(left: &ThreatAccumulator, right: &ThreatAccumulator) -> ThreatAccumulator {
+        let mut combined = left.clone();
+        combined.merge(right);
+        combined
+    }
+}
 
 /// Merges explicit fear inputs with entity positions, defaulting to zero.
 ///
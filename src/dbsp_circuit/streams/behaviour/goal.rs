@@ -0,0 +1,62 @@
+//! Steering goals combining raw targets, pathfinder waypoints and threats.
+
+use dbsp::{typed_batch::OrdZSet, RootCircuit, Stream};
+
+use crate::dbsp_circuit::streams::config::with_physics_config;
+use crate::dbsp_circuit::{PathGoal, Target, ThreatFear};
+use crate::PhysicsConfig;
+
+/// Selects the point each entity steers towards this tick.
+///
+/// An entity's [`PathGoal`] supersedes its raw [`Target`], so once the
+/// pathfinder has supplied a waypoint the entity heads for that rather than
+/// straight at its destination. Entities without a waypoint, such as those
+/// whose path is still being computed, keep steering at their target. The
+/// result is expressed as [`Target`] records so it feeds
+/// [`movement_decision_streams`](super::movement_decision_streams) unchanged.
+#[must_use]
+pub fn steering_goal_stream(
+    targets: &Stream<RootCircuit, OrdZSet<Target>>,
+    path_goals: &Stream<RootCircuit, OrdZSet<PathGoal>>,
+) -> Stream<RootCircuit, OrdZSet<Target>> {
+    let routed = path_goals.map_index(|g| (g.entity, ())).distinct();
+    let unrouted = targets
+        .map_index(|t| (t.entity, *t))
+        .antijoin(&routed)
+        .map(|(_, t)| *t);
+    path_goals
+        .map(|g| Target {
+            entity: g.entity,
+            x: g.x,
+            y: g.y,
+        })
+        .plus(&unrouted)
+}
+
+/// Points civilians frightened by nearby baddies in place of their goals.
+///
+/// A civilian whose [`ThreatFear`] exceeds the
+/// [`PhysicsConfig::fear_threshold`] in force on the tick steers at the
+/// centre of its threats instead of its goal. As its fear also exceeds the
+/// threshold, [`movement_decision_streams`](super::movement_decision_streams)
+/// turns that into flight away from the threats, so civilians flee baddies
+/// whether or not they were given a target. Other entities keep their goals.
+#[must_use]
+pub fn fleeing_goal_stream(
+    goals: &Stream<RootCircuit, OrdZSet<Target>>,
+    threats: &Stream<RootCircuit, OrdZSet<ThreatFear>>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
+) -> Stream<RootCircuit, OrdZSet<Target>> {
+    let fleeing = with_physics_config(threats, config)
+        .filter(|(threat, params)| threat.level > params.fear_threshold)
+        .map(|(threat, _)| Target {
+            entity: threat.entity,
+            x: threat.x,
+            y: threat.y,
+        });
+    let calm = goals
+        .map_index(|t| (t.entity, *t))
+        .antijoin(&fleeing.map_index(|t| (t.entity, ())))
+        .map(|(_, t)| *t);
+    fleeing.plus(&calm)
+}
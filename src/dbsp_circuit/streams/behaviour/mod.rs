@@ -1,14 +1,20 @@
 //! Behavioural streams deriving movement from fear and targets.
 //!
-//! These helpers merge fear levels with positions, transform targets into
-//! movement decisions and apply those decisions to base positions.
+//! These helpers derive fear from nearby threats, merge fear levels with
+//! positions, pick each entity's steering goal, transform goals into movement
+//! decisions, blend those decisions with arrival and obstacle avoidance, and
+//! apply them to base positions.
 
 mod apply;
 mod decide;
 mod fear;
+mod goal;
+mod steer;
 #[cfg(test)]
 mod tests;
 
-pub use apply::apply_movement;
+pub use apply::{apply_movement, movement_steps};
 pub use decide::{movement_decision_stream, movement_decision_streams};
-pub use fear::fear_level_stream;
+pub use fear::{fear_level_stream, threat_fear_stream};
+pub use goal::{fleeing_goal_stream, steering_goal_stream};
+pub use steer::{steering_streams, SteeringContext};
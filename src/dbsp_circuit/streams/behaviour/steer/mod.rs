@@ -0,0 +1,366 @@
+//! Steering stage blending seek and flee with arrival and obstacle
+//! avoidance.
+//!
+//! Seek and flee decisions are unit vectors. This stage weights them before
+//! they are applied: approaching entities slow down inside
+//! [`PhysicsConfig::arrival_radius`] and stop on their target, and a step
+//! heading into a wall turns away from it. Crowding is left to the physical
+//! separation applied after steering. The weighted terms are summed per
+//! entity and clamped to a unit vector, so the stage still emits one
+//! [`MovementDecision`] per steering entity.
+
+use dbsp::{operator::Min, typed_batch::OrdZSet, RootCircuit, Stream};
+use glam::DVec2;
+use ordered_float::OrderedFloat;
+
+use super::decide::MIN_DIRECTION_MAGNITUDE;
+use crate::numeric::floor_to_i32;
+use crate::{PhysicsConfig, ARRIVAL_TOLERANCE, BLOCK_CENTRE_OFFSET};
+
+use crate::dbsp_circuit::streams::config::with_physics_config;
+use crate::dbsp_circuit::streams::floor::supporting_floor;
+use crate::dbsp_circuit::streams::vector_sum::{sum_vectors, VectorSum};
+use crate::dbsp_circuit::{Arrived, FearLevel, FloorHeightAt, MovementDecision, Position, Target};
+
+/// Builds a decision moving `entity` by `vector`.
+const fn decision(entity: i64, vector: DVec2) -> MovementDecision {
+    MovementDecision {
+        entity,
+        dx: OrderedFloat(vector.x),
+        dy: OrderedFloat(vector.y),
+    }
+}
+
+/// The vector a decision moves its entity by.
+fn vector(decision: &MovementDecision) -> DVec2 {
+    DVec2::new(decision.dx.into_inner(), decision.dy.into_inner())
+}
+
+/// Grid cell the position lies in.
+fn cell(position: &Position) -> (i32, i32) {
+    (floor_to_i32(position.x), floor_to_i32(position.y))
+}
+
+crate::dbsp_copy_record! {
+    /// Distance from an entity to one of its goals.
+    ///
+    /// The distance comes first so the smallest record is the nearest goal.
+    struct GoalDistance {
+        /// Distance to the goal in block units.
+        distance: OrderedFloat<f64>,
+        /// Goal X coordinate.
+        x: OrderedFloat<f64>,
+        /// Goal Y coordinate.
+        y: OrderedFloat<f64>,
+    }
+}
+
+impl GoalDistance {
+    fn between(position: &Position, goal: &Target) -> Self {
+        let dx = goal.x.into_inner() - position.x.into_inner();
+        let dy = goal.y.into_inner() - position.y.into_inner();
+        Self {
+            distance: OrderedFloat(dx.hypot(dy)),
+            x: goal.x,
+            y: goal.y,
+        }
+    }
+}
+
+crate::dbsp_copy_record! {
+    /// Nearest goal of an entity approaching rather than fleeing it.
+    struct Approach {
+        /// Approaching entity.
+        entity: i64,
+        /// The entity's nearest goal.
+        goal: GoalDistance,
+        /// Distance within which the entity slows down.
+        arrival_radius: OrderedFloat<f64>,
+        /// Length of the tick the step covers, in seconds.
+        delta_time: OrderedFloat<f64>,
+    }
+}
+
+impl Approach {
+    fn has_arrived(&self) -> bool {
+        self.goal.distance.into_inner() <= ARRIVAL_TOLERANCE
+    }
+
+    /// Fraction of full speed the entity keeps towards its goal.
+    ///
+    /// Inside the arrival radius the speed shrinks with the distance, and it
+    /// never carries the entity past the goal within one tick. A radius that
+    /// is not positive disables the slow-down.
+    fn speed(&self) -> OrderedFloat<f64> {
+        if self.has_arrived() {
+            return OrderedFloat(0.0);
+        }
+        let distance = self.goal.distance.into_inner();
+        let radius = self.arrival_radius.into_inner();
+        let eased = if radius.is_nan() || radius <= 0.0 {
+            1.0
+        } else {
+            (distance / radius).min(1.0)
+        };
+        let dt = self.delta_time.into_inner();
+        OrderedFloat(if dt > 0.0 {
+            eased.min(distance / dt)
+        } else {
+            eased
+        })
+    }
+
+    fn arrived(&self) -> Option<Arrived> {
+        self.has_arrived().then_some(Arrived {
+            entity: self.entity,
+            x: self.goal.x,
+            y: self.goal.y,
+        })
+    }
+}
+
+/// Nearest goal of every entity whose fear does not exceed the threshold.
+///
+/// Fleeing entities keep their full speed, so they yield no record.
+fn approaches(
+    fear: &Stream<RootCircuit, OrdZSet<FearLevel>>,
+    goals: &Stream<RootCircuit, OrdZSet<Target>>,
+    positions: &Stream<RootCircuit, OrdZSet<Position>>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
+) -> Stream<RootCircuit, OrdZSet<Approach>> {
+    let calm = with_physics_config(fear, config)
+        .filter(|(f, params)| f.level <= params.fear_threshold)
+        .map_index(|(f, params)| (f.entity, (params.arrival_radius, params.delta_time)));
+    positions
+        .map_index(|p| (p.entity, *p))
+        .join(&goals.map_index(|t| (t.entity, *t)), |&entity, p, t| {
+            (entity, GoalDistance::between(p, t))
+        })
+        .map_index(|&(entity, goal)| (entity, goal))
+        .aggregate(Min)
+        .join(&calm, |&entity, &goal, &(arrival_radius, delta_time)| {
+            Approach {
+                entity,
+                goal,
+                arrival_radius,
+                delta_time,
+            }
+        })
+}
+
+/// Scales each seek decision by the speed its approach allows.
+fn arrival_steps(
+    seek: &Stream<RootCircuit, OrdZSet<MovementDecision>>,
+    approaches: &Stream<RootCircuit, OrdZSet<Approach>>,
+) -> Stream<RootCircuit, OrdZSet<MovementDecision>> {
+    seek.map_index(|d| (d.entity, *d))
+        .outer_join(
+            &approaches.map_index(|a| (a.entity, a.speed())),
+            |_, d, &speed| Some(decision(d.entity, vector(d) * speed.into_inner())),
+            |_, d| Some(*d),
+            |_, _| None,
+        )
+        .flat_map(|d| *d)
+}
+
+crate::dbsp_copy_record! {
+    /// A step probed for a wall in the cell it enters.
+    struct AvoidanceProbe {
+        /// Position the entity holds before stepping.
+        origin: Position,
+        /// Step the entity intends to take this tick.
+        step: MovementDecision,
+        /// Highest floor rise the entity can step onto.
+        max_step: OrderedFloat<f64>,
+        /// Weight of the turn away from a wall.
+        weight: OrderedFloat<f64>,
+    }
+}
+
+impl AvoidanceProbe {
+    fn point(&self) -> (OrderedFloat<f64>, OrderedFloat<f64>) {
+        (
+            OrderedFloat(self.origin.x.into_inner() + self.step.dx.into_inner()),
+            OrderedFloat(self.origin.y.into_inner() + self.step.dy.into_inner()),
+        )
+    }
+
+    fn cell(&self) -> (i32, i32) {
+        let (x, y) = self.point();
+        (floor_to_i32(x), floor_to_i32(y))
+    }
+
+    /// Returns `true` when the step ends in another cell, the only case in
+    /// which it can run into a wall.
+    fn leaves_cell(&self) -> bool {
+        self.cell() != cell(&self.origin)
+    }
+
+    /// Returns `true` when `floor` rises too far above the origin to step
+    /// onto, matching the wall rule of
+    /// [`wall_collision_stream`](crate::dbsp_circuit::wall_collision_stream).
+    fn is_wall(&self, floor: &FloorHeightAt) -> bool {
+        let (x, y) = self.point();
+        floor.sample(x, y).into_inner() > self.origin.z.into_inner() + self.max_step.into_inner()
+    }
+
+    /// Weighted turn away from the wall in the probed cell.
+    ///
+    /// The turn is the part of the offset from the wall's centre to the
+    /// entity that lies across the step, so a wall ahead and to one side
+    /// deflects the entity to the other. A wall dead ahead turns it to the
+    /// left of its step.
+    fn turn(&self) -> MovementDecision {
+        let direction = vector(&self.step).normalize_or_zero();
+        let (cx, cy) = self.cell();
+        let centre = DVec2::new(
+            f64::from(cx) + BLOCK_CENTRE_OFFSET,
+            f64::from(cy) + BLOCK_CENTRE_OFFSET,
+        );
+        let away = DVec2::new(self.origin.x.into_inner(), self.origin.y.into_inner()) - centre;
+        let across = away - direction * away.dot(direction);
+        let turn = if across.length() > MIN_DIRECTION_MAGNITUDE {
+            across.normalize()
+        } else {
+            direction.perp()
+        };
+        decision(self.origin.entity, turn * self.weight.into_inner())
+    }
+}
+
+/// Turns entities whose step would enter a wall aside.
+fn avoidance(
+    steps: &Stream<RootCircuit, OrdZSet<MovementDecision>>,
+    positions: &Stream<RootCircuit, OrdZSet<Position>>,
+    floor_height: &Stream<RootCircuit, OrdZSet<FloorHeightAt>>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
+) -> Stream<RootCircuit, OrdZSet<MovementDecision>> {
+    let stepping = positions
+        .map_index(|p| (p.entity, *p))
+        .join(&steps.map_index(|d| (d.entity, *d)), |_, p, d| (*p, *d));
+    let probes = with_physics_config(&stepping, config)
+        .map(|&((origin, decision), params)| AvoidanceProbe {
+            origin,
+            step: MovementDecision {
+                dx: OrderedFloat(params.displacement(decision.dx.into_inner())),
+                dy: OrderedFloat(params.displacement(decision.dy.into_inner())),
+                ..decision
+            },
+            max_step: params.max_step_height,
+            weight: params.avoidance_weight,
+        })
+        .filter(AvoidanceProbe::leaves_cell);
+    supporting_floor(&probes, floor_height, |p| (p.cell(), p.origin.z))
+        .flat_map(|(probe, floor)| floor.filter(|fh| probe.is_wall(fh)).map(|_| probe.turn()))
+}
+
+/// The blended decision of `entity`, clamped to a full step.
+fn clamped(entity: i64, sum: &VectorSum) -> MovementDecision {
+    let total = DVec2::new(sum.dx.into_inner(), sum.dy.into_inner());
+    decision(entity, total.clamp_length_max(1.0))
+}
+
+/// Streams the steering stage weighs seek and flee decisions against.
+#[derive(Clone, Copy)]
+pub struct SteeringContext<'a> {
+    /// Fear level of every positioned entity.
+    pub fear: &'a Stream<RootCircuit, OrdZSet<FearLevel>>,
+    /// Goals the decisions were taken towards.
+    pub goals: &'a Stream<RootCircuit, OrdZSet<Target>>,
+    /// Entity positions at the start of the tick.
+    pub positions: &'a Stream<RootCircuit, OrdZSet<Position>>,
+    /// Floor of every cell, probed for walls ahead.
+    pub floor_height: &'a Stream<RootCircuit, OrdZSet<FloorHeightAt>>,
+    /// Physics configuration in force on the tick.
+    pub config: &'a Stream<RootCircuit, PhysicsConfig>,
+}
+
+/// Blends seek and flee decisions with arrival and obstacle avoidance.
+///
+/// `seek` holds the deduplicated unit decisions of
+/// [`movement_decision_streams`](super::movement_decision_streams), and the
+/// `fear`, `goals` and `positions` of `context` are the streams they were
+/// decided from.
+/// The stage then:
+///
+/// - scales the decision of an entity approaching its nearest goal by
+///   `distance / arrival_radius`, capped at one and at the speed that reaches
+///   the goal within [`PhysicsConfig::delta_time`], and drops it once the entity
+///   stands within [`ARRIVAL_TOLERANCE`] of the goal, reporting an
+///   [`Arrived`] record instead;
+/// - adds a turn away from any wall the scaled step would enter, weighted by
+///   [`PhysicsConfig::avoidance_weight`].
+///
+/// The terms are summed and clamped to a unit vector, giving one weighted
+/// decision per entity in `seek`. Fleeing entities keep their full speed and
+/// never arrive.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use anyhow::Result;
+/// # use dbsp::{operator::Generator, Circuit, RootCircuit};
+/// # use lille::dbsp_circuit::{
+/// #     fear_level_stream, movement_decision_stream, steering_streams, FearLevel,
+/// #     FloorHeightAt, MovementDecision, Position, SteeringContext, Target,
+/// # };
+/// # use lille::PhysicsConfig;
+/// # fn main() -> Result<()> {
+/// let (mut circuit, (target_in, pos_in, mut decisions_out, mut arrived_out)) =
+///     RootCircuit::build(|circuit| {
+///         let (fears, _fear_in) = circuit.add_input_zset::<FearLevel>();
+///         let (targets, target_in) = circuit.add_input_zset::<Target>();
+///         let (positions, pos_in) = circuit.add_input_zset::<Position>();
+///         let (floors, _floor_in) = circuit.add_input_zset::<FloorHeightAt>();
+///         let config = circuit.add_source(Generator::new(PhysicsConfig::default));
+///         let fear = fear_level_stream(&positions, &fears);
+///         let seek = movement_decision_stream(&fear, &targets, &positions, &config);
+///         let context = SteeringContext {
+///             fear: &fear,
+///             goals: &targets,
+///             positions: &positions,
+///             floor_height: &floors,
+///             config: &config,
+///         };
+///         let (decisions, arrived) = steering_streams(&seek, context);
+///         Ok((target_in, pos_in, decisions.output(), arrived.output()))
+///     })?;
+///
+/// pos_in.push(Position { entity: 1, x: 0.0.into(), y: 0.0.into(), z: 1.0.into() }, 1);
+/// target_in.push(Target { entity: 1, x: 0.3.into(), y: 0.0.into() }, 1);
+/// circuit.step()?;
+///
+/// let decisions: Vec<MovementDecision> = decisions_out
+///     .consolidate()
+///     .iter()
+///     .map(|(decision, (), _)| decision)
+///     .collect();
+/// assert_eq!(decisions[0].dx.into_inner(), 0.3, "the step ends on the target");
+/// assert_eq!(arrived_out.consolidate().iter().count(), 0);
+/// # Ok(())
+/// # }
+/// ```
+#[must_use]
+pub fn steering_streams(
+    seek: &Stream<RootCircuit, OrdZSet<MovementDecision>>,
+    context: SteeringContext<'_>,
+) -> (
+    Stream<RootCircuit, OrdZSet<MovementDecision>>,
+    Stream<RootCircuit, OrdZSet<Arrived>>,
+) {
+    let SteeringContext {
+        fear,
+        goals,
+        positions,
+        floor_height,
+        config,
+    } = context;
+    let approaches = approaches(fear, goals, positions, config);
+    let steps = arrival_steps(seek, &approaches);
+    let terms = steps.plus(&avoidance(&steps, positions, floor_height, config));
+    let decisions = sum_vectors(&terms, |term| (term.entity, (term.dx, term.dy)))
+        .map(|(entity, sum)| clamped(*entity, sum));
+    (decisions, approaches.flat_map(Approach::arrived))
+}
+
+#[cfg(test)]
+mod tests;
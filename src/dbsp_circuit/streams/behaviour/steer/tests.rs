@@ -0,0 +1,215 @@
+//! Tests for the steering stage.
+
+use approx::assert_relative_eq;
+use dbsp::{operator::Generator, Circuit, OutputHandle, ZSetHandle};
+use rstest::rstest;
+
+use super::*;
+use crate::dbsp_circuit::streams::behaviour::{fear_level_stream, movement_decision_stream};
+
+/// Inputs and outputs of a circuit running seek decisions through
+/// [`steering_streams`].
+struct SteeringHandles {
+    fear_in: ZSetHandle<FearLevel>,
+    target_in: ZSetHandle<Target>,
+    position_in: ZSetHandle<Position>,
+    floor_in: ZSetHandle<FloorHeightAt>,
+    decisions_out: OutputHandle<OrdZSet<MovementDecision>>,
+    arrived_out: OutputHandle<OrdZSet<Arrived>>,
+}
+
+impl SteeringHandles {
+    fn position(&self, entity: i64, (x, y, z): (f64, f64, f64)) {
+        self.position_in.push(
+            Position {
+                entity,
+                x: x.into(),
+                y: y.into(),
+                z: z.into(),
+            },
+            1,
+        );
+    }
+
+    fn target(&self, entity: i64, (x, y): (f64, f64)) {
+        self.target_in.push(
+            Target {
+                entity,
+                x: x.into(),
+                y: y.into(),
+            },
+            1,
+        );
+    }
+
+    /// Flat floor of the cell `(x, y)` at height `z`.
+    fn floor(&self, (x, y): (i32, i32), z: f64) {
+        self.floor_in.push(
+            FloorHeightAt {
+                x,
+                y,
+                z: z.into(),
+                grad_x: 0.0.into(),
+                grad_y: 0.0.into(),
+                base: 0.0.into(),
+                ceiling: None,
+            },
+            1,
+        );
+    }
+
+    fn decisions(&self) -> Vec<MovementDecision> {
+        let mut decisions: Vec<_> = test_utils::collect_weighted(&self.decisions_out)
+            .into_iter()
+            .map(|(decision, _)| decision)
+            .collect();
+        decisions.sort_by_key(|d| d.entity);
+        decisions
+    }
+}
+
+fn build_steering_circuit(config: PhysicsConfig) -> (dbsp::CircuitHandle, SteeringHandles) {
+    RootCircuit::build(move |circuit| {
+        let (fears, fear_in) = circuit.add_input_zset::<FearLevel>();
+        let (targets, target_in) = circuit.add_input_zset::<Target>();
+        let (positions, position_in) = circuit.add_input_zset::<Position>();
+        let (floors, floor_in) = circuit.add_input_zset::<FloorHeightAt>();
+        let config_stream = circuit.add_source(Generator::new(move || config));
+        let fear = fear_level_stream(&positions, &fears);
+        let seek = movement_decision_stream(&fear, &targets, &positions, &config_stream);
+        let context = SteeringContext {
+            fear: &fear,
+            goals: &targets,
+            positions: &positions,
+            floor_height: &floors,
+            config: &config_stream,
+        };
+        let (decisions, arrived) = steering_streams(&seek, context);
+        Ok(SteeringHandles {
+            fear_in,
+            target_in,
+            position_in,
+            floor_in,
+            decisions_out: decisions.output(),
+            arrived_out: arrived.output(),
+        })
+    })
+    .expect("failed to build steering circuit")
+}
+
+fn assert_moves_by(decision: &MovementDecision, (dx, dy): (f64, f64)) {
+    assert_relative_eq!(decision.dx.into_inner(), dx, epsilon = 1e-9);
+    assert_relative_eq!(decision.dy.into_inner(), dy, epsilon = 1e-9);
+}
+
+#[rstest]
+#[case::far_away(2.0, 1.0, false)]
+#[case::inside_the_radius(0.3, 0.3, false)]
+#[case::on_the_target(0.0, 0.0, true)]
+fn arrival_slows_entities_onto_their_target(
+    #[case] distance: f64,
+    #[case] expected_dx: f64,
+    #[case] arrives: bool,
+) {
+    let (circuit, handles) = build_steering_circuit(PhysicsConfig::default());
+    handles.position(1, (0.5, 0.5, 1.0));
+    handles.target(1, (0.5 + distance, 0.5));
+    circuit.step().expect("dbsp step");
+
+    let decisions = handles.decisions();
+    let decision = test_utils::expect_single(&decisions, "steered decision");
+    assert_moves_by(decision, (expected_dx, 0.0));
+    let arrived = test_utils::collect_weighted(&handles.arrived_out);
+    let expected = Arrived {
+        entity: 1,
+        x: (0.5 + distance).into(),
+        y: 0.5.into(),
+    };
+    assert_eq!(arrived, if arrives { vec![(expected, 1)] } else { vec![] });
+}
+
+#[rstest]
+fn arrival_radius_comes_from_config() {
+    let (circuit, handles) = build_steering_circuit(PhysicsConfig {
+        arrival_radius: 2.0.into(),
+        ..PhysicsConfig::default()
+    });
+    handles.position(1, (0.5, 0.5, 1.0));
+    handles.target(1, (0.5, 1.5));
+    circuit.step().expect("dbsp step");
+
+    let decisions = handles.decisions();
+    let decision = test_utils::expect_single(&decisions, "eased decision");
+    assert_moves_by(decision, (0.0, 0.5));
+}
+
+// A long tick scales the step by `delta_time`, so the eased speed is capped
+// further to keep the step from overshooting the goal.
+#[rstest]
+#[case::short_tick(0.5, 0.5)]
+#[case::long_tick(2.0, 0.25)]
+fn arrival_never_overshoots_within_a_tick(#[case] delta_time: f64, #[case] expected_dy: f64) {
+    let (circuit, handles) = build_steering_circuit(PhysicsConfig {
+        delta_time: delta_time.into(),
+        ..PhysicsConfig::default()
+    });
+    handles.position(1, (0.5, 0.5, 1.0));
+    handles.target(1, (0.5, 1.0));
+    circuit.step().expect("dbsp step");
+
+    let decisions = handles.decisions();
+    let decision = test_utils::expect_single(&decisions, "capped decision");
+    assert_moves_by(decision, (0.0, expected_dy));
+}
+
+#[rstest]
+fn fleeing_entities_keep_full_speed() {
+    let (circuit, handles) = build_steering_circuit(PhysicsConfig::default());
+    handles.position(1, (0.5, 0.5, 1.0));
+    handles.target(1, (0.8, 0.5));
+    handles.fear_in.push(
+        FearLevel {
+            entity: 1,
+            level: 0.9.into(),
+        },
+        1,
+    );
+    circuit.step().expect("dbsp step");
+
+    let decisions = handles.decisions();
+    let decision = test_utils::expect_single(&decisions, "fleeing decision");
+    assert_moves_by(decision, (-1.0, 0.0));
+    assert!(test_utils::collect_weighted(&handles.arrived_out).is_empty());
+}
+
+#[rstest]
+#[case::wall_to_one_side(0.3, (1.0, -0.5))]
+#[case::wall_dead_ahead(0.5, (1.0, 0.5))]
+fn walls_ahead_turn_steering_entities(#[case] y: f64, #[case] blended: (f64, f64)) {
+    let (circuit, handles) = build_steering_circuit(PhysicsConfig::default());
+    handles.floor((0, 0), 1.0);
+    handles.floor((1, 0), 5.0);
+    handles.position(1, (0.5, y, 1.0));
+    handles.target(1, (3.5, y));
+    circuit.step().expect("dbsp step");
+
+    let decisions = handles.decisions();
+    let decision = test_utils::expect_single(&decisions, "avoiding decision");
+    let (x, turn) = blended;
+    let length = x.hypot(turn);
+    assert_moves_by(decision, (x / length, turn / length));
+}
+
+#[rstest]
+fn steps_onto_climbable_floors_are_not_avoided() {
+    let (circuit, handles) = build_steering_circuit(PhysicsConfig::default());
+    handles.floor((0, 0), 1.0);
+    handles.floor((1, 0), 2.0);
+    handles.position(1, (0.5, 0.5, 1.0));
+    handles.target(1, (3.5, 0.5));
+    circuit.step().expect("dbsp step");
+
+    let decisions = handles.decisions();
+    let decision = test_utils::expect_single(&decisions, "unobstructed decision");
+    assert_moves_by(decision, (1.0, 0.0));
+}
@@ -1,11 +1,17 @@
 //! Tests for the behavioural movement streams.
 
 use super::decide::{decide_movement, PositionTarget};
-use super::{apply_movement, fear_level_stream, movement_decision_stream};
-use crate::dbsp_circuit::{FearLevel, MovementDecision, Position, Target};
-use crate::FEAR_THRESHOLD;
+use super::fear::threat_level;
+use super::{
+    apply_movement, fear_level_stream, fleeing_goal_stream, movement_decision_stream,
+    steering_goal_stream, threat_fear_stream,
+};
+use crate::dbsp_circuit::{
+    Baddie, Civvy, FearLevel, MovementDecision, PathGoal, Position, Target, ThreatFear,
+};
+use crate::{PhysicsConfig, FEAR_DISTANCE_EPSILON, FEAR_THRESHOLD};
 use approx::assert_relative_eq;
-use dbsp::RootCircuit;
+use dbsp::{operator::Generator, Circuit, RootCircuit};
 use rstest::rstest;
 
 fn pt(px: f64, py: f64, tx: f64, ty: f64) -> PositionTarget {
@@ -35,7 +41,7 @@ fn decide_movement_direction(
     #[case] expected_dx: f64,
     #[case] expected_dy: f64,
 ) {
-    let mv = decide_movement(fear.into(), &pt(0.0, 0.0, 1.0, 1.0));
+    let mv = decide_movement(fear.into(), FEAR_THRESHOLD.into(), &pt(0.0, 0.0, 1.0, 1.0));
     assert_relative_eq!(mv.dx.into_inner(), expected_dx);
     assert_relative_eq!(mv.dy.into_inner(), expected_dy);
 }
@@ -64,7 +70,44 @@ fn movement_decision_join(
 ) {
     let (circuit, (fear_in, target_in, pos_in, decisions_handle)) =
         build_decision_circuit().expect("failed to build circuit for movement_decision_join");
+    push_fear_target_position(&fear_in, &target_in, &pos_in, fear);
 
+    circuit.step().expect("dbsp step");
+
+    let decisions = collect_decisions(&decisions_handle);
+    let decision = test_utils::expect_single(&decisions, "movement decision result");
+    assert_relative_eq!(decision.dx.into_inner(), expected_dx);
+    assert_relative_eq!(decision.dy.into_inner(), expected_dy);
+}
+
+#[test]
+fn fear_threshold_comes_from_config() {
+    let (circuit, (fear_in, target_in, pos_in, decisions_handle)) =
+        build_decision_circuit_with(PhysicsConfig {
+            fear_threshold: 0.5.into(),
+            ..PhysicsConfig::default()
+        })
+        .expect("failed to build circuit for fear_threshold_comes_from_config");
+    push_fear_target_position(&fear_in, &target_in, &pos_in, Some(0.3));
+
+    circuit.step().expect("dbsp step");
+
+    let decisions = collect_decisions(&decisions_handle);
+    let decision = test_utils::expect_single(&decisions, "movement decision result");
+    assert!(
+        decision.dx.into_inner() > 0.0 && decision.dy.into_inner() > 0.0,
+        "fear below the configured threshold approaches: {decision:?}"
+    );
+}
+
+/// Pushes an optional fear level, a target at `(1, 1)` and a position at the
+/// origin for entity 1.
+fn push_fear_target_position(
+    fear_in: &dbsp::ZSetHandle<FearLevel>,
+    target_in: &dbsp::ZSetHandle<Target>,
+    pos_in: &dbsp::ZSetHandle<Position>,
+    fear: Option<f64>,
+) {
     if let Some(level) = fear {
         fear_in.push(
             FearLevel {
@@ -91,20 +134,6 @@ fn movement_decision_join(
         },
         1,
     );
-
-    circuit.step().expect("dbsp step");
-
-    let decisions: Vec<MovementDecision> = decisions_handle
-        .consolidate()
-        .iter()
-        .map(|(decision, (), _timestamp)| {
-            let decision_ref: &MovementDecision = &decision;
-            *decision_ref
-        })
-        .collect();
-    let decision = test_utils::expect_single(&decisions, "movement decision result");
-    assert_relative_eq!(decision.dx.into_inner(), expected_dx);
-    assert_relative_eq!(decision.dy.into_inner(), expected_dy);
 }
 
 #[test]
@@ -146,7 +175,7 @@ fn no_decision_without_target() {
 
 #[rstest]
 fn decide_movement_zero_displacement_yields_zero_vector() {
-    let mv = decide_movement(0.0.into(), &pt(1.0, 1.0, 1.0, 1.0));
+    let mv = decide_movement(0.0.into(), FEAR_THRESHOLD.into(), &pt(1.0, 1.0, 1.0, 1.0));
     assert_relative_eq!(mv.dx.into_inner(), 0.0);
     assert_relative_eq!(mv.dy.into_inner(), 0.0);
 }
@@ -170,14 +199,42 @@ fn build_decision_circuit() -> Result<
         ),
     ),
     dbsp::Error,
+> {
+    build_decision_circuit_with(PhysicsConfig::default())
+}
+
+/// As [`build_decision_circuit`], with the physics configuration pinned.
+#[expect(
+    clippy::type_complexity,
+    reason = "DBSP handle tuples are verbose by nature"
+)]
+fn build_decision_circuit_with(
+    config: PhysicsConfig,
+) -> Result<
+    (
+        dbsp::CircuitHandle,
+        (
+            dbsp::ZSetHandle<FearLevel>,
+            dbsp::ZSetHandle<Target>,
+            dbsp::ZSetHandle<Position>,
+            dbsp::OutputHandle<dbsp::typed_batch::OrdZSet<MovementDecision>>,
+        ),
+    ),
+    dbsp::Error,
 > {
     let handles = RootCircuit::build(|circuit| {
         let (fear_input, fear_handle) = circuit.add_input_zset::<FearLevel>();
         let (target_stream, target_handle) = circuit.add_input_zset::<Target>();
         let (position_stream, position_handle) = circuit.add_input_zset::<Position>();
+        let config_stream = circuit.add_source(Generator::new(move || config));
         let fear_stream = fear_level_stream(&position_stream, &fear_input);
-        let output_handle =
-            movement_decision_stream(&fear_stream, &target_stream, &position_stream).output();
+        let output_handle = movement_decision_stream(
+            &fear_stream,
+            &target_stream,
+            &position_stream,
+            &config_stream,
+        )
+        .output();
         Ok((fear_handle, target_handle, position_handle, output_handle))
     })?;
     Ok(handles)
@@ -319,9 +376,11 @@ fn build_decision_and_apply_circuit(
         let (fear_input, fear) = circuit.add_input_zset::<FearLevel>();
         let (target_stream, targets) = circuit.add_input_zset::<Target>();
         let (position_stream, positions) = circuit.add_input_zset::<Position>();
+        let config = circuit.add_source(Generator::new(PhysicsConfig::default));
         let fear_stream = fear_level_stream(&position_stream, &fear_input);
-        let decisions = movement_decision_stream(&fear_stream, &target_stream, &position_stream);
-        let moved = apply_movement(&position_stream, &decisions).output();
+        let decisions =
+            movement_decision_stream(&fear_stream, &target_stream, &position_stream, &config);
+        let moved = apply_movement(&position_stream, &decisions, &config).output();
         Ok(DecisionApplyHandles {
             fear,
             targets,
@@ -380,3 +439,266 @@ fn duplicate_targets_shift_position_once() {
     assert_relative_eq!(position.y.into_inner(), -3.0 / magnitude);
     assert_relative_eq!(position.z.into_inner(), 0.0);
 }
+
+const fn target_at(entity: i64, x: f64, y: f64) -> Target {
+    Target {
+        entity,
+        x: ordered_float::OrderedFloat(x),
+        y: ordered_float::OrderedFloat(y),
+    }
+}
+
+const fn waypoint_at(entity: i64, x: f64, y: f64) -> PathGoal {
+    PathGoal {
+        entity,
+        x: ordered_float::OrderedFloat(x),
+        y: ordered_float::OrderedFloat(y),
+    }
+}
+
+#[test]
+fn path_goals_supersede_targets() {
+    let (circuit, (target_in, goal_in, goals_out)) = RootCircuit::build(|circuit| {
+        let (targets, target_in) = circuit.add_input_zset::<Target>();
+        let (path_goals, goal_in) = circuit.add_input_zset::<PathGoal>();
+        let goals = steering_goal_stream(&targets, &path_goals).output();
+        Ok((target_in, goal_in, goals))
+    })
+    .expect("failed to build steering goal circuit");
+
+    target_in.push(target_at(1, 9.0, 0.0), 1);
+    target_in.push(target_at(2, 4.0, 4.0), 1);
+    goal_in.push(waypoint_at(1, 0.5, 3.5), 1);
+    circuit.step().expect("dbsp step");
+
+    let mut goals = test_utils::collect_weighted(&goals_out);
+    goals.sort_by_key(|(goal, _)| goal.entity);
+    assert_eq!(
+        goals,
+        vec![(target_at(1, 0.5, 3.5), 1), (target_at(2, 4.0, 4.0), 1)],
+        "the waypoint replaces entity 1's target; entity 2 keeps its own"
+    );
+
+    // Dropping the waypoint hands steering back to the raw target.
+    goal_in.push(waypoint_at(1, 0.5, 3.5), -1);
+    circuit.step().expect("dbsp step");
+
+    let mut changes = test_utils::collect_weighted(&goals_out);
+    changes.sort_by_key(|(_, weight)| *weight);
+    assert_eq!(
+        changes,
+        vec![(target_at(1, 0.5, 3.5), -1), (target_at(1, 9.0, 0.0), 1)]
+    );
+}
+
+fn at(entity: i64, x: f64, y: f64) -> Position {
+    Position {
+        entity,
+        x: x.into(),
+        y: y.into(),
+        z: 0.0.into(),
+    }
+}
+
+#[rstest]
+#[case::in_range(1.0, 0.8, 0.5, 0.4 / (1.0 + FEAR_DISTANCE_EPSILON))]
+#[case::outside_the_radius(2.0, 0.8, 0.5, 0.0)]
+#[case::fearless(1.0, 0.0, 0.5, 0.0)]
+#[case::harmless(1.0, 0.8, 0.0, 0.0)]
+#[case::coincident(0.0, 0.8, 0.5, 0.4 / FEAR_DISTANCE_EPSILON)]
+fn threat_level_falls_off_with_distance(
+    #[case] distance: f64,
+    #[case] fraidiness: f64,
+    #[case] meanness: f64,
+    #[case] expected: f64,
+) {
+    let level = threat_level(
+        &at(1, 0.0, 0.0),
+        fraidiness,
+        &at(2, distance, 0.0),
+        meanness,
+    );
+    assert!(level.is_finite());
+    assert_relative_eq!(level, expected);
+}
+
+#[expect(
+    clippy::type_complexity,
+    reason = "DBSP handle tuples are verbose by nature"
+)]
+fn build_threat_circuit() -> (
+    dbsp::CircuitHandle,
+    (
+        dbsp::ZSetHandle<Position>,
+        dbsp::ZSetHandle<Civvy>,
+        dbsp::ZSetHandle<Baddie>,
+        dbsp::ZSetHandle<FearLevel>,
+        dbsp::OutputHandle<dbsp::typed_batch::OrdZSet<ThreatFear>>,
+    ),
+) {
+    RootCircuit::build(|circuit| {
+        let (positions, pos_in) = circuit.add_input_zset::<Position>();
+        let (civvies, civvy_in) = circuit.add_input_zset::<Civvy>();
+        let (baddies, baddie_in) = circuit.add_input_zset::<Baddie>();
+        let (fears, fear_in) = circuit.add_input_zset::<FearLevel>();
+        let threats = threat_fear_stream(&positions, &civvies, &baddies, &fears).output();
+        Ok((pos_in, civvy_in, baddie_in, fear_in, threats))
+    })
+    .expect("failed to build threat circuit")
+}
+
+#[test]
+fn threats_sum_and_centre_on_the_baddies() {
+    let (circuit, (pos_in, civvy_in, baddie_in, _fear_in, threats_out)) = build_threat_circuit();
+    pos_in.push(at(1, 0.0, 0.0), 1);
+    pos_in.push(at(2, 1.0, 0.0), 1);
+    pos_in.push(at(3, 0.0, 1.0), 1);
+    pos_in.push(at(4, 9.0, 9.0), 1);
+    civvy_in.push(
+        Civvy {
+            entity: 1,
+            fraidiness: 0.8.into(),
+        },
+        1,
+    );
+    for entity in 2..=4 {
+        baddie_in.push(
+            Baddie {
+                entity,
+                meanness: 0.5.into(),
+            },
+            1,
+        );
+    }
+    circuit.step().expect("dbsp step");
+
+    let threats = test_utils::collect_weighted(&threats_out);
+    let (threat, weight) = test_utils::expect_single(&threats, "threat for the civvy");
+    assert_eq!((threat.entity, *weight), (1, 1));
+    let single = 0.4 / (1.0 + FEAR_DISTANCE_EPSILON);
+    assert_relative_eq!(threat.level.into_inner(), 2.0 * single);
+    assert_relative_eq!(threat.x.into_inner(), 0.5);
+    assert_relative_eq!(threat.y.into_inner(), 0.5);
+}
+
+// A fraidiness above one reaches past the neighbouring fear-grid cells, and a
+// civvy straddling a cell boundary still sees baddies on both sides.
+#[rstest]
+#[case::far_reaching(at(1, 0.5, 0.5), 3.0, at(2, 5.5, 0.5), Some(1.5 / (25.0 + FEAR_DISTANCE_EPSILON)))]
+#[case::across_cell_boundary(at(1, 1.9, 0.5), 0.8, at(2, 2.1, 0.5), Some(1.0))]
+#[case::out_of_reach(at(1, 0.5, 0.5), 3.0, at(2, 6.6, 0.5), None)]
+fn threats_are_found_across_grid_cells(
+    #[case] civvy: Position,
+    #[case] fraidiness: f64,
+    #[case] baddie: Position,
+    #[case] expected: Option<f64>,
+) {
+    let (circuit, (pos_in, civvy_in, baddie_in, _fear_in, threats_out)) = build_threat_circuit();
+    pos_in.push(civvy, 1);
+    pos_in.push(baddie, 1);
+    civvy_in.push(
+        Civvy {
+            entity: civvy.entity,
+            fraidiness: fraidiness.into(),
+        },
+        1,
+    );
+    baddie_in.push(
+        Baddie {
+            entity: baddie.entity,
+            meanness: 0.5.into(),
+        },
+        1,
+    );
+    circuit.step().expect("dbsp step");
+
+    let threats = test_utils::collect_weighted(&threats_out);
+    match expected {
+        Some(level) => {
+            let (threat, _) = test_utils::expect_single(&threats, "threat for the civvy");
+            assert_relative_eq!(threat.level.into_inner(), level);
+        }
+        None => assert!(threats.is_empty(), "unexpected threats: {threats:?}"),
+    }
+}
+
+#[test]
+fn threat_fear_is_clamped_and_yields_to_explicit_fear() {
+    let (circuit, (pos_in, civvy_in, baddie_in, fear_in, threats_out)) = build_threat_circuit();
+    pos_in.push(at(1, 0.0, 0.0), 1);
+    pos_in.push(at(2, 0.1, 0.0), 1);
+    civvy_in.push(
+        Civvy {
+            entity: 1,
+            fraidiness: 1.0.into(),
+        },
+        1,
+    );
+    baddie_in.push(
+        Baddie {
+            entity: 2,
+            meanness: 1.0.into(),
+        },
+        1,
+    );
+    circuit.step().expect("dbsp step");
+
+    let threats = test_utils::collect_weighted(&threats_out);
+    let (threat, _) = test_utils::expect_single(&threats, "clamped threat");
+    assert_relative_eq!(threat.level.into_inner(), 1.0);
+
+    fear_in.push(
+        FearLevel {
+            entity: 1,
+            level: 0.0.into(),
+        },
+        1,
+    );
+    circuit.step().expect("dbsp step");
+
+    let changes = test_utils::collect_weighted(&threats_out);
+    let (_, weight) = test_utils::expect_single(&changes, "retracted threat");
+    assert_eq!(
+        *weight, -1,
+        "an explicit fear level replaces the derived one"
+    );
+}
+
+#[test]
+fn frightened_entities_flee_the_threat_centre() {
+    let (circuit, (target_in, threat_in, goals_out)) = RootCircuit::build(|circuit| {
+        let (targets, target_in) = circuit.add_input_zset::<Target>();
+        let (threats, threat_in) = circuit.add_input_zset::<ThreatFear>();
+        let config = circuit.add_source(Generator::new(PhysicsConfig::default));
+        let goals = fleeing_goal_stream(&targets, &threats, &config).output();
+        Ok((target_in, threat_in, goals))
+    })
+    .expect("failed to build fleeing goal circuit");
+
+    target_in.push(target_at(1, 5.0, 5.0), 1);
+    target_in.push(target_at(2, 4.0, 4.0), 1);
+    for (entity, level) in [(1, 0.9), (2, 0.1), (3, 0.5)] {
+        threat_in.push(
+            ThreatFear {
+                entity,
+                level: level.into(),
+                x: 1.0.into(),
+                y: 2.0.into(),
+            },
+            1,
+        );
+    }
+    circuit.step().expect("dbsp step");
+
+    let mut goals = test_utils::collect_weighted(&goals_out);
+    goals.sort_by_key(|(goal, _)| goal.entity);
+    assert_eq!(
+        goals,
+        vec![
+            (target_at(1, 1.0, 2.0), 1),
+            (target_at(2, 4.0, 4.0), 1),
+            (target_at(3, 1.0, 2.0), 1),
+        ],
+        "frightened entities steer on the threat; calm ones keep their goal"
+    );
+}
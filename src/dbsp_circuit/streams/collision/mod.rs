@@ -0,0 +1,13 @@
+//! Collision streams for terrain walls and neighbouring entities.
+//!
+//! Wall collision resolves one-tick moves against the floor grid, while
+//! separation pushes overlapping entities apart so units do not stack.
+
+mod separation;
+mod walls;
+
+pub use separation::{apply_separation, separation_stream};
+pub use walls::wall_collision_stream;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,197 @@
+//! Separation of overlapping entities.
+//!
+//! Entities carrying an [`Extent`] are bucketed into every unit grid cell
+//! their bounding square covers, so bodies of any radius meet each neighbour
+//! they can overlap in at least one shared cell. Each overlapping pair pushes
+//! both entities apart by half the overlap, and the pushes are summed per
+//! entity into a single [`Separation`] correction.
+
+use dbsp::{typed_batch::OrdZSet, RootCircuit, Stream};
+use ordered_float::OrderedFloat;
+
+use crate::numeric::floor_to_i32;
+use crate::SEPARATION_EPSILON;
+
+use crate::dbsp_circuit::streams::vector_sum::sum_vectors;
+use crate::dbsp_circuit::{Extent, Position, Separation, Velocity};
+
+crate::dbsp_copy_record! {
+    /// An entity's position paired with its collision radius.
+    struct Body {
+        /// Position of the entity at the start of the tick.
+        position: Position,
+        /// Radius of the entity's collision circle.
+        radius: OrderedFloat<f64>,
+    }
+}
+
+impl Body {
+    /// Inclusive ranges of grid cells the body's bounding square covers along
+    /// the X and Y axes.
+    ///
+    /// A radius that is negative or not finite covers only the centre's cell.
+    fn span(&self) -> ((i32, i32), (i32, i32)) {
+        let radius = self.radius.into_inner();
+        let reach = if radius.is_finite() {
+            radius.max(0.0)
+        } else {
+            0.0
+        };
+        let bounds = |centre: OrderedFloat<f64>| {
+            (floor_to_i32(centre - reach), floor_to_i32(centre + reach))
+        };
+        (bounds(self.position.x), bounds(self.position.y))
+    }
+
+    /// The body keyed by every cell its bounding square covers.
+    fn covered_cells(&self) -> Vec<((i32, i32), Self)> {
+        let ((x0, x1), (y0, y1)) = self.span();
+        (x0..=x1)
+            .flat_map(|cx| (y0..=y1).map(move |cy| ((cx, cy), *self)))
+            .collect()
+    }
+
+    /// Lowest cell covered by both bodies.
+    ///
+    /// Bodies sharing several cells are compared only in this one, so each
+    /// pair pushes once.
+    fn first_shared_cell(&self, other: &Self) -> (i32, i32) {
+        let ((ax, _), (ay, _)) = self.span();
+        let ((bx, _), (by, _)) = other.span();
+        (ax.max(bx), ay.max(by))
+    }
+
+    /// Push `other` applies to this body, or `None` when the two do not
+    /// overlap.
+    ///
+    /// Bodies whose heights differ by at least their combined radii, such as
+    /// units on different floors, never push each other.
+    fn push_from(&self, other: &Self) -> Option<Separation> {
+        let (a, b) = (&self.position, &other.position);
+        let reach = self.radius.into_inner() + other.radius.into_inner();
+        if (a.z.into_inner() - b.z.into_inner()).abs() >= reach {
+            return None;
+        }
+        let dx = a.x.into_inner() - b.x.into_inner();
+        let dy = a.y.into_inner() - b.y.into_inner();
+        let distance = dx.hypot(dy);
+        let overlap = reach - distance;
+        if overlap <= 0.0 {
+            return None;
+        }
+        let half = overlap / 2.0;
+        let (px, py) = if distance < SEPARATION_EPSILON {
+            // Coincident bodies have no separating direction, so split them
+            // along X with the lower entity id moving towards negative X.
+            let direction = if a.entity < b.entity { -1.0 } else { 1.0 };
+            (direction * half, 0.0)
+        } else {
+            (dx / distance * half, dy / distance * half)
+        };
+        Some(Separation {
+            entity: a.entity,
+            dx: OrderedFloat(px),
+            dy: OrderedFloat(py),
+        })
+    }
+}
+
+/// Computes separation corrections for overlapping entities.
+///
+/// Only entities with both a [`Position`] and an [`Extent`] take part. Every
+/// overlapping pair pushes each member directly away from the other by half
+/// the overlap, so two units meeting head-on end the tick just touching.
+/// Corrections from several neighbours are summed, and entities that overlap
+/// nothing emit no record.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use anyhow::Result;
+/// # use dbsp::RootCircuit;
+/// # use lille::dbsp_circuit::{separation_stream, Extent, Position, Separation};
+/// # fn main() -> Result<()> {
+/// let (mut circuit, (position_in, extent_in, mut separation_out)) =
+///     RootCircuit::build(|circuit| {
+///         let (positions, position_in) = circuit.add_input_zset::<Position>();
+///         let (extents, extent_in) = circuit.add_input_zset::<Extent>();
+///         let separations = separation_stream(&positions, &extents).output();
+///         Ok((position_in, extent_in, separations))
+///     })?;
+///
+/// for (entity, x) in [(1, 0.4), (2, 0.6)] {
+///     position_in.push(Position { entity, x: x.into(), y: 0.5.into(), z: 1.0.into() }, 1);
+///     extent_in.push(Extent { entity, radius: 0.25.into() }, 1);
+/// }
+/// circuit.step()?;
+///
+/// let pushes: Vec<Separation> = separation_out
+///     .consolidate()
+///     .iter()
+///     .map(|(separation, (), _)| separation)
+///     .collect();
+/// assert_eq!(pushes.len(), 2, "both units are pushed apart");
+/// # Ok(())
+/// # }
+/// ```
+#[must_use]
+pub fn separation_stream(
+    positions: &Stream<RootCircuit, OrdZSet<Position>>,
+    extents: &Stream<RootCircuit, OrdZSet<Extent>>,
+) -> Stream<RootCircuit, OrdZSet<Separation>> {
+    let bodies = positions.map_index(|p| (p.entity, *p)).join(
+        &extents.map_index(|e| (e.entity, e.radius)),
+        |_, position, &radius| Body {
+            position: *position,
+            radius,
+        },
+    );
+    let cells = bodies
+        .flat_map(Body::covered_cells)
+        .map_index(|(cell, body)| (*cell, *body));
+
+    let pushes = cells
+        .join(&cells, |cell, body, other| (*cell, *body, *other))
+        .flat_map(|(cell, body, other)| {
+            (body.position.entity != other.position.entity
+                && *cell == body.first_shared_cell(other))
+            .then(|| body.push_from(other))
+            .flatten()
+        });
+    sum_vectors(&pushes, |push| (push.entity, (push.dx, push.dy))).map(|(entity, sum)| Separation {
+        entity: *entity,
+        dx: sum.dx,
+        dy: sum.dy,
+    })
+}
+
+/// Adds separation corrections to per-tick displacements.
+///
+/// `steps` pairs each entity's position with the displacement it attempts this
+/// tick, as produced by [`movement_steps`](crate::dbsp_circuit::movement_steps).
+/// Entities without a correction pass through unchanged. Applying the push
+/// before [`wall_collision_stream`](crate::dbsp_circuit::wall_collision_stream)
+/// keeps separation from shoving units into walls.
+#[must_use]
+pub fn apply_separation(
+    steps: &Stream<RootCircuit, OrdZSet<(Position, Velocity)>>,
+    separations: &Stream<RootCircuit, OrdZSet<Separation>>,
+) -> Stream<RootCircuit, OrdZSet<(Position, Velocity)>> {
+    steps
+        .map_index(|(position, step)| (position.entity, (*position, *step)))
+        .outer_join(
+            &separations.map_index(|s| (s.entity, (s.dx, s.dy))),
+            |_, &(position, step), &(dx, dy)| {
+                Some((
+                    position,
+                    Velocity {
+                        vx: OrderedFloat(step.vx.into_inner() + dx.into_inner()),
+                        vy: OrderedFloat(step.vy.into_inner() + dy.into_inner()),
+                        ..step
+                    },
+                ))
+            },
+            |_, &(position, step)| Some((position, step)),
+            |_, _| None,
+        )
+        .flat_map(|moved| *moved)
+}
@@ -0,0 +1,4 @@
+//! Collision stream tests.
+
+mod separation;
+mod walls;
@@ -0,0 +1,188 @@
+//! Tests for separating overlapping entities.
+
+use crate::dbsp_circuit::{
+    apply_separation, separation_stream, Extent, Position, Separation, Velocity,
+};
+use approx::assert_relative_eq;
+use dbsp::RootCircuit;
+use rstest::rstest;
+
+type SeparationCircuit = (
+    dbsp::CircuitHandle,
+    (
+        dbsp::ZSetHandle<Position>,
+        dbsp::ZSetHandle<Extent>,
+        dbsp::OutputHandle<dbsp::typed_batch::OrdZSet<Separation>>,
+    ),
+);
+
+fn build_separation_circuit() -> Result<SeparationCircuit, dbsp::Error> {
+    RootCircuit::build(|circuit| {
+        let (positions, position_in) = circuit.add_input_zset::<Position>();
+        let (extents, extent_in) = circuit.add_input_zset::<Extent>();
+        let output = separation_stream(&positions, &extents).output();
+        Ok((position_in, extent_in, output))
+    })
+}
+
+fn at(entity: i64, x: f64, y: f64, z: f64) -> Position {
+    Position {
+        entity,
+        x: x.into(),
+        y: y.into(),
+        z: z.into(),
+    }
+}
+
+fn extent(entity: i64) -> Extent {
+    Extent {
+        entity,
+        radius: 0.25.into(),
+    }
+}
+
+fn large_extent(entity: i64) -> Extent {
+    Extent {
+        entity,
+        radius: 0.8.into(),
+    }
+}
+
+/// Steps a circuit holding the given bodies and returns each entity's push,
+/// ordered by entity id.
+fn separate(positions: &[Position], extents: &[Extent]) -> Vec<(i64, f64, f64)> {
+    let (circuit, (position_in, extent_in, out)) =
+        build_separation_circuit().expect("failed to build separation circuit");
+    for position in positions {
+        position_in.push(*position, 1);
+    }
+    for e in extents {
+        extent_in.push(*e, 1);
+    }
+
+    circuit.step().expect("dbsp step");
+
+    test_utils::collect_weighted(&out)
+        .into_iter()
+        .map(|(s, weight)| {
+            assert_eq!(weight, 1, "each entity emits a single correction");
+            (s.entity, s.dx.into_inner(), s.dy.into_inner())
+        })
+        .collect()
+}
+
+fn assert_pushes(actual: &[(i64, f64, f64)], expected: &[(i64, f64, f64)]) {
+    assert_eq!(actual.len(), expected.len(), "pushes: {actual:?}");
+    for (&(entity, dx, dy), &(exp_entity, exp_dx, exp_dy)) in actual.iter().zip(expected) {
+        assert_eq!(entity, exp_entity);
+        assert_relative_eq!(dx, exp_dx, epsilon = 1e-9);
+        assert_relative_eq!(dy, exp_dy, epsilon = 1e-9);
+    }
+}
+
+#[rstest]
+#[case::head_on(at(1, 0.4, 0.5, 1.0), at(2, 0.6, 0.5, 1.0), &[(1, -0.15, 0.0), (2, 0.15, 0.0)])]
+#[case::across_cell_boundary(
+    at(1, 0.9, 0.5, 1.0),
+    at(2, 1.1, 0.5, 1.0),
+    &[(1, -0.15, 0.0), (2, 0.15, 0.0)]
+)]
+#[case::coincident_split_by_id(
+    at(1, 0.5, 0.5, 1.0),
+    at(2, 0.5, 0.5, 1.0),
+    &[(1, -0.25, 0.0), (2, 0.25, 0.0)]
+)]
+#[case::along_y(at(1, 0.5, 0.4, 1.0), at(2, 0.5, 0.6, 1.0), &[(1, 0.0, -0.15), (2, 0.0, 0.15)])]
+#[case::apart(at(1, 0.2, 0.5, 1.0), at(2, 0.8, 0.5, 1.0), &[])]
+#[case::different_floors(at(1, 0.5, 0.5, 1.0), at(2, 0.5, 0.5, 3.0), &[])]
+fn separates_overlapping_pairs(
+    #[case] first: Position,
+    #[case] second: Position,
+    #[case] expected: &[(i64, f64, f64)],
+) {
+    let pushes = separate(&[first, second], &[extent(1), extent(2)]);
+    assert_pushes(&pushes, expected);
+}
+
+// Bodies of radius 0.8 overlap across more than one cell boundary, so their
+// centres can lie two cells apart.
+#[rstest]
+#[case::two_cells_apart(
+    at(1, 0.9, 0.5, 1.0),
+    at(2, 2.4, 0.5, 1.0),
+    &[(1, -0.05, 0.0), (2, 0.05, 0.0)]
+)]
+#[case::two_cells_apart_along_y(
+    at(1, 0.5, 0.9, 1.0),
+    at(2, 0.5, 2.4, 1.0),
+    &[(1, 0.0, -0.05), (2, 0.0, 0.05)]
+)]
+#[case::apart(at(1, 0.1, 0.5, 1.0), at(2, 2.6, 0.5, 1.0), &[])]
+fn separates_large_bodies(
+    #[case] first: Position,
+    #[case] second: Position,
+    #[case] expected: &[(i64, f64, f64)],
+) {
+    let pushes = separate(&[first, second], &[large_extent(1), large_extent(2)]);
+    assert_pushes(&pushes, expected);
+}
+
+#[test]
+fn entities_without_extent_are_ignored() {
+    let pushes = separate(&[at(1, 0.5, 0.5, 1.0), at(2, 0.5, 0.5, 1.0)], &[extent(1)]);
+    assert!(pushes.is_empty(), "unexpected pushes: {pushes:?}");
+}
+
+#[test]
+fn pushes_from_several_neighbours_are_summed() {
+    let pushes = separate(
+        &[
+            at(1, 0.4, 0.5, 1.0),
+            at(2, 0.6, 0.5, 1.0),
+            at(3, 0.8, 0.5, 1.0),
+        ],
+        &[extent(1), extent(2), extent(3)],
+    );
+    // The middle unit is pushed equally from both sides and stays put, while
+    // the outer pair also overlap each other slightly.
+    assert_pushes(&pushes, &[(1, -0.2, 0.0), (2, 0.0, 0.0), (3, 0.2, 0.0)]);
+}
+
+#[test]
+fn separation_is_added_to_the_step() {
+    let (circuit, (step_in, separation_in, out)) = RootCircuit::build(|circuit| {
+        let (steps, step_in) = circuit.add_input_zset::<(Position, Velocity)>();
+        let (separations, separation_in) = circuit.add_input_zset::<Separation>();
+        let output = apply_separation(&steps, &separations).output();
+        Ok((step_in, separation_in, output))
+    })
+    .expect("failed to build apply circuit");
+    let step = |entity, vx: f64| Velocity {
+        entity,
+        vx: vx.into(),
+        vy: 0.0.into(),
+        vz: 0.0.into(),
+    };
+    step_in.push((at(1, 0.5, 0.5, 1.0), step(1, 1.0)), 1);
+    step_in.push((at(2, 3.5, 0.5, 1.0), step(2, 1.0)), 1);
+    separation_in.push(
+        Separation {
+            entity: 1,
+            dx: (-0.25).into(),
+            dy: 0.5.into(),
+        },
+        1,
+    );
+
+    circuit.step().expect("dbsp step");
+
+    let mut pushed = step(1, 0.75);
+    pushed.vy = 0.5.into();
+    assert_eq!(
+        test_utils::collect_weighted(&out),
+        vec![
+            ((at(1, 0.5, 0.5, 1.0), pushed), 1),
+            ((at(2, 3.5, 0.5, 1.0), step(2, 1.0)), 1),
+        ]
+    );
+}
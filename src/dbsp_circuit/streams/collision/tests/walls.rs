@@ -0,0 +1,148 @@
+//! Tests for wall collision against block terrain.
+
+use crate::dbsp_circuit::{wall_collision_stream, FloorHeightAt, Position, Velocity};
+use crate::{PhysicsConfig, MAX_STEP_HEIGHT};
+use dbsp::{operator::Generator, Circuit, RootCircuit};
+use rstest::rstest;
+
+type Move = (Position, Velocity);
+
+type CollisionCircuit = (
+    dbsp::CircuitHandle,
+    (
+        dbsp::ZSetHandle<Move>,
+        dbsp::ZSetHandle<FloorHeightAt>,
+        dbsp::OutputHandle<dbsp::typed_batch::OrdZSet<Move>>,
+    ),
+);
+
+fn build_collision_circuit(config: PhysicsConfig) -> Result<CollisionCircuit, dbsp::Error> {
+    RootCircuit::build(move |circuit| {
+        let (moves, move_in) = circuit.add_input_zset::<Move>();
+        let (floor, floor_in) = circuit.add_input_zset::<FloorHeightAt>();
+        let config_source = circuit.add_source(Generator::new(move || config));
+        let output = wall_collision_stream(&moves, &floor, &config_source).output();
+        Ok((move_in, floor_in, output))
+    })
+}
+
+fn at(x: f64, y: f64) -> Position {
+    Position {
+        entity: 1,
+        x: x.into(),
+        y: y.into(),
+        z: 1.0.into(),
+    }
+}
+
+fn step(vx: f64, vy: f64) -> Velocity {
+    Velocity {
+        entity: 1,
+        vx: vx.into(),
+        vy: vy.into(),
+        vz: 0.0.into(),
+    }
+}
+
+/// Flat ground at height 1.0 across the 3x3 cells around the origin, with the
+/// listed cells raised to the given floor heights.
+fn terrain(raised: &[(i32, i32, f64)]) -> Vec<FloorHeightAt> {
+    let mut cells = Vec::new();
+    for x in 0..3 {
+        for y in 0..3 {
+            let z = raised
+                .iter()
+                .find(|&&(rx, ry, _)| rx == x && ry == y)
+                .map_or(1.0, |&(_, _, rz)| rz);
+            cells.push(FloorHeightAt {
+                x,
+                y,
+                z: z.into(),
+                grad_x: 0.0.into(),
+                grad_y: 0.0.into(),
+                // Solid from the ground block up, so lowered cells stay
+                // below the entity rather than opening a void.
+                base: (z - 1.0).min(0.0).into(),
+                ceiling: None,
+            });
+        }
+    }
+    cells
+}
+
+fn resolve(floor: Vec<FloorHeightAt>, weight: i64) -> Vec<(Move, i64)> {
+    resolve_with(PhysicsConfig::default(), floor, weight)
+}
+
+fn resolve_with(config: PhysicsConfig, floor: Vec<FloorHeightAt>, weight: i64) -> Vec<(Move, i64)> {
+    let (circuit, (move_in, floor_in, out)) =
+        build_collision_circuit(config).expect("failed to build collision circuit");
+    for cell in floor {
+        floor_in.push(cell, 1);
+    }
+    move_in.push((at(0.5, 0.5), step(1.0, 1.0)), weight);
+
+    circuit.step().expect("dbsp step");
+
+    test_utils::collect_weighted(&out)
+}
+
+#[rstest]
+#[case::open_ground(terrain(&[]), (at(1.5, 1.5), step(1.0, 1.0)))]
+#[case::single_step_is_climbable(
+    terrain(&[(1, 1, 1.0 + MAX_STEP_HEIGHT)]),
+    (at(1.5, 1.5), step(1.0, 1.0))
+)]
+#[case::wall_rejects_move(
+    terrain(&[(1, 1, 5.0), (1, 0, 5.0), (0, 1, 5.0)]),
+    (at(0.5, 0.5), step(0.0, 0.0))
+)]
+#[case::slides_along_x(
+    terrain(&[(1, 1, 5.0), (0, 1, 5.0)]),
+    (at(1.5, 0.5), step(1.0, 0.0))
+)]
+#[case::slides_along_y(
+    terrain(&[(1, 1, 5.0), (1, 0, 5.0)]),
+    (at(0.5, 1.5), step(0.0, 1.0))
+)]
+#[case::drop_is_not_a_wall(terrain(&[(1, 1, -4.0)]), (at(1.5, 1.5), step(1.0, 1.0)))]
+#[case::missing_floor_is_not_a_wall(
+    terrain(&[]).into_iter().filter(|f| (f.x, f.y) != (1, 1)).collect(),
+    (at(1.5, 1.5), step(1.0, 1.0))
+)]
+#[case::corner_between_two_walls(
+    terrain(&[(1, 0, 5.0), (0, 1, 5.0)]),
+    (at(0.5, 0.5), step(0.0, 0.0))
+)]
+#[case::embedded_entity_stays_put(
+    terrain(&[(0, 0, 9.0), (1, 1, 9.0), (1, 0, 9.0), (0, 1, 9.0)]),
+    (at(0.5, 0.5), step(0.0, 0.0))
+)]
+fn resolves_diagonal_move(#[case] floor: Vec<FloorHeightAt>, #[case] expected: Move) {
+    assert_eq!(resolve(floor, 1), vec![(expected, 1)]);
+}
+
+#[test]
+fn retracted_move_resolves_to_retraction() {
+    let floor = terrain(&[(1, 1, 5.0), (0, 1, 5.0)]);
+    assert_eq!(
+        resolve(floor, -1),
+        vec![((at(1.5, 0.5), step(1.0, 0.0)), -1)]
+    );
+}
+
+#[rstest]
+#[case::low_allowance_blocks_one_block(0.5, 2.0, (at(0.5, 0.5), step(0.0, 0.0)))]
+#[case::high_allowance_climbs_cliff(4.0, 5.0, (at(1.5, 1.5), step(1.0, 1.0)))]
+fn step_height_follows_config(
+    #[case] max_step_height: f64,
+    #[case] raised: f64,
+    #[case] expected: Move,
+) {
+    let config = PhysicsConfig {
+        max_step_height: max_step_height.into(),
+        ..PhysicsConfig::default()
+    };
+    let floor = terrain(&[(1, 1, raised), (1, 0, raised), (0, 1, raised)]);
+    assert_eq!(resolve_with(config, floor, 1), vec![(expected, 1)]);
+}
@@ -0,0 +1,227 @@
+//! Horizontal wall collision against block terrain.
+//!
+//! These helpers resolve one-tick moves against the floor grid so entities
+//! cannot walk into cells whose floor rises more than
+//! [`PhysicsConfig::max_step_height`] above them. Blocked moves slide along whichever axis remains open, or are rejected
+//! outright when neither does.
+
+use dbsp::{typed_batch::OrdZSet, RootCircuit, Stream};
+use ordered_float::OrderedFloat;
+
+use crate::numeric::floor_to_i32;
+use crate::PhysicsConfig;
+
+use crate::dbsp_circuit::streams::config::with_physics_config;
+use crate::dbsp_circuit::streams::floor::supporting_floor;
+use crate::dbsp_circuit::{FloorHeightAt, Position, Velocity};
+
+/// Candidate destinations probed for each move, in order of preference.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Candidate {
+    /// The full move along both axes.
+    Full,
+    /// Only the X component of the move.
+    SlideX,
+    /// Only the Y component of the move.
+    SlideY,
+}
+
+crate::dbsp_copy_record! {
+    /// A proposed move annotated with which candidate destinations are walls.
+    struct WallProbe {
+        /// Position the entity holds before moving.
+        origin: Position,
+        /// Displacement the entity attempts this tick.
+        step: Velocity,
+        /// Highest floor rise the entity can step onto.
+        max_step: OrderedFloat<f64>,
+        /// Whether the full move runs into a wall.
+        full_blocked: bool,
+        /// Whether the X-only slide runs into a wall.
+        slide_x_blocked: bool,
+        /// Whether the Y-only slide runs into a wall.
+        slide_y_blocked: bool,
+    }
+}
+
+impl WallProbe {
+    const fn new(origin: Position, step: Velocity, max_step: OrderedFloat<f64>) -> Self {
+        Self {
+            origin,
+            step,
+            max_step,
+            full_blocked: false,
+            slide_x_blocked: false,
+            slide_y_blocked: false,
+        }
+    }
+
+    fn target_x(&self) -> OrderedFloat<f64> {
+        OrderedFloat(self.origin.x.into_inner() + self.step.vx.into_inner())
+    }
+
+    fn target_y(&self) -> OrderedFloat<f64> {
+        OrderedFloat(self.origin.y.into_inner() + self.step.vy.into_inner())
+    }
+
+    /// Coordinates the given candidate would move the entity to.
+    fn point(&self, candidate: Candidate) -> (OrderedFloat<f64>, OrderedFloat<f64>) {
+        match candidate {
+            Candidate::Full => (self.target_x(), self.target_y()),
+            Candidate::SlideX => (self.target_x(), self.origin.y),
+            Candidate::SlideY => (self.origin.x, self.target_y()),
+        }
+    }
+
+    /// Grid cell the given candidate would move the entity into.
+    fn cell(&self, candidate: Candidate) -> (i32, i32) {
+        let (x, y) = self.point(candidate);
+        (floor_to_i32(x), floor_to_i32(y))
+    }
+
+    const fn with_blocked(mut self, candidate: Candidate, blocked: bool) -> Self {
+        match candidate {
+            Candidate::Full => self.full_blocked = blocked,
+            Candidate::SlideX => self.slide_x_blocked = blocked,
+            Candidate::SlideY => self.slide_y_blocked = blocked,
+        }
+        self
+    }
+
+    /// Picks the most preferred open candidate and zeroes the step along any
+    /// axis it gives up.
+    ///
+    /// When both slides are blocked the move is rejected even if the full
+    /// destination is open, so entities cannot slip diagonally between two
+    /// walls meeting at a corner.
+    fn resolve(&self) -> (Position, Velocity) {
+        let cornered = self.slide_x_blocked && self.slide_y_blocked;
+        let keep_x = !cornered && (!self.full_blocked || !self.slide_x_blocked);
+        let keep_y = !cornered && (!self.full_blocked || self.slide_x_blocked);
+        let zero = OrderedFloat(0.0);
+        let step = Velocity {
+            vx: if keep_x { self.step.vx } else { zero },
+            vy: if keep_y { self.step.vy } else { zero },
+            ..self.step
+        };
+        let position = Position {
+            entity: self.origin.entity,
+            x: if keep_x {
+                self.target_x()
+            } else {
+                self.origin.x
+            },
+            y: if keep_y {
+                self.target_y()
+            } else {
+                self.origin.y
+            },
+            z: OrderedFloat(self.origin.z.into_inner() + self.step.vz.into_inner()),
+        };
+        (position, step)
+    }
+
+    /// Returns `true` when `z_floor` rises too far above the origin to step
+    /// onto.
+    ///
+    /// Equality with the allowance counts as a step, so with the default
+    /// allowance a unit standing on a block can climb onto a neighbour exactly
+    /// one block higher.
+    fn is_wall(&self, z_floor: OrderedFloat<f64>) -> bool {
+        z_floor.into_inner() > self.origin.z.into_inner() + self.max_step.into_inner()
+    }
+}
+
+/// Samples the floor under one candidate destination and records whether it
+/// is a wall.
+///
+/// The floor is the destination surface whose band holds the entity's height,
+/// so a unit walks under a bridge rather than into its deck. Destinations with
+/// no floor beneath the entity are never walls.
+fn probe_candidate(
+    probes: &Stream<RootCircuit, OrdZSet<WallProbe>>,
+    floors: &Stream<RootCircuit, OrdZSet<FloorHeightAt>>,
+    candidate: Candidate,
+) -> Stream<RootCircuit, OrdZSet<WallProbe>> {
+    supporting_floor(probes, floors, move |p| (p.cell(candidate), p.origin.z)).map(
+        move |&(p, floor)| {
+            floor.map_or(p, |fh| {
+                let (x, y) = p.point(candidate);
+                p.with_blocked(candidate, p.is_wall(fh.sample(x, y)))
+            })
+        },
+    )
+}
+
+/// Resolves one-tick moves against terrain walls.
+///
+/// Each `moves` record pairs an entity's position before the move with the
+/// displacement it attempts this tick (dt = 1). A destination whose floor
+/// surface lies more than [`PhysicsConfig::max_step_height`] above the origin
+/// height is a
+/// wall: the move then slides along the X axis, failing that along the Y
+/// axis, and otherwise keeps the origin `(x, y)`. A move whose X and Y slides
+/// are both blocked also keeps the origin, even when the full destination is
+/// open. Destinations with no floor beneath the entity are never walls.
+///
+/// The output pairs each resolved position with the displacement actually
+/// taken, so the component along any blocked axis is zero. Vertical motion is
+/// applied unchanged, leaving floor snapping to the caller. Record weights pass
+/// through untouched, so retractions resolve to retractions.
+///
+/// # Examples
+/// ```rust,no_run
+/// # use anyhow::Result;
+/// # use dbsp::{operator::Generator, Circuit, RootCircuit};
+/// # use lille::dbsp_circuit::{wall_collision_stream, FloorHeightAt, Position, Velocity};
+/// # use lille::PhysicsConfig;
+/// # fn main() -> Result<()> {
+/// let (mut circuit, (move_in, floor_in, mut resolved_out)) =
+///     RootCircuit::build(|circuit| {
+///         let (moves, move_in) = circuit.add_input_zset::<(Position, Velocity)>();
+///         let (floor, floor_in) = circuit.add_input_zset::<FloorHeightAt>();
+///         let config = circuit.add_source(Generator::new(PhysicsConfig::default));
+///         let resolved = wall_collision_stream(&moves, &floor, &config).output();
+///         Ok((move_in, floor_in, resolved))
+///     })?;
+///
+/// let origin = Position { entity: 1, x: 0.5.into(), y: 0.5.into(), z: 1.0.into() };
+/// let step = Velocity { entity: 1, vx: 1.0.into(), vy: 0.0.into(), vz: 0.0.into() };
+/// let flat = |x, z: f64| FloorHeightAt {
+///     x,
+///     y: 0,
+///     z: z.into(),
+///     grad_x: 0.0.into(),
+///     grad_y: 0.0.into(),
+///     base: 0.0.into(),
+///     ceiling: None,
+/// };
+/// floor_in.push(flat(0, 1.0), 1);
+/// floor_in.push(flat(1, 5.0), 1);
+/// move_in.push((origin, step), 1);
+/// circuit.step()?;
+///
+/// let resolved: Vec<Position> = resolved_out
+///     .consolidate()
+///     .iter()
+///     .map(|((position, _), (), _)| position)
+///     .collect();
+/// assert_eq!(resolved, vec![origin], "the wall rejects the move");
+/// # Ok(())
+/// # }
+/// ```
+#[must_use]
+pub fn wall_collision_stream(
+    moves: &Stream<RootCircuit, OrdZSet<(Position, Velocity)>>,
+    floor_height: &Stream<RootCircuit, OrdZSet<FloorHeightAt>>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
+) -> Stream<RootCircuit, OrdZSet<(Position, Velocity)>> {
+    let unprobed = with_physics_config(moves, config)
+        .map(|&((origin, step), params)| WallProbe::new(origin, step, params.max_step_height));
+    [Candidate::Full, Candidate::SlideX, Candidate::SlideY]
+        .into_iter()
+        .fold(unprobed, |probes, candidate| {
+            probe_candidate(&probes, floor_height, candidate)
+        })
+        .map(WallProbe::resolve)
+}
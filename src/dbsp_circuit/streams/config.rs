@@ -0,0 +1,46 @@
+//! Physics configuration streams.
+//!
+//! The circuit receives [`PhysicsConfig`] as a per-tick input value, set on
+//! every worker before each step so `apply`-based operators see the same
+//! configuration everywhere. The helper below pairs records with that value on
+//! the worker that holds them, so a configuration change retracts results
+//! derived from the previous values without gathering the records on one
+//! worker.
+
+use dbsp::utils::Tup2;
+use dbsp::{typed_batch::OrdZSet, DBData, RootCircuit, Stream};
+
+use crate::PhysicsConfig;
+
+/// Pairs each record of `stream` with the configuration in force on the tick.
+///
+/// Every worker pairs its own records with its copy of the configuration, so
+/// the records stay spread across workers rather than being sharded onto one
+/// as a join on a unit key would. When the configuration changes, every record
+/// seen so far is retracted with the old values and emitted again with the new
+/// ones, so results derived from the pairs follow the change.
+pub(crate) fn with_physics_config<T: DBData>(
+    stream: &Stream<RootCircuit, OrdZSet<T>>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
+) -> Stream<RootCircuit, OrdZSet<(T, PhysicsConfig)>> {
+    let mut last = None;
+    let changes = config.apply_mut(move |params: &PhysicsConfig| (last.replace(*params), *params));
+    stream.apply3(
+        &stream.integrate().delay(),
+        &changes,
+        |delta, seen, change| {
+            let (previous, current) = *change;
+            let mut tuples: Vec<_> = delta
+                .iter()
+                .map(|(record, (), weight)| Tup2(Tup2((record, current), ()), weight))
+                .collect();
+            if let Some(old) = previous.filter(|old| *old != current) {
+                for (record, (), weight) in seen.iter() {
+                    tuples.push(Tup2(Tup2((record.clone(), old), ()), -weight));
+                    tuples.push(Tup2(Tup2((record, current), ()), weight));
+                }
+            }
+            OrdZSet::from_tuples((), tuples)
+        },
+    )
+}
@@ -1,9 +1,14 @@
 //! Floor aggregation and height derivation streams.
 //!
-//! These helpers process block records to compute discrete floor heights used in
-//! movement and collision calculations.
+//! These helpers process block records to compute the floor surfaces used in
+//! movement and collision calculations, including stacked surfaces where
+//! bridges and overhangs leave open space beneath solid blocks.
 
-use dbsp::{operator::Max, typed_batch::OrdZSet, RootCircuit, Stream};
+use dbsp::{
+    operator::{Max, Min},
+    typed_batch::OrdZSet,
+    DBData, RootCircuit, Stream,
+};
 use ordered_float::OrderedFloat;
 
 use crate::components::{Block, BlockSlope};
@@ -66,12 +71,116 @@ pub fn highest_block_pair(
         })
 }
 
-/// Derives the floor height for each block, optionally applying slopes.
+crate::dbsp_copy_record! {
+    /// A solid vertical run of blocks within one grid cell.
+    struct BlockRun {
+        /// Grid X coordinate of the run.
+        x: i32,
+        /// Grid Y coordinate of the run.
+        y: i32,
+        /// Height of the lowest block in the run.
+        base: i32,
+        /// Height of the highest block in the run.
+        top: i32,
+        /// Identifier of the highest block, used to look up its slope.
+        top_id: i64,
+    }
+}
+
+/// Splits each column of blocks into solid vertical runs.
+///
+/// Blocks sharing a voxel collapse to the highest id, matching
+/// [`highest_block_pair`]. A block with no neighbour directly above tops a run
+/// and one with no neighbour directly below starts one; each top is paired
+/// with the highest start beneath it.
+fn block_runs(
+    blocks: &Stream<RootCircuit, OrdZSet<Block>>,
+) -> Stream<RootCircuit, OrdZSet<BlockRun>> {
+    let voxels = blocks.map_index(|b| ((b.x, b.y, b.z), b.id)).aggregate(Max);
+    let occupied = voxels.map_index(|(&voxel, _)| (voxel, ()));
+    let tops = voxels
+        .map_index(|(&(x, y, z), &id)| ((x, y, z.saturating_add(1)), (z, id)))
+        .antijoin(&occupied)
+        .map_index(|(&(x, y, _), &top)| ((x, y), top));
+    let starts = voxels
+        .map_index(|(&(x, y, z), _)| ((x, y, z.saturating_sub(1)), z))
+        .antijoin(&occupied)
+        .map_index(|(&(x, y, _), &z)| ((x, y), z));
+
+    tops.join(&starts, |&(x, y), &(top, top_id), &start| {
+        ((x, y, top, top_id), start)
+    })
+    .filter(|&((_, _, top, _), start)| start <= top)
+    .map_index(|&(run, start)| (run, start))
+    .aggregate(Max)
+    .map(|(&(x, y, top, top_id), &base)| BlockRun {
+        x,
+        y,
+        base,
+        top,
+        top_id,
+    })
+}
+
+/// Caps each floor surface at the underside of the next surface above it.
+///
+/// The ceiling of a surface becomes the lowest `base` among the surfaces of
+/// its cell that start above its own, so the support bands of a column never
+/// overlap, whichever streams contributed the surfaces.
+fn stacked(
+    surfaces: &Stream<RootCircuit, OrdZSet<FloorHeightAt>>,
+) -> Stream<RootCircuit, OrdZSet<FloorHeightAt>> {
+    let by_cell = surfaces.map_index(|fh| ((fh.x, fh.y), *fh));
+    let ceilings = by_cell
+        .join(&by_cell, |_, below, above| (*below, above.base))
+        .filter(|(below, base)| *base > below.base)
+        .map_index(|&(below, base)| (below, base))
+        .aggregate(Min);
+
+    surfaces
+        .map_index(|fh| (*fh, ()))
+        .outer_join(
+            &ceilings,
+            |fh, (), &ceiling| {
+                Some(FloorHeightAt {
+                    ceiling: Some(ceiling),
+                    ..*fh
+                })
+            },
+            |fh, ()| Some(*fh),
+            |_, _| None,
+        )
+        .flat_map(|fh| *fh)
+}
+
+/// Derives the uncapped floor surface on top of every run of terrain blocks.
+fn terrain_surfaces(
+    blocks: &Stream<RootCircuit, OrdZSet<Block>>,
+    slopes: &Stream<RootCircuit, OrdZSet<BlockSlope>>,
+) -> Stream<RootCircuit, OrdZSet<FloorHeightAt>> {
+    block_runs(blocks)
+        .map_index(|run| (run.top_id, *run))
+        .outer_join(
+            &slopes.map_index(|bs| (bs.block_id, (bs.grad_x, bs.grad_y))),
+            |_, run, &(grad_x, grad_y)| Some(surface(run, grad_x, grad_y)),
+            |_, run| Some(surface(run, OrderedFloat(0.0), OrderedFloat(0.0))),
+            |_, _| None,
+        )
+        // Convert `Option<FloorHeightAt>` from the outer join, discarding
+        // unmatched slope records.
+        .flat_map(|fh| (*fh).into_iter())
+}
+
+/// Derives the floor surfaces of each grid cell, optionally applying slopes.
 ///
-/// The stream joins the highest block id at a grid cell with any matching
-/// [`BlockSlope`] record. When slope data is present the returned
-/// [`FloorHeightAt`] accounts for the block's gradient, producing a smooth
-/// surface. Missing slope data falls back to a flat top.
+/// Every column is split into solid vertical runs of blocks, and each run
+/// yields one [`FloorHeightAt`] on top of its highest block. The record
+/// carries the run's base and the underside of the next run above, so a cell
+/// with a bridge over open ground exposes both surfaces and entities can stand
+/// on either. The top block of each run is joined with any matching
+/// [`BlockSlope`] record: the surface then holds the height at the cell centre
+/// together with the block's gradients, so [`FloorHeightAt::sample`] can
+/// follow it across the cell. Missing slope data falls back to a flat top.
 ///
 /// # Examples
 /// ```rust,no_run
@@ -79,19 +188,18 @@ pub fn highest_block_pair(
 /// # fn demo() -> Result<()> {
 /// use dbsp::RootCircuit;
 /// use lille::components::{Block, BlockSlope};
-/// use lille::dbsp_circuit::{floor_height_stream, highest_block_pair};
+/// use lille::dbsp_circuit::floor_height_stream;
 /// use ordered_float::OrderedFloat;
 ///
 /// let (mut circuit, (block_in, slope_in, mut floor_out)) = RootCircuit::build(|circuit| {
 ///     let (blocks_stream, blocks_input) = circuit.add_input_zset::<Block>();
 ///     let (slopes_stream, slopes_input) = circuit.add_input_zset::<BlockSlope>();
-///     let highest = highest_block_pair(&blocks_stream);
-///     let floor = floor_height_stream(&highest, &slopes_stream).output();
+///     let floor = floor_height_stream(&blocks_stream, &slopes_stream).output();
 ///     Ok((blocks_input, slopes_input, floor))
 /// })?;
 ///
-/// block_in.push(Block { id: 10, x: 0, y: 0, z: 4 }, 1);
-/// block_in.push(Block { id: 11, x: 0, y: 0, z: 5 }, 1);
+/// block_in.push(Block { id: 10, x: 0, y: 0, z: 0 }, 1);
+/// block_in.push(Block { id: 11, x: 0, y: 0, z: 4 }, 1);
 /// block_in.push(Block { id: 12, x: 1, y: 0, z: 3 }, 1);
 ///
 /// slope_in.push(
@@ -110,45 +218,85 @@ pub fn highest_block_pair(
 ///     .iter()
 ///     .map(|(height, (), _)| height.clone())
 ///     .collect();
-/// assert_eq!(heights.len(), 2);
-/// let origin_height = heights
+/// assert_eq!(heights.len(), 3, "the bridge cell has two surfaces");
+/// let bridge = heights
 ///     .iter()
-///     .find(|h| h.x == 0 && h.y == 0)
-///     .expect("origin cell");
-/// assert!(origin_height.z.into_inner() > 5.0, "slope raises the floor height");
+///     .find(|h| h.x == 0 && h.y == 0 && h.ceiling.is_none())
+///     .expect("bridge deck");
+/// assert!(bridge.z.into_inner() > 5.0, "slope raises the floor height");
 /// # Ok(())
 /// # }
 /// ```
 #[must_use]
 pub fn floor_height_stream(
-    highest_pair: &Stream<RootCircuit, OrdZSet<(HighestBlockAt, i64)>>,
+    blocks: &Stream<RootCircuit, OrdZSet<Block>>,
     slopes: &Stream<RootCircuit, OrdZSet<BlockSlope>>,
 ) -> Stream<RootCircuit, OrdZSet<FloorHeightAt>> {
-    highest_pair
-        .map_index(|(hb, id)| (*id, (hb.x, hb.y, hb.z)))
-        .outer_join(
-            &slopes.map_index(|bs| (bs.block_id, (bs.grad_x, bs.grad_y))),
-            |_, &(x, y, z), &(gx, gy)| {
-                let base = f64::from(z) + BLOCK_TOP_OFFSET;
-                let gradient = BLOCK_CENTRE_OFFSET * (gx.into_inner() + gy.into_inner());
-                Some(FloorHeightAt {
-                    x,
-                    y,
-                    z: OrderedFloat(base + gradient),
-                })
-            },
-            |_, &(x, y, z)| {
-                Some(FloorHeightAt {
-                    x,
-                    y,
-                    z: OrderedFloat(f64::from(z) + BLOCK_TOP_OFFSET),
-                })
-            },
-            |_, _| None,
+    stacked(&terrain_surfaces(blocks, slopes))
+}
+
+/// Derives the floor surfaces of the terrain together with `extra` surfaces,
+/// such as those of moving platforms.
+///
+/// All surfaces of a cell are stacked as in [`floor_height_stream`]: each one
+/// is capped at the base of the next, so an entity below a platform hanging
+/// over the ground keeps standing on the ground while one on the platform
+/// stands on its deck.
+#[must_use]
+pub fn layered_floor_height_stream(
+    blocks: &Stream<RootCircuit, OrdZSet<Block>>,
+    slopes: &Stream<RootCircuit, OrdZSet<BlockSlope>>,
+    extra: &Stream<RootCircuit, OrdZSet<FloorHeightAt>>,
+) -> Stream<RootCircuit, OrdZSet<FloorHeightAt>> {
+    stacked(&terrain_surfaces(blocks, slopes).plus(extra))
+}
+
+/// Builds the floor surface on top of `run` with the given gradients.
+fn surface(run: &BlockRun, grad_x: OrderedFloat<f64>, grad_y: OrderedFloat<f64>) -> FloorHeightAt {
+    let top = f64::from(run.top) + BLOCK_TOP_OFFSET;
+    let gradient = BLOCK_CENTRE_OFFSET * (grad_x.into_inner() + grad_y.into_inner());
+    FloorHeightAt {
+        x: run.x,
+        y: run.y,
+        z: OrderedFloat(top + gradient),
+        grad_x,
+        grad_y,
+        base: OrderedFloat(f64::from(run.base)),
+        ceiling: None,
+    }
+}
+
+/// Pairs each item with the floor surface that supports it, if any.
+///
+/// `locate` returns the grid cell and height to test for an item. At most one
+/// surface per cell [supports](FloorHeightAt::supports) a given height, so
+/// every item appears exactly once: with its surface, or with `None` when it
+/// is below every run in its cell or the cell has no floor at all. Unsupported
+/// items are derived by subtraction rather than an antijoin, so record weights
+/// pass through unchanged and retractions stay retractions.
+pub(crate) fn supporting_floor<T, F>(
+    items: &Stream<RootCircuit, OrdZSet<T>>,
+    floor_height: &Stream<RootCircuit, OrdZSet<FloorHeightAt>>,
+    locate: F,
+) -> Stream<RootCircuit, OrdZSet<(T, Option<FloorHeightAt>)>>
+where
+    T: DBData,
+    F: Fn(&T) -> ((i32, i32), OrderedFloat<f64>) + Clone + 'static,
+{
+    let supported = items
+        .map_index(move |item| {
+            let (cell, z) = locate(item);
+            (cell, (item.clone(), z))
+        })
+        .join(
+            &floor_height.map_index(|fh| ((fh.x, fh.y), *fh)),
+            |_, (item, z), fh| (item.clone(), fh.supports(*z).then_some(*fh)),
         )
-        // Convert `Option<FloorHeightAt>` from the outer join, discarding
-        // unmatched slope records.
-        .flat_map(|fh| (*fh).into_iter())
+        .filter(|(_, fh)| fh.is_some());
+    let unsupported = items
+        .map(|item| (item.clone(), None))
+        .minus(&supported.map(|(item, _)| (item.clone(), None)));
+    supported.plus(&unsupported)
 }
 
 #[cfg(test)]
@@ -4,6 +4,7 @@ use crate::components::{Block, BlockSlope};
 use crate::dbsp_circuit::step_named;
 use crate::dbsp_circuit::streams::test_utils::{block, new_circuit, slope};
 use crate::dbsp_circuit::{FloorHeightAt, HighestBlockAt};
+use approx::assert_relative_eq;
 use rstest::rstest;
 
 fn hb(x: i32, y: i32, z: i32) -> HighestBlockAt {
@@ -11,10 +12,18 @@ fn hb(x: i32, y: i32, z: i32) -> HighestBlockAt {
 }
 
 fn fh(x: i32, y: i32, height: f64) -> FloorHeightAt {
+    sloped_fh(x, y, height, (0.0, 0.0))
+}
+
+fn sloped_fh(x: i32, y: i32, height: f64, (grad_x, grad_y): (f64, f64)) -> FloorHeightAt {
     FloorHeightAt {
         x,
         y,
         z: height.into(),
+        grad_x: grad_x.into(),
+        grad_y: grad_y.into(),
+        base: 0.0.into(),
+        ceiling: None,
     }
 }
 
@@ -66,13 +75,13 @@ fn highest_block_cases(#[case] blocks: Vec<Block>, #[case] expected: Vec<Highest
 
 #[rstest]
 #[case::block_only(vec![block(1, (0, 0, 0))], vec![], vec![fh(0,0,1.0)])]
-#[case::block_with_slope(vec![block(1, (0, 0, 0))], vec![slope(1, (1.0, 0.0))], vec![fh(0,0,1.5)])]
+#[case::block_with_slope(vec![block(1, (0, 0, 0))], vec![slope(1, (1.0, 0.0))], vec![sloped_fh(0,0,1.5,(1.0,0.0))])]
 #[case::highest_block_wins(vec![block(1, (0, 0, 0)), block(2, (0, 0, 1))], vec![], vec![fh(0,0,2.0)])] // highest block wins
-#[case::negative_slope(vec![block(1, (0, 0, 0))], vec![slope(1, (-1.0, 0.0))], vec![fh(0,0,0.5)])] // negative slope
+#[case::negative_slope(vec![block(1, (0, 0, 0))], vec![slope(1, (-1.0, 0.0))], vec![sloped_fh(0,0,0.5,(-1.0,0.0))])] // negative slope
 #[case::zero_slope(vec![block(1, (0, 0, 0))], vec![slope(1, (0.0, 0.0))], vec![fh(0,0,1.0)])] // zero slope
-#[case::negative_coords(vec![block(1, (-1, -1, 0))], vec![slope(1, (1.0, 1.0))], vec![fh(-1,-1,2.0)])] // negative coordinates
-#[case::large_gradients(vec![block(1, (0, 0, 0))], vec![slope(1, (100.0, 100.0))], vec![fh(0,0,101.0)])] // large gradients
-#[case::multiple_slopes(vec![block(1, (0, 0, 0)), block(2, (0, 0, 1))], vec![slope(1, (1.0, 0.0)), slope(2, (0.0, 1.0))], vec![fh(0,0,2.5)])] // multiple slopes, highest wins
+#[case::negative_coords(vec![block(1, (-1, -1, 0))], vec![slope(1, (1.0, 1.0))], vec![sloped_fh(-1,-1,2.0,(1.0,1.0))])] // negative coordinates
+#[case::large_gradients(vec![block(1, (0, 0, 0))], vec![slope(1, (100.0, 100.0))], vec![sloped_fh(0,0,101.0,(100.0,100.0))])] // large gradients
+#[case::multiple_slopes(vec![block(1, (0, 0, 0)), block(2, (0, 0, 1))], vec![slope(1, (1.0, 0.0)), slope(2, (0.0, 1.0))], vec![sloped_fh(0,0,2.5,(0.0,1.0))])] // multiple slopes, highest wins
 fn floor_height_cases(
     #[case] blocks: Vec<Block>,
     #[case] slopes: Vec<BlockSlope>,
@@ -98,6 +107,70 @@ fn floor_height_cases(
     assert_eq!(vals, exp);
 }
 
+fn layer(height: f64, base: f64, ceiling: Option<f64>) -> FloorHeightAt {
+    FloorHeightAt {
+        base: base.into(),
+        ceiling: ceiling.map(Into::into),
+        ..fh(0, 0, height)
+    }
+}
+
+#[rstest]
+#[case::bridge(vec![block(1, (0, 0, 0)), block(2, (0, 0, 3))], vec![layer(1.0, 0.0, Some(3.0)), layer(4.0, 3.0, None)])]
+#[case::tunnel(
+    vec![block(1, (0, 0, 0)), block(2, (0, 0, 1)), block(3, (0, 0, 4)), block(4, (0, 0, 5))],
+    vec![layer(2.0, 0.0, Some(4.0)), layer(6.0, 4.0, None)],
+)]
+#[case::three_levels(
+    vec![block(1, (0, 0, -2)), block(2, (0, 0, 2)), block(3, (0, 0, 6))],
+    vec![layer(-1.0, -2.0, Some(2.0)), layer(3.0, 2.0, Some(6.0)), layer(7.0, 6.0, None)],
+)]
+#[case::floating_run(vec![block(1, (0, 0, 5)), block(2, (0, 0, 6))], vec![layer(7.0, 5.0, None)])]
+fn stacked_runs_expose_each_surface(
+    #[case] blocks: Vec<Block>,
+    #[case] expected: Vec<FloorHeightAt>,
+) {
+    let mut circuit = new_circuit().expect("failed to build DBSP circuit");
+    for b in blocks {
+        circuit.block_in().push(b, 1);
+    }
+    step_named(&mut circuit, "stacked_runs_expose_each_surface");
+    let mut vals: Vec<FloorHeightAt> = circuit
+        .floor_height_out()
+        .consolidate()
+        .iter()
+        .map(|(fh, (), _timestamp)| fh)
+        .collect();
+    vals.sort_by_key(|h| h.z);
+    assert_eq!(vals, expected);
+}
+
+#[test]
+fn removing_the_gap_merges_runs() {
+    let mut circuit = new_circuit().expect("failed to build DBSP circuit");
+    circuit.block_in().push(block(1, (0, 0, 0)), 1);
+    circuit.block_in().push(block(2, (0, 0, 2)), 1);
+    step_named(&mut circuit, "removing_the_gap_merges_runs: split");
+    circuit.block_in().push(block(3, (0, 0, 1)), 1);
+    step_named(&mut circuit, "removing_the_gap_merges_runs: filled");
+
+    let mut deltas: Vec<(FloorHeightAt, i64)> = circuit
+        .floor_height_out()
+        .consolidate()
+        .iter()
+        .map(|(fh, (), weight)| (fh, weight))
+        .collect();
+    deltas.sort_by_key(|(h, _)| h.z);
+    assert_eq!(
+        deltas,
+        vec![
+            (layer(1.0, 0.0, Some(2.0)), -1),
+            (layer(3.0, 0.0, None), 1),
+            (layer(3.0, 2.0, None), -1),
+        ]
+    );
+}
+
 #[test]
 fn unmatched_slope_is_ignored() {
     let mut circuit = new_circuit().expect("failed to build DBSP circuit");
@@ -132,3 +205,24 @@ fn slope_without_block_yields_no_height() {
 
     assert!(vals.is_empty());
 }
+
+#[rstest]
+#[case::low_edge(0.0, 0.5, 1.0)]
+#[case::quarter(0.25, 0.5, 1.25)]
+#[case::centre(0.5, 0.5, 1.5)]
+#[case::high_edge(0.9, 0.5, 1.9)]
+#[case::negative_cell(-0.75, 0.5, 1.25)]
+#[case::cross_gradient(0.5, 0.0, 1.75)]
+fn sample_follows_gradients(#[case] x: f64, #[case] y: f64, #[case] expected: f64) {
+    let cell_x = if x < 0.0 { -1 } else { 0 };
+    let ramp = sloped_fh(cell_x, 0, 1.5, (1.0, -0.5));
+    assert_relative_eq!(ramp.sample(x.into(), y.into()).into_inner(), expected);
+}
+
+#[test]
+fn flat_floor_samples_constant_height() {
+    let flat = fh(3, 4, 2.0);
+    for (x, y) in [(3.0, 4.0), (3.5, 4.5), (3.99, 4.01)] {
+        assert_relative_eq!(flat.sample(x.into(), y.into()).into_inner(), 2.0);
+    }
+}
@@ -129,9 +129,10 @@ struct HealthAggregate {
 fn signed_amount(event: &DamageEvent) -> i32 {
     match event.source {
         DamageSource::Script => i32::from(event.amount),
-        DamageSource::External | DamageSource::Fall | DamageSource::Other { .. } => {
-            -i32::from(event.amount)
-        }
+        DamageSource::External
+        | DamageSource::Fall
+        | DamageSource::OutOfBounds
+        | DamageSource::Other { .. } => -i32::from(event.amount),
     }
 }
 
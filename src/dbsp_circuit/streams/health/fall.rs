@@ -1,11 +1,13 @@
 //! Fall damage derivation streams.
 //!
 //! Detects landing transitions and emits [`DamageEvent`] records that apply
-//! fall damage entirely within the DBSP circuit.
+//! fall damage entirely within the DBSP circuit. Landings clamped by the swept
+//! fall integration are damaged on the tick they happen; other transitions
+//! from unsupported to standing are detected on the following tick.
 
 use crate::dbsp_circuit::{DamageEvent, DamageSource, PositionFloor, Tick, Velocity};
 use crate::numeric::floor_to_u16;
-use crate::{FALL_DAMAGE_SCALE, LANDING_COOLDOWN_TICKS, SAFE_LANDING_SPEED, TERMINAL_VELOCITY};
+use crate::PhysicsConfig;
 use dbsp::utils::Tup2;
 use dbsp::{typed_batch::OrdZSet, RootCircuit, Stream};
 use ordered_float::OrderedFloat;
@@ -13,27 +15,55 @@ use ordered_float::OrderedFloat;
 fn detect_landings(
     standing: &Stream<RootCircuit, OrdZSet<PositionFloor>>,
     unsupported: &Stream<RootCircuit, OrdZSet<PositionFloor>>,
+    swept_landings: &Stream<RootCircuit, OrdZSet<Velocity>>,
 ) -> Stream<RootCircuit, OrdZSet<i64>> {
     let standing_entities = standing.map(|pf| pf.position.entity);
-    let prev_unsupported = unsupported.map(|pf| pf.position.entity).delay();
+    let swept_entities = swept_landings.map(|vel| vel.entity);
+    // Entities whose landing was already counted by the sweep must not land
+    // a second time when they show up as standing on the next tick.
+    let prev_airborne = unsupported
+        .map_index(|pf| (pf.position.entity, ()))
+        .antijoin(&swept_entities.map_index(|entity| (*entity, ())))
+        .map(|(entity, ())| *entity)
+        .delay();
 
-    prev_unsupported.map_index(|entity| (*entity, ())).join(
-        &standing_entities.map_index(|entity| (*entity, ())),
-        |entity, (), ()| *entity,
-    )
+    prev_airborne
+        .map_index(|entity| (*entity, ()))
+        .join(
+            &standing_entities.map_index(|entity| (*entity, ())),
+            |entity, (), ()| *entity,
+        )
+        .plus(&swept_entities)
 }
 
 fn apply_landing_cooldown(
     landings: &Stream<RootCircuit, OrdZSet<i64>>,
+    ticks: &Stream<RootCircuit, Tick>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
 ) -> Stream<RootCircuit, OrdZSet<i64>> {
-    let mut cooldown_end = landings.clone();
-    for _ in 0..LANDING_COOLDOWN_TICKS {
-        cooldown_end = cooldown_end.delay();
-    }
-
-    let cooldown_updates = landings.clone().plus(&cooldown_end.neg());
-    let active_cooldown = cooldown_updates.integrate();
-    let cooling_entities = active_cooldown.delay().map_index(|entity| (*entity, ()));
+    // Stamping each landing with its tick lets the cooldown window follow the
+    // configured length and tick rate, which a fixed chain of delays could
+    // not.
+    let stamped = landings.apply2(ticks, |landed, tick| {
+        let tuples = landed
+            .iter()
+            .map(|(entity, (), weight)| Tup2(Tup2((entity, *tick), ()), weight))
+            .collect();
+        OrdZSet::from_tuples((), tuples)
+    });
+    let cooling_entities = stamped
+        .integrate()
+        .delay()
+        .apply3(ticks, config, |history, tick, params| {
+            let window_start = tick.saturating_sub(params.landing_cooldown_ticks());
+            let tuples = history
+                .iter()
+                .filter(|((_, landed_at), (), _)| *landed_at >= window_start)
+                .map(|((entity, _), (), weight)| Tup2(Tup2(entity, ()), weight))
+                .collect();
+            OrdZSet::from_tuples((), tuples)
+        })
+        .map_index(|entity| (*entity, ()));
 
     landings
         .map_index(|entity| (*entity, ()))
@@ -41,16 +71,30 @@ fn apply_landing_cooldown(
         .map(|(entity, ())| *entity)
 }
 
+fn impact_velocities(
+    unsupported_velocities: &Stream<RootCircuit, OrdZSet<Velocity>>,
+    swept_landings: &Stream<RootCircuit, OrdZSet<Velocity>>,
+) -> Stream<RootCircuit, OrdZSet<Velocity>> {
+    // Swept landings carry their own impact velocity; every other landing
+    // uses the last velocity recorded while the entity was unsupported.
+    unsupported_velocities
+        .delay()
+        .map_index(|vel| (vel.entity, *vel))
+        .antijoin(&swept_landings.map_index(|vel| (vel.entity, ())))
+        .map(|(_, vel)| *vel)
+        .plus(swept_landings)
+}
+
 fn calculate_fall_damage(
     allowed_landings: &Stream<RootCircuit, OrdZSet<i64>>,
-    unsupported_velocities: &Stream<RootCircuit, OrdZSet<Velocity>>,
+    impact_velocities: &Stream<RootCircuit, OrdZSet<Velocity>>,
     ticks: &Stream<RootCircuit, Tick>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
 ) -> Stream<RootCircuit, OrdZSet<DamageEvent>> {
-    let prev_velocities = unsupported_velocities.delay();
     let landing_impacts = allowed_landings
         .map_index(|entity| (*entity, *entity))
         .join(
-            &prev_velocities.map_index(|vel| (vel.entity, vel.vz)),
+            &impact_velocities.map_index(|vel| (vel.entity, vel.vz)),
             |_entity, &landing_entity, &vz| (landing_entity, vz),
         );
 
@@ -61,7 +105,7 @@ fn calculate_fall_damage(
             .into_iter()
     });
 
-    downward_impacts.apply2(ticks, |impacts, tick| {
+    downward_impacts.apply3(ticks, config, |impacts, tick, params| {
         let mut tuples = Vec::new();
         for ((entity, speed), (), weight) in impacts.iter() {
             if weight == 0 {
@@ -71,12 +115,9 @@ fn calculate_fall_damage(
             let Ok(entity_id) = u64::try_from(entity) else {
                 continue;
             };
-            let clamped_speed = speed.into_inner().min(TERMINAL_VELOCITY);
-            let excess = clamped_speed - SAFE_LANDING_SPEED;
-            if excess <= 0.0 {
+            let Some(scaled) = params.fall_damage(speed.into_inner()) else {
                 continue;
-            }
-            let scaled = excess * FALL_DAMAGE_SCALE;
+            };
             let floored = scaled.min(f64::from(u16::MAX)).floor();
             let Some(damage) = floor_to_u16(floored) else {
                 // When the scaled damage escapes the `u16` range we treat the
@@ -100,13 +141,23 @@ fn calculate_fall_damage(
 
 /// Derives fall damage events from landing transitions.
 ///
+/// `swept_landings` holds the impact velocities of entities whose fall was
+/// clamped to the floor this tick, as produced by
+/// [`swept_fall_stream`](crate::dbsp_circuit::swept_fall_stream). Those
+/// landings are damaged immediately, while an entity that was unsupported on
+/// the previous tick and stands now is damaged using its last unsupported
+/// velocity.
+///
+/// Damage thresholds and the per-entity landing cooldown come from the
+/// [`PhysicsConfig`] in force on the tick of the landing.
+///
 /// # Examples
 /// ```rust,no_run
 /// use dbsp::{operator::Generator, Circuit, RootCircuit};
 /// use lille::dbsp_circuit::{
 ///     fall_damage_stream, DamageEvent, DamageSource, Position, PositionFloor, Tick, Velocity,
 /// };
-/// use lille::{FALL_DAMAGE_SCALE, SAFE_LANDING_SPEED, TERMINAL_VELOCITY};
+/// use lille::{PhysicsConfig, FALL_DAMAGE_SCALE, SAFE_LANDING_SPEED, TERMINAL_VELOCITY};
 /// use ordered_float::OrderedFloat;
 ///
 /// let (circuit, (standing_in, unsupported_in, velocity_in, fall_output)) =
@@ -114,6 +165,7 @@ fn calculate_fall_damage(
 ///         let (standing_stream, standing_in) = circuit.add_input_zset::<PositionFloor>();
 ///         let (unsupported_stream, unsupported_in) = circuit.add_input_zset::<PositionFloor>();
 ///         let (velocity_stream, velocity_in) = circuit.add_input_zset::<Velocity>();
+///         let (landing_stream, _landing_in) = circuit.add_input_zset::<Velocity>();
 ///         let ticks = circuit.add_source(Generator::new({
 ///             let mut tick: Tick = 0;
 ///             move || {
@@ -122,11 +174,14 @@ fn calculate_fall_damage(
 ///                 current
 ///             }
 ///         }));
+///         let config = circuit.add_source(Generator::new(PhysicsConfig::default));
 ///         let fall = fall_damage_stream(
 ///             &standing_stream,
 ///             &unsupported_stream,
 ///             &velocity_stream,
+///             &landing_stream,
 ///             &ticks,
+///             &config,
 ///         );
 ///         Ok((standing_in, unsupported_in, velocity_in, fall.output()))
 ///     })
@@ -184,13 +239,24 @@ fn calculate_fall_damage(
 /// assert_eq!(event.amount, expected_damage);
 /// assert_eq!(event.at_tick, 1);
 #[must_use]
+#[expect(
+    clippy::too_many_arguments,
+    reason = "Landing detection needs both floor partitions, both velocity sources, the tick and the physics configuration."
+)]
 pub fn fall_damage_stream(
     standing: &Stream<RootCircuit, OrdZSet<PositionFloor>>,
     unsupported: &Stream<RootCircuit, OrdZSet<PositionFloor>>,
     unsupported_velocities: &Stream<RootCircuit, OrdZSet<Velocity>>,
+    swept_landings: &Stream<RootCircuit, OrdZSet<Velocity>>,
     ticks: &Stream<RootCircuit, Tick>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
 ) -> Stream<RootCircuit, OrdZSet<DamageEvent>> {
-    let landings = detect_landings(standing, unsupported);
-    let allowed_landings = apply_landing_cooldown(&landings);
-    calculate_fall_damage(&allowed_landings, unsupported_velocities, ticks)
+    let landings = detect_landings(standing, unsupported, swept_landings);
+    let allowed_landings = apply_landing_cooldown(&landings, ticks, config);
+    calculate_fall_damage(
+        &allowed_landings,
+        &impact_velocities(unsupported_velocities, swept_landings),
+        ticks,
+        config,
+    )
 }
@@ -0,0 +1,108 @@
+//! Kill plane damage derivation streams.
+//!
+//! Entities falling through the void would otherwise descend forever. This
+//! stream emits a lethal [`DamageEvent`] on the tick an entity drops below
+//! [`PhysicsConfig::kill_plane_z`], letting the health pipeline flag the
+//! death.
+
+use crate::dbsp_circuit::{DamageEvent, DamageSource, Position, Tick, Velocity};
+use crate::PhysicsConfig;
+use dbsp::utils::Tup2;
+use dbsp::{typed_batch::OrdZSet, RootCircuit, Stream};
+use ordered_float::OrderedFloat;
+
+/// Returns `true` when one tick of falling from `z` at `vz` crosses the kill
+/// plane of `params`.
+fn crosses_kill_plane(z: f64, vz: f64, params: &PhysicsConfig) -> bool {
+    let plane = params.kill_plane_z.into_inner();
+    z >= plane && z + params.displacement(vz) < plane
+}
+
+/// Emits lethal damage for entities crossing the kill plane this tick.
+///
+/// `positions` holds the entities to check, typically those over the void,
+/// and `velocities` the velocities they integrate with this tick, over
+/// [`PhysicsConfig::delta_time`] seconds. An event is
+/// emitted only on the tick the entity passes from at or above the
+/// [`PhysicsConfig::kill_plane_z`] in force on the tick to below it, so an
+/// entity lingering beneath the plane is not damaged again. The event carries [`DamageSource::OutOfBounds`] and the
+/// maximum damage amount, which the health aggregation clamps to the entity's
+/// remaining hit points.
+///
+/// # Examples
+/// ```rust,no_run
+/// use dbsp::{operator::Generator, Circuit, RootCircuit};
+/// use lille::dbsp_circuit::{
+///     kill_plane_damage_stream, DamageEvent, DamageSource, Position, Tick, Velocity,
+/// };
+/// use lille::{PhysicsConfig, KILL_PLANE_Z};
+///
+/// let (circuit, (position_in, velocity_in, damage_out)) = RootCircuit::build(|circuit| {
+///     let (positions, position_in) = circuit.add_input_zset::<Position>();
+///     let (velocities, velocity_in) = circuit.add_input_zset::<Velocity>();
+///     let ticks = circuit.add_source(Generator::new({
+///         let mut tick: Tick = 0;
+///         move || {
+///             let current = tick;
+///             tick = tick.saturating_add(1);
+///             current
+///         }
+///     }));
+///     let config = circuit.add_source(Generator::new(PhysicsConfig::default));
+///     let damage = kill_plane_damage_stream(&positions, &velocities, &ticks, &config);
+///     Ok((position_in, velocity_in, damage.output()))
+/// })
+/// .expect("build kill plane stream");
+///
+/// position_in.push(
+///     Position { entity: 1, x: 0.0.into(), y: 0.0.into(), z: KILL_PLANE_Z.into() },
+///     1,
+/// );
+/// velocity_in.push(
+///     Velocity { entity: 1, vx: 0.0.into(), vy: 0.0.into(), vz: (-1.0).into() },
+///     1,
+/// );
+/// circuit.step().expect("dbsp step");
+///
+/// let events: Vec<DamageEvent> = damage_out
+///     .consolidate()
+///     .iter()
+///     .map(|(event, (), _)| event)
+///     .collect();
+/// assert_eq!(events.len(), 1);
+/// assert_eq!(events[0].source, DamageSource::OutOfBounds);
+/// ```
+#[must_use]
+pub fn kill_plane_damage_stream(
+    positions: &Stream<RootCircuit, OrdZSet<Position>>,
+    velocities: &Stream<RootCircuit, OrdZSet<Velocity>>,
+    ticks: &Stream<RootCircuit, Tick>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
+) -> Stream<RootCircuit, OrdZSet<DamageEvent>> {
+    let falls = positions.map_index(|p| (p.entity, p.z)).join(
+        &velocities.map_index(|v| (v.entity, v.vz)),
+        |&entity, &z, &vz| (entity, z, vz),
+    );
+
+    falls.apply3(ticks, config, |falling, tick, params| {
+        let mut tuples = Vec::new();
+        for ((entity, OrderedFloat(z), OrderedFloat(vz)), (), weight) in falling.iter() {
+            if weight == 0 || !crosses_kill_plane(z, vz, &params) {
+                continue;
+            }
+            debug_assert!(entity >= 0, "negative entity id {entity}");
+            let Ok(entity_id) = u64::try_from(entity) else {
+                continue;
+            };
+            let event = DamageEvent {
+                entity: entity_id,
+                amount: u16::MAX,
+                source: DamageSource::OutOfBounds,
+                at_tick: *tick,
+                seq: None,
+            };
+            tuples.push(Tup2(Tup2(event, ()), weight));
+        }
+        OrdZSet::from_tuples((), tuples)
+    })
+}
@@ -1,13 +1,15 @@
 //! Health aggregation streams.
 //!
 //! This module wires together domain-specific helpers that derive
-//! authoritative [`HealthDelta`] records and fall-damage events within the
-//! DBSP circuit.
+//! authoritative [`HealthDelta`] records, fall-damage events and kill plane
+//! deaths within the DBSP circuit.
 
 mod aggregate;
 mod fall;
+mod kill_plane;
 #[cfg(test)]
 mod tests;
 
 pub use aggregate::health_delta_stream;
 pub use fall::fall_damage_stream;
+pub use kill_plane::kill_plane_damage_stream;
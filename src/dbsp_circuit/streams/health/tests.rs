@@ -1,10 +1,13 @@
 //! Tests for the health stream pipelines.
 
-use super::fall_damage_stream;
+use super::{fall_damage_stream, kill_plane_damage_stream};
 use crate::dbsp_circuit::Position;
 use crate::dbsp_circuit::{DamageEvent, DamageSource, PositionFloor, Tick, Velocity};
 use crate::numeric::expect_u16;
-use crate::{FALL_DAMAGE_SCALE, LANDING_COOLDOWN_TICKS, SAFE_LANDING_SPEED, TERMINAL_VELOCITY};
+use crate::{
+    PhysicsConfig, DELTA_TIME, FALL_DAMAGE_SCALE, KILL_PLANE_Z, LANDING_COOLDOWN,
+    SAFE_LANDING_SPEED, TERMINAL_VELOCITY,
+};
 use dbsp::{operator::Generator, typed_batch::OrdZSet, Circuit, RootCircuit};
 use ordered_float::OrderedFloat;
 use rstest::rstest;
@@ -16,6 +19,7 @@ type FallDamageHarness = (
     dbsp::ZSetHandle<PositionFloor>,
     dbsp::ZSetHandle<Velocity>,
     dbsp::OutputHandle<OrdZSet<DamageEvent>>,
+    dbsp::ZSetHandle<Velocity>,
 );
 
 fn pf(entity: i64, z: f64, floor: f64) -> PositionFloor {
@@ -40,11 +44,16 @@ fn vel(entity: i64, vz: f64) -> Velocity {
 }
 
 fn build_circuit() -> Result<FallDamageHarness, dbsp::Error> {
-    let (circuit, (standing_in, unsupported_in, velocity_in, output)) =
+    build_circuit_with(PhysicsConfig::default())
+}
+
+fn build_circuit_with(config: PhysicsConfig) -> Result<FallDamageHarness, dbsp::Error> {
+    let (circuit, (standing_in, unsupported_in, velocity_in, output, landing_in)) =
         RootCircuit::build(|circuit| {
             let (standing_stream, standing_in) = circuit.add_input_zset::<PositionFloor>();
             let (unsupported_stream, unsupported_in) = circuit.add_input_zset::<PositionFloor>();
             let (velocity_stream, velocity_in) = circuit.add_input_zset::<Velocity>();
+            let (landing_stream, landing_in) = circuit.add_input_zset::<Velocity>();
             let tick_source = circuit.add_source(Generator::new({
                 let mut tick: Tick = 0;
                 move || {
@@ -56,21 +65,32 @@ fn build_circuit() -> Result<FallDamageHarness, dbsp::Error> {
                 }
             }));
             let current_tick = tick_source;
+            let config_source = circuit.add_source(Generator::new(move || config));
             let fall_damage = fall_damage_stream(
                 &standing_stream,
                 &unsupported_stream,
                 &velocity_stream,
+                &landing_stream,
                 &current_tick,
+                &config_source,
             );
             Ok((
                 standing_in,
                 unsupported_in,
                 velocity_in,
                 fall_damage.output(),
+                landing_in,
             ))
         })?;
 
-    Ok((circuit, standing_in, unsupported_in, velocity_in, output))
+    Ok((
+        circuit,
+        standing_in,
+        unsupported_in,
+        velocity_in,
+        output,
+        landing_in,
+    ))
 }
 
 fn read_events(output: &dbsp::OutputHandle<OrdZSet<DamageEvent>>) -> Vec<DamageEvent> {
@@ -107,7 +127,7 @@ fn delta_events(
 
 #[rstest]
 fn fall_damage_emits_event() {
-    let (circuit, standing_in, unsupported_in, velocity_in, output) =
+    let (circuit, standing_in, unsupported_in, velocity_in, output, _) =
         build_circuit().expect("failed to build fall damage circuit");
 
     let unsupported_pf = pf(1, 5.0, 0.0);
@@ -138,7 +158,7 @@ fn fall_damage_emits_event() {
 
 #[rstest]
 fn multiple_entities_land_without_interference() {
-    let (circuit, standing_in, unsupported_in, velocity_in, output) =
+    let (circuit, standing_in, unsupported_in, velocity_in, output, _) =
         build_circuit().expect("failed to build fall damage circuit");
 
     let unsupported_pf_a = pf(1, 5.0, 0.0);
@@ -188,7 +208,7 @@ fn multiple_entities_land_without_interference() {
 
 #[rstest]
 fn safe_speed_emits_no_damage() {
-    let (circuit, standing_in, unsupported_in, velocity_in, output) =
+    let (circuit, standing_in, unsupported_in, velocity_in, output, _) =
         build_circuit().expect("failed to build fall damage circuit");
     let unsupported_pf = pf(2, 5.0, 0.0);
     let standing_pf = pf(2, 1.0, 1.0);
@@ -207,7 +227,7 @@ fn safe_speed_emits_no_damage() {
 
 #[rstest]
 fn cooldown_prevents_rapid_retrigger() {
-    let (circuit, standing_in, unsupported_in, velocity_in, output) =
+    let (circuit, standing_in, unsupported_in, velocity_in, output, _) =
         build_circuit().expect("failed to build fall damage circuit");
     let unsupported_pf = pf(3, 5.0, 0.0);
     let standing_pf = pf(3, 1.0, 1.0);
@@ -240,7 +260,7 @@ fn cooldown_prevents_rapid_retrigger() {
     assert!(cooldown_events.is_empty());
 
     standing_in.push(standing_pf.clone(), -1);
-    for _ in 0..LANDING_COOLDOWN_TICKS {
+    for _ in 0..PhysicsConfig::default().landing_cooldown_ticks() {
         circuit.step().expect("cooldown tick");
     }
 
@@ -260,3 +280,192 @@ fn cooldown_prevents_rapid_retrigger() {
     assert!(final_event_record.at_tick > first_event_record.at_tick);
     assert_eq!(cumulative.len(), 2);
 }
+
+fn lands(harness: &FallDamageHarness, entity: i64, vz: f64) -> Result<(), dbsp::Error> {
+    let (circuit, standing_in, unsupported_in, velocity_in, _, _) = harness;
+    unsupported_in.push(pf(entity, 5.0, 0.0), 1);
+    velocity_in.push(vel(entity, vz), 1);
+    circuit.step()?;
+    unsupported_in.push(pf(entity, 5.0, 0.0), -1);
+    standing_in.push(pf(entity, 1.0, 1.0), 1);
+    circuit.step()?;
+    standing_in.push(pf(entity, 1.0, 1.0), -1);
+    Ok(())
+}
+
+#[rstest]
+#[case::lower_safe_speed(
+    PhysicsConfig { safe_landing_speed: OrderedFloat(2.0), ..PhysicsConfig::default() },
+    Some(24)
+)]
+#[case::gentler_scale(
+    PhysicsConfig { fall_damage_scale: OrderedFloat(1.0), ..PhysicsConfig::default() },
+    Some(2)
+)]
+#[case::lower_terminal_velocity(
+    PhysicsConfig { terminal_velocity: OrderedFloat(7.0), ..PhysicsConfig::default() },
+    Some(4)
+)]
+#[case::higher_safe_speed(
+    PhysicsConfig { safe_landing_speed: OrderedFloat(9.0), ..PhysicsConfig::default() },
+    None
+)]
+fn pinned_config_sets_fall_damage(#[case] config: PhysicsConfig, #[case] expected: Option<u16>) {
+    let harness = build_circuit_with(config).expect("failed to build fall damage circuit");
+
+    lands(&harness, 1, -8.0).expect("landing steps");
+
+    let amounts: Vec<u16> = read_events(&harness.4)
+        .iter()
+        .map(|event| event.amount)
+        .collect();
+    assert_eq!(amounts, expected.into_iter().collect::<Vec<_>>());
+}
+
+#[rstest]
+#[case::disabled(0.0, DELTA_TIME, 2)]
+#[case::default_length(LANDING_COOLDOWN, DELTA_TIME, 1)]
+#[case::shorter_than_the_gap(1.0, 1.0, 2)]
+#[case::same_length_at_a_faster_tick_rate(1.0, 0.5, 1)]
+fn cooldown_length_follows_config(
+    #[case] cooldown: f64,
+    #[case] delta_time: f64,
+    #[case] expected_events: usize,
+) {
+    let harness = build_circuit_with(PhysicsConfig {
+        landing_cooldown: OrderedFloat(cooldown),
+        delta_time: OrderedFloat(delta_time),
+        ..PhysicsConfig::default()
+    })
+    .expect("failed to build fall damage circuit");
+    let mut cumulative = BTreeMap::new();
+
+    lands(&harness, 3, -9.0).expect("first landing");
+    let mut events = delta_events(&harness.4, &mut cumulative);
+    lands(&harness, 3, -9.0).expect("second landing");
+    events.extend(delta_events(&harness.4, &mut cumulative));
+
+    assert_eq!(events.len(), expected_events, "events: {events:?}");
+}
+
+#[rstest]
+fn swept_landing_damages_on_same_tick() {
+    let (circuit, standing_in, unsupported_in, velocity_in, output, landing_in) =
+        build_circuit().expect("failed to build fall damage circuit");
+    let impact = vel(5, -TERMINAL_VELOCITY);
+
+    unsupported_in.push(pf(5, 10.0, 1.0), 1);
+    velocity_in.push(impact, 1);
+    landing_in.push(impact, 1);
+    circuit.step().expect("swept landing tick");
+
+    let events = read_events(&output);
+    let event = test_utils::expect_single(&events, "swept landing event");
+    assert_eq!(event.entity, 5);
+    assert_eq!(event.source, DamageSource::Fall);
+    let expected_amount =
+        expect_u16(((TERMINAL_VELOCITY - SAFE_LANDING_SPEED) * FALL_DAMAGE_SCALE).floor());
+    assert_eq!(event.amount, expected_amount);
+    assert_eq!(event.at_tick, 0);
+
+    standing_in.push(pf(5, 1.0, 1.0), 1);
+    circuit.step().expect("standing after swept landing");
+    assert!(
+        delta_events(&output, &mut BTreeMap::new()).is_empty(),
+        "a swept landing must not be counted again once standing"
+    );
+}
+
+type KillPlaneHarness = (
+    dbsp::CircuitHandle,
+    dbsp::ZSetHandle<Position>,
+    dbsp::ZSetHandle<Velocity>,
+    dbsp::OutputHandle<OrdZSet<DamageEvent>>,
+);
+
+fn build_kill_plane_circuit(config: PhysicsConfig) -> Result<KillPlaneHarness, dbsp::Error> {
+    let (circuit, (position_in, velocity_in, output)) = RootCircuit::build(move |circuit| {
+        let (positions, position_in) = circuit.add_input_zset::<Position>();
+        let (velocities, velocity_in) = circuit.add_input_zset::<Velocity>();
+        let ticks = circuit.add_source(Generator::new({
+            let mut tick: Tick = 0;
+            move || {
+                let current = tick;
+                tick = tick.saturating_add(1);
+                current
+            }
+        }));
+        let config_source = circuit.add_source(Generator::new(move || config));
+        let damage = kill_plane_damage_stream(&positions, &velocities, &ticks, &config_source);
+        Ok((position_in, velocity_in, damage.output()))
+    })?;
+
+    Ok((circuit, position_in, velocity_in, output))
+}
+
+#[rstest]
+#[case::crosses_plane(KILL_PLANE_Z + 0.5, -1.0, true)]
+#[case::starts_on_plane(KILL_PLANE_Z, -0.5, true)]
+#[case::stays_above(KILL_PLANE_Z + 2.0, -1.0, false)]
+#[case::already_below(KILL_PLANE_Z - 1.0, -1.0, false)]
+#[case::rising_through(KILL_PLANE_Z - 0.5, 1.0, false)]
+fn kill_plane_emits_lethal_damage_on_crossing(
+    #[case] z: f64,
+    #[case] vz: f64,
+    #[case] expect_event: bool,
+) {
+    let (circuit, position_in, velocity_in, output) =
+        build_kill_plane_circuit(PhysicsConfig::default())
+            .expect("failed to build kill plane circuit");
+
+    position_in.push(pf(4, z, 0.0).position, 1);
+    velocity_in.push(vel(4, vz), 1);
+    circuit.step().expect("kill plane tick");
+
+    let events = read_events(&output);
+    if expect_event {
+        let event = test_utils::expect_single(&events, "kill plane event");
+        assert_eq!(event.entity, 4);
+        assert_eq!(event.source, DamageSource::OutOfBounds);
+        assert_eq!(event.amount, u16::MAX);
+        assert_eq!(event.at_tick, 0);
+    } else {
+        assert!(events.is_empty(), "unexpected events: {events:?}");
+    }
+}
+
+#[rstest]
+#[case::crosses_configured_plane(-4.5, true)]
+#[case::default_plane_is_ignored(KILL_PLANE_Z + 0.5, false)]
+fn kill_plane_height_comes_from_config(#[case] z: f64, #[case] expect_event: bool) {
+    let config = PhysicsConfig {
+        kill_plane_z: OrderedFloat(-5.0),
+        ..PhysicsConfig::default()
+    };
+    let (circuit, position_in, velocity_in, output) =
+        build_kill_plane_circuit(config).expect("failed to build kill plane circuit");
+
+    position_in.push(pf(4, z, 0.0).position, 1);
+    velocity_in.push(vel(4, -1.0), 1);
+    circuit.step().expect("kill plane tick");
+
+    assert_eq!(read_events(&output).len(), usize::from(expect_event));
+}
+
+#[rstest]
+#[case::short_tick_stops_short(0.25, false)]
+#[case::long_tick_crosses(2.0, true)]
+fn kill_plane_look_ahead_follows_delta_time(#[case] delta_time: f64, #[case] expect_event: bool) {
+    let config = PhysicsConfig {
+        delta_time: OrderedFloat(delta_time),
+        ..PhysicsConfig::default()
+    };
+    let (circuit, position_in, velocity_in, output) =
+        build_kill_plane_circuit(config).expect("failed to build kill plane circuit");
+
+    position_in.push(pf(4, KILL_PLANE_Z + 1.5, 0.0).position, 1);
+    velocity_in.push(vel(4, -1.0), 1);
+    circuit.step().expect("kill plane tick");
+
+    assert_eq!(read_events(&output).len(), usize::from(expect_event));
+}
@@ -10,85 +10,170 @@ use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 use size_of::SizeOf;
 
 use crate::numeric::floor_to_i32;
-use crate::{applied_acceleration, apply_ground_friction, GRAVITY_PULL, TERMINAL_VELOCITY};
+use crate::{applied_acceleration, PhysicsConfig, ENTITY_HEIGHT};
 
-use crate::dbsp_circuit::{FloorHeightAt, Force, Position, Velocity};
+use crate::dbsp_circuit::{Drag, FloorHeightAt, Force, Impulse, Position, Velocity};
 
-/// Clamps a vertical velocity to the configured terminal speed.
-fn clamp_terminal_velocity(vz: f64) -> OrderedFloat<f64> {
-    // Prevent unbounded acceleration by enforcing a maximum fall speed.
-    OrderedFloat(vz.max(-TERMINAL_VELOCITY))
+use super::collision::wall_collision_stream;
+use super::config::with_physics_config;
+use super::floor::supporting_floor;
+
+/// Adds one tick of the acceleration `force` imparts to `vel`.
+///
+/// Forces with invalid masses are ignored with a log warning.
+fn accelerate(vel: Velocity, force: &Force, params: &PhysicsConfig) -> Velocity {
+    let accel = applied_acceleration(
+        (
+            force.fx.into_inner(),
+            force.fy.into_inner(),
+            force.fz.into_inner(),
+        ),
+        force.mass.map(OrderedFloat::into_inner),
+    );
+    if accel.is_none() {
+        warn!(
+            "force with invalid mass for entity {} ignored",
+            force.entity
+        );
+    }
+    let (ax, ay, az) = accel.unwrap_or((0.0, 0.0, 0.0));
+    Velocity {
+        entity: vel.entity,
+        vx: OrderedFloat(vel.vx.into_inner() + params.accelerate(ax)),
+        vy: OrderedFloat(vel.vy.into_inner() + params.accelerate(ay)),
+        vz: OrderedFloat(vel.vz.into_inner() + params.accelerate(az)),
+    }
 }
 
-/// Applies gravity and a single external force to each velocity record (dt = 1).
+/// Applies gravity and a single external force to each velocity record.
 ///
 /// Each entity may supply at most one [`Force`] record per tick. Forces with
-/// invalid masses are ignored with a log warning.
+/// invalid masses are ignored with a log warning. Both accelerations act for
+/// [`PhysicsConfig::delta_time`] seconds. Gravity, the terminal velocity clamp
+/// and the tick length come from the [`PhysicsConfig`] in force on the tick,
+/// so a configuration change re-derives every velocity on the next step.
 #[must_use]
 pub fn new_velocity_stream(
     velocities: &Stream<RootCircuit, OrdZSet<Velocity>>,
     forces: &Stream<RootCircuit, OrdZSet<Force>>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
+) -> Stream<RootCircuit, OrdZSet<Velocity>> {
+    with_physics_config(velocities, config)
+        .map_index(|&(vel, params)| (vel.entity, (vel, params)))
+        .outer_join(
+            &forces.map_index(|f| (f.entity, *f)),
+            |_, &(vel, params), force| Some((accelerate(vel, force, &params), params)),
+            |_, &(vel, params)| Some((vel, params)),
+            |_, _| None,
+        )
+        .flat_map(|accelerated| {
+            accelerated.map(|(vel, params)| Velocity {
+                vz: OrderedFloat(params.fall(vel.vz.into_inner())),
+                ..vel
+            })
+        })
+}
+
+/// Adds each entity's [`Impulse`] to its velocity.
+///
+/// Impulses are applied before gravity and forces, so a jump of `dvz` leaves
+/// the ground with `dvz + gravity_pull` on its first tick. Entities without an
+/// impulse keep their velocity unchanged.
+#[must_use]
+pub fn impulse_velocity_stream(
+    velocities: &Stream<RootCircuit, OrdZSet<Velocity>>,
+    impulses: &Stream<RootCircuit, OrdZSet<Impulse>>,
 ) -> Stream<RootCircuit, OrdZSet<Velocity>> {
     velocities
         .map_index(|v| (v.entity, *v))
         .outer_join(
-            &forces.map_index(|f| (f.entity, *f)),
-            |_, vel, force| {
-                let accel = applied_acceleration(
-                    (
-                        force.fx.into_inner(),
-                        force.fy.into_inner(),
-                        force.fz.into_inner(),
-                    ),
-                    force.mass.map(OrderedFloat::into_inner),
-                );
-                if accel.is_none() {
-                    warn!(
-                        "force with invalid mass for entity {} ignored",
-                        force.entity
-                    );
-                }
-                let (ax, ay, az) = accel.unwrap_or((0.0, 0.0, 0.0));
+            &impulses.map_index(|i| (i.entity, *i)),
+            |_, vel, impulse| {
                 Some(Velocity {
                     entity: vel.entity,
-                    vx: OrderedFloat(vel.vx.into_inner() + ax),
-                    vy: OrderedFloat(vel.vy.into_inner() + ay),
-                    vz: clamp_terminal_velocity(vel.vz.into_inner() + az + GRAVITY_PULL),
-                })
-            },
-            |_, vel| {
-                Some(Velocity {
-                    entity: vel.entity,
-                    vx: vel.vx,
-                    vy: vel.vy,
-                    vz: clamp_terminal_velocity(vel.vz.into_inner() + GRAVITY_PULL),
+                    vx: vel.vx + impulse.dvx,
+                    vy: vel.vy + impulse.dvy,
+                    vz: vel.vz + impulse.dvz,
                 })
             },
+            |_, vel| Some(*vel),
             |_, _| None,
         )
         .flat_map(|v| *v)
 }
 
+/// Applies one tick of drag with `coefficient` to the horizontal components
+/// of `vel`.
+fn drag(vel: Velocity, coefficient: OrderedFloat<f64>, params: &PhysicsConfig) -> Velocity {
+    Velocity {
+        vx: OrderedFloat(params.apply_drag(vel.vx.into_inner(), coefficient)),
+        vy: OrderedFloat(params.apply_drag(vel.vy.into_inner(), coefficient)),
+        ..vel
+    }
+}
+
+/// Applies air drag to the horizontal velocity of airborne entities.
+///
+/// `velocities` holds the entities that are unsupported this tick. Each loses
+/// the fraction of `vx` and `vy` per second given by its [`Drag`] record, or
+/// by [`PhysicsConfig::air_friction`] when it has none, compounded over the
+/// tick. Vertical velocity is left to gravity and the terminal velocity
+/// clamp.
+#[must_use]
+pub fn air_drag_stream(
+    velocities: &Stream<RootCircuit, OrdZSet<Velocity>>,
+    drags: &Stream<RootCircuit, OrdZSet<Drag>>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
+) -> Stream<RootCircuit, OrdZSet<Velocity>> {
+    with_physics_config(velocities, config)
+        .map_index(|&(vel, params)| (vel.entity, (vel, params)))
+        .outer_join(
+            &drags.map_index(|d| (d.entity, d.coefficient)),
+            |_, &(vel, params), &coefficient| Some(drag(vel, coefficient, &params)),
+            |_, &(vel, params)| Some(drag(vel, params.air_friction, &params)),
+            |_, _| None,
+        )
+        .flat_map(|v| *v)
+}
+
+/// Returns `p` moved along `v` for one tick.
+fn integrate(p: &Position, v: &Velocity, params: &PhysicsConfig) -> Position {
+    Position {
+        entity: p.entity,
+        x: OrderedFloat(p.x.into_inner() + params.displacement(v.vx.into_inner())),
+        y: OrderedFloat(p.y.into_inner() + params.displacement(v.vy.into_inner())),
+        z: OrderedFloat(p.z.into_inner() + params.displacement(v.vz.into_inner())),
+    }
+}
+
+/// Pairs each entity's position and velocity with the tick's configuration.
+fn with_velocity_and_config(
+    positions: &Stream<RootCircuit, OrdZSet<Position>>,
+    velocities: &Stream<RootCircuit, OrdZSet<Velocity>>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
+) -> Stream<RootCircuit, OrdZSet<(Position, Velocity, PhysicsConfig)>> {
+    let moving = positions
+        .map_index(|p| (p.entity, *p))
+        .join(&velocities.map_index(|v| (v.entity, *v)), |_, p, v| {
+            (*p, *v)
+        });
+    with_physics_config(&moving, config).map(|&((p, v), params)| (p, v, params))
+}
+
 /// Integrates positions with updated velocities.
 ///
 /// The input streams are joined by `entity`, producing a new [`Position`]
-/// translated by the entity's velocity components. The function performs a
-/// simple Euler integration suitable for the small time step used in the game
-/// loop.
+/// translated by the entity's velocity over one tick of
+/// [`PhysicsConfig::delta_time`] seconds. The function performs a simple
+/// Euler integration suitable for the small time step used in the game loop.
 #[must_use]
 pub fn new_position_stream(
     positions: &Stream<RootCircuit, OrdZSet<Position>>,
     new_vel: &Stream<RootCircuit, OrdZSet<Velocity>>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
 ) -> Stream<RootCircuit, OrdZSet<Position>> {
-    positions.map_index(|p| (p.entity, *p)).join(
-        &new_vel.map_index(|v| (v.entity, *v)),
-        |_, p, v| Position {
-            entity: p.entity,
-            x: OrderedFloat(p.x.into_inner() + v.vx.into_inner()),
-            y: OrderedFloat(p.y.into_inner() + v.vy.into_inner()),
-            z: OrderedFloat(p.z.into_inner() + v.vz.into_inner()),
-        },
-    )
+    with_velocity_and_config(positions, new_vel, config)
+        .map(|(p, v, params)| integrate(p, v, params))
 }
 
 /// Pairs an entity's position with the floor height at its grid location.
@@ -122,29 +207,206 @@ pub struct PositionFloor {
 /// Joins each `Position` with the corresponding floor height.
 ///
 /// Positions are discretised to grid coordinates by flooring their `x` and `y`
-/// values. Those indices look up a [`FloorHeightAt`] record to produce a
-/// [`PositionFloor`] stream suitable for higher-level physics logic.
+/// values. Among the [`FloorHeightAt`] surfaces of that cell, the one whose
+/// band [supports](FloorHeightAt::supports) the entity's `z` is chosen, so a
+/// unit under a bridge stands on the ground rather than the deck. The surface
+/// is sampled at the fractional part of the position so entities on a slope
+/// stand on the surface beneath them rather than at the cell-centre height.
+/// The result is a [`PositionFloor`] stream suitable for higher-level physics
+/// logic.
 #[must_use]
 pub fn position_floor_stream(
     positions: &Stream<RootCircuit, OrdZSet<Position>>,
     floor_height: &Stream<RootCircuit, OrdZSet<FloorHeightAt>>,
 ) -> Stream<RootCircuit, OrdZSet<PositionFloor>> {
-    positions
-        .map_index(|p| ((floor_to_i32(p.x), floor_to_i32(p.y)), *p))
-        .join(
-            &floor_height.map_index(|fh| ((fh.x, fh.y), fh.z)),
-            |_idx, pos, &z_floor| PositionFloor {
-                position: *pos,
-                z_floor,
+    supporting_floor(positions, floor_height, locate_position).flat_map(|&(pos, fh)| {
+        fh.map(|floor| PositionFloor {
+            position: pos,
+            z_floor: floor.sample(pos.x, pos.y),
+        })
+    })
+}
+
+/// Grid cell and height used to find the floor supporting a position.
+fn locate_position(p: &Position) -> ((i32, i32), OrderedFloat<f64>) {
+    ((floor_to_i32(p.x), floor_to_i32(p.y)), p.z)
+}
+
+/// Selects positions with no floor beneath them.
+///
+/// [`position_floor_stream`] drops such entities because no [`FloorHeightAt`]
+/// surface in their cell supports them: the cell has no blocks, or every run
+/// starts above the entity. The circuit routes them through the unsupported
+/// branch instead, so units that walk off the edge of the map, or drop below
+/// the bottom of a floating platform, fall freely under gravity rather than
+/// freezing in mid-air.
+#[must_use]
+pub fn void_position_stream(
+    positions: &Stream<RootCircuit, OrdZSet<Position>>,
+    floor_height: &Stream<RootCircuit, OrdZSet<FloorHeightAt>>,
+) -> Stream<RootCircuit, OrdZSet<Position>> {
+    supporting_floor(positions, floor_height, locate_position)
+        .flat_map(|&(pos, fh)| fh.is_none().then_some(pos))
+}
+
+/// Clamps one integrated fall against the floor and ceiling of its
+/// destination.
+///
+/// `floor` is the surface whose band holds the entity's height before the
+/// move. Returns the resolved position and velocity, plus the velocity at
+/// impact when the integrated height ends below the floor surface. A rise
+/// that would lift the entity's head through the ceiling stops beneath it.
+fn sweep_fall(
+    start_z: OrderedFloat<f64>,
+    target: Position,
+    vel: Velocity,
+    floor: &FloorHeightAt,
+) -> (Position, Velocity, Option<Velocity>) {
+    let stopped = Velocity {
+        vz: OrderedFloat(0.0),
+        ..vel
+    };
+    let z_floor = floor.sample(target.x, target.y);
+    if target.z < z_floor {
+        return (
+            Position {
+                z: z_floor,
+                ..target
             },
-        )
+            stopped,
+            Some(vel),
+        );
+    }
+    match floor.ceiling {
+        Some(ceiling)
+            if vel.vz.into_inner() > 0.0
+                && target.z.into_inner() + ENTITY_HEIGHT > ceiling.into_inner() =>
+        {
+            // Hold the entity where it started if it is already above the
+            // headroom, but never push it below the floor.
+            let headroom = OrderedFloat(ceiling.into_inner() - ENTITY_HEIGHT);
+            (
+                Position {
+                    z: headroom.max(start_z).min(target.z).max(z_floor),
+                    ..target
+                },
+                stopped,
+                None,
+            )
+        }
+        _ => (target, vel, None),
+    }
+}
+
+/// Streams produced by [`swept_fall_stream`].
+pub struct SweptFall {
+    /// Integrated positions, clamped to the floor surface on landing.
+    pub positions: Stream<RootCircuit, OrdZSet<Position>>,
+    /// Integrated velocities, with `vz` zeroed on landing.
+    pub velocities: Stream<RootCircuit, OrdZSet<Velocity>>,
+    /// Velocities at impact of entities that landed this tick.
+    pub landings: Stream<RootCircuit, OrdZSet<Velocity>>,
+}
+
+/// Integrates falling entities and clamps landings to the floor surface.
+///
+/// Each position is advanced by its velocity as in [`new_position_stream`],
+/// then checked against the floor surface beneath the destination whose band
+/// holds the entity's starting height. A fall that would pass through the
+/// floor in a single tick stops on the surface with its vertical velocity
+/// zeroed, so fast-falling units cannot tunnel through terrain. A rising unit
+/// stops with its head [`ENTITY_HEIGHT`](crate::ENTITY_HEIGHT) below the
+/// ceiling above that surface. Entities with no floor beneath them integrate
+/// freely.
+///
+/// The [`SweptFall::landings`] stream carries the velocity at impact, which
+/// lets [`fall_damage_stream`](crate::dbsp_circuit::fall_damage_stream) apply
+/// damage on the tick of the landing.
+#[must_use]
+pub fn swept_fall_stream(
+    positions: &Stream<RootCircuit, OrdZSet<Position>>,
+    velocities: &Stream<RootCircuit, OrdZSet<Velocity>>,
+    floor_height: &Stream<RootCircuit, OrdZSet<FloorHeightAt>>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
+) -> SweptFall {
+    let falls = with_velocity_and_config(positions, velocities, config)
+        .map(|(p, v, params)| (p.z, integrate(p, v, params), *v));
+    let swept = supporting_floor(&falls, floor_height, |&(start_z, target, _)| {
+        ((floor_to_i32(target.x), floor_to_i32(target.y)), start_z)
+    })
+    .map(|&((start_z, target, vel), floor)| {
+        floor.map_or((target, vel, None), |surface| {
+            sweep_fall(start_z, target, vel, &surface)
+        })
+    });
+
+    SweptFall {
+        positions: swept.map(|(p, _, _)| *p),
+        velocities: swept.map(|(_, v, _)| *v),
+        landings: swept.flat_map(|(_, _, impact)| *impact),
+    }
+}
+
+/// Places a resolved standing move relative to the floor at its destination.
+///
+/// A floor no more than [`PhysicsConfig::max_step_height`] below the entity
+/// snaps it onto the surface. A deeper drop, or a destination with no floor
+/// beneath the entity, leaves it at its current height so the next tick
+/// classifies it as unsupported and it falls. The resolved one-tick `step`
+/// becomes the entity's velocity, with the vertical component zero either
+/// way.
+fn settle(
+    position: Position,
+    step: Velocity,
+    z_floor: Option<OrderedFloat<f64>>,
+    params: &PhysicsConfig,
+) -> (Position, Velocity) {
+    let max_step = params.max_step_height.into_inner();
+    let ground = z_floor.filter(|z| z.into_inner() >= position.z.into_inner() - max_step);
+    (
+        Position {
+            z: ground.unwrap_or(position.z),
+            ..position
+        },
+        Velocity {
+            vx: OrderedFloat(params.velocity(step.vx.into_inner())),
+            vy: OrderedFloat(params.velocity(step.vy.into_inner())),
+            vz: OrderedFloat(0.0),
+            ..step
+        },
+    )
+}
+
+/// Settles resolved standing moves onto the floor beneath their destination.
+///
+/// The floor is the destination surface whose band holds the entity's height,
+/// sampled at the destination coordinates and compared with the tick's
+/// [`PhysicsConfig::max_step_height`] as described in [`settle`].
+fn settle_on_floor(
+    resolved: &Stream<RootCircuit, OrdZSet<(Position, Velocity)>>,
+    floor_height: &Stream<RootCircuit, OrdZSet<FloorHeightAt>>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
+) -> Stream<RootCircuit, OrdZSet<(Position, Velocity)>> {
+    let sampled = supporting_floor(resolved, floor_height, |(p, _)| locate_position(p))
+        .map(|&((p, v), floor)| (p, v, floor.map(|fh| fh.sample(p.x, p.y))));
+    with_physics_config(&sampled, config)
+        .map(|&((p, v, z_floor), params)| settle(p, v, z_floor, &params))
 }
 
 /// Computes new positions and velocities for entities standing on the ground.
 ///
 /// Standing entities move according to their horizontal velocity components and
-/// snap to the floor height at their new `(x, y)` coordinates. The vertical
-/// velocity is reset to zero to keep entities grounded.
+/// snap to the floor surface sampled at their new `(x, y)` coordinates. The
+/// vertical velocity is reset to zero to keep entities grounded. Moves are
+/// resolved through [`wall_collision_stream`] first, so an entity cannot walk
+/// into a cell whose floor rises more than
+/// [`PhysicsConfig::max_step_height`] above it; the velocity component along
+/// any blocked axis drops to zero. A move onto a floor more than that height
+/// below the entity, or off the edge of the map, does not snap: the entity
+/// keeps its height, becomes unsupported on the next tick and falls, so
+/// walking off a ledge can cause fall damage. Horizontal velocity is damped by
+/// the ground friction of the tick's [`PhysicsConfig`], and the damped
+/// velocity carries the entity for [`PhysicsConfig::delta_time`] seconds.
 ///
 /// # Returns
 ///
@@ -155,51 +417,36 @@ pub fn standing_motion_stream(
     standing: &Stream<RootCircuit, OrdZSet<PositionFloor>>,
     floor_height: &Stream<RootCircuit, OrdZSet<FloorHeightAt>>,
     velocities: &Stream<RootCircuit, OrdZSet<Velocity>>,
+    config: &Stream<RootCircuit, PhysicsConfig>,
 ) -> (
     Stream<RootCircuit, OrdZSet<Position>>,
     Stream<RootCircuit, OrdZSet<Velocity>>,
 ) {
-    // `dbsp` 0.98 lacks a `join_map` combinator. We explicitly compose
-    // `map_index` and `join` operators to achieve equivalent behaviour.
-    let moved = standing
-        .map_index(|pf| (pf.position.entity, pf.position))
-        .join(&velocities.map_index(|v| (v.entity, *v)), |_, pos, vel| {
-            let fx = apply_ground_friction(vel.vx.into_inner());
-            let fy = apply_ground_friction(vel.vy.into_inner());
-            let new_x = OrderedFloat(pos.x.into_inner() + fx);
-            let new_y = OrderedFloat(pos.y.into_inner() + fy);
-            (new_x, new_y, pos.entity, OrderedFloat(fx), OrderedFloat(fy))
+    let positions = standing.map(|pf| pf.position);
+    let moves =
+        with_velocity_and_config(&positions, velocities, config).map(|(pos, vel, params)| {
+            // Walls resolve the distance covered this tick, which `settle` turns
+            // back into a velocity.
+            let glide = |v: OrderedFloat<f64>| {
+                OrderedFloat(params.displacement(params.apply_ground_friction(v.into_inner())))
+            };
+            let step = Velocity {
+                entity: pos.entity,
+                vx: glide(vel.vx),
+                vy: glide(vel.vy),
+                vz: OrderedFloat(0.0),
+            };
+            (*pos, step)
         });
 
-    let indexed = moved.map_index(|(x, y, entity, vx, vy)| {
-        (
-            (floor_to_i32(*x), floor_to_i32(*y)),
-            (*entity, *x, *y, *vx, *vy),
-        )
-    });
-
-    let with_floor = indexed.join(
-        &floor_height.map_index(|fh| ((fh.x, fh.y), fh.z)),
-        |_idx, &(entity, x, y, vx, vy), &z_floor| {
-            (
-                Position {
-                    entity,
-                    x,
-                    y,
-                    z: z_floor,
-                },
-                Velocity {
-                    entity,
-                    vx,
-                    vy,
-                    vz: OrderedFloat(0.0),
-                },
-            )
-        },
+    let settled = settle_on_floor(
+        &wall_collision_stream(&moves, floor_height, config),
+        floor_height,
+        config,
     );
 
-    let new_pos = with_floor.map(|(p, _)| *p);
-    let new_vel = with_floor.map(|(_, v)| *v);
+    let new_pos = settled.map(|(p, _)| *p);
+    let new_vel = settled.map(|(_, v)| *v);
     (new_pos, new_vel)
 }
 
@@ -2,9 +2,14 @@
 
 use crate::components::Block;
 use crate::dbsp_circuit::step_named;
-use crate::dbsp_circuit::streams::test_utils::{block, force, force_with_mass, new_circuit, vel};
-use crate::dbsp_circuit::{Force, NewPosition, NewVelocity, Position, Velocity};
-use crate::{apply_ground_friction, GRAVITY_PULL, TERMINAL_VELOCITY};
+use crate::dbsp_circuit::streams::test_utils::{
+    block, force, force_with_mass, new_circuit, slope, vel,
+};
+use crate::dbsp_circuit::{Drag, Force, Impulse, NewPosition, NewVelocity, Position, Velocity};
+use crate::physics::{apply_friction, apply_friction_over};
+use crate::{
+    apply_ground_friction, AIR_FRICTION, GRAVITY_PULL, GROUND_FRICTION, TERMINAL_VELOCITY,
+};
 use approx::assert_relative_eq;
 use rstest::rstest;
 use test_utils::expect_single;
@@ -117,6 +122,41 @@ struct MotionExpectation {
         velocity: Some(vel(3, (apply_ground_friction(-1.0), 0.0, 0.0))),
     },
 })]
+#[case::wall_blocks_standing_motion(MotionScenario {
+    position: Position { entity: 4, x: 0.5.into(), y: 0.0.into(), z: 1.0.into() },
+    velocity: vel(4, (1.0, 0.0, 0.0)),
+    blocks: vec![block(1, (0, 0, 0)), block(2, (1, 0, 0)), block(3, (1, 0, 1)), block(4, (1, 0, 2))],
+    force: None,
+    expected: MotionExpectation {
+        position: Some(Position { entity: 4, x: 0.5.into(), y: 0.0.into(), z: 1.0.into() }),
+        velocity: Some(vel(4, (0.0, 0.0, 0.0))),
+    },
+})]
+#[case::fast_fall_lands_on_floor(MotionScenario {
+    position: Position { entity: 6, x: 0.5.into(), y: 0.5.into(), z: 10.0.into() },
+    velocity: vel(6, (0.0, 0.0, -11.0)),
+    blocks: vec![block(1, (0, 0, 0))],
+    force: None,
+    expected: MotionExpectation {
+        position: Some(Position { entity: 6, x: 0.5.into(), y: 0.5.into(), z: 1.0.into() }),
+        velocity: Some(vel(6, (0.0, 0.0, 0.0))),
+    },
+})]
+#[case::void_falls(MotionScenario {
+    position: Position { entity: 5, x: 0.5.into(), y: 0.5.into(), z: 2.0.into() },
+    velocity: vel(5, (0.5, 0.0, 0.0)),
+    blocks: vec![],
+    force: None,
+    expected: MotionExpectation {
+        position: Some(Position {
+            entity: 5,
+            x: (0.5 + apply_friction(0.5, AIR_FRICTION)).into(),
+            y: 0.5.into(),
+            z: (2.0 + GRAVITY_PULL).into(),
+        }),
+        velocity: Some(vel(5, (apply_friction(0.5, AIR_FRICTION), 0.0, GRAVITY_PULL))),
+    },
+})]
 fn motion_cases(#[case] scenario: MotionScenario) {
     let MotionScenario {
         position,
@@ -287,7 +327,289 @@ fn standing_friction(#[case] vx: f64) {
 }
 
 #[test]
-fn airborne_preserves_velocity() {
+fn standing_snap_follows_slope() {
+    let mut circuit = new_circuit().expect("failed to build DBSP circuit");
+
+    circuit.block_in().push(block(1, (0, 0, 0)), 1);
+    circuit.block_slope_in().push(slope(1, (1.0, 0.0)), 1);
+    circuit.position_in().push(
+        Position {
+            entity: 1,
+            x: 0.2.into(),
+            y: 0.5.into(),
+            z: 1.2.into(),
+        },
+        1,
+    );
+    circuit.velocity_in().push(vel(1, (0.5, 0.0, 0.0)), 1);
+
+    step_named(&mut circuit, "standing_snap_follows_slope");
+
+    let pos_out: Vec<NewPosition> = circuit
+        .new_position_out()
+        .consolidate()
+        .iter()
+        .map(|t| t.0)
+        .collect();
+    let position = expect_single(pos_out.as_slice(), "position output");
+    let moved_x = 0.2 + apply_ground_friction(0.5);
+    assert_relative_eq!(position.x.into_inner(), moved_x);
+    assert_relative_eq!(position.z.into_inner(), 1.0 + moved_x);
+}
+
+#[rstest]
+#[case::step_down_snaps(Some(block(2, (1, 0, -1))), 0.0)]
+#[case::ledge_keeps_height(Some(block(2, (1, 0, -5))), 1.0)]
+#[case::map_edge_keeps_height(None, 1.0)]
+fn standing_step_down(#[case] below: Option<Block>, #[case] expected_z: f64) {
+    let mut circuit = new_circuit().expect("failed to build DBSP circuit");
+
+    circuit.block_in().push(block(1, (0, 0, 0)), 1);
+    if let Some(lower) = below {
+        circuit.block_in().push(lower, 1);
+    }
+    circuit.position_in().push(
+        Position {
+            entity: 1,
+            x: 0.8.into(),
+            y: 0.5.into(),
+            z: 1.0.into(),
+        },
+        1,
+    );
+    circuit.velocity_in().push(vel(1, (0.5, 0.0, 0.0)), 1);
+
+    step_named(&mut circuit, "standing_step_down");
+
+    let pos_out: Vec<NewPosition> = circuit
+        .new_position_out()
+        .consolidate()
+        .iter()
+        .map(|t| t.0)
+        .collect();
+    let position = expect_single(pos_out.as_slice(), "position output");
+    assert_relative_eq!(position.x.into_inner(), 0.8 + apply_ground_friction(0.5));
+    assert_relative_eq!(position.z.into_inner(), expected_z);
+
+    let vel_out: Vec<NewVelocity> = circuit
+        .new_velocity_out()
+        .consolidate()
+        .iter()
+        .map(|t| t.0)
+        .collect();
+    let velocity = expect_single(vel_out.as_slice(), "velocity output");
+    assert_relative_eq!(velocity.vz.into_inner(), 0.0);
+}
+
+#[test]
+fn step_down_allowance_follows_config() {
+    let mut circuit = new_circuit().expect("failed to build DBSP circuit");
+    circuit.set_physics_config(crate::PhysicsConfig {
+        max_step_height: 6.0.into(),
+        ..crate::PhysicsConfig::default()
+    });
+
+    circuit.block_in().push(block(1, (0, 0, 0)), 1);
+    circuit.block_in().push(block(2, (1, 0, -5)), 1);
+    circuit.position_in().push(
+        Position {
+            entity: 1,
+            x: 0.8.into(),
+            y: 0.5.into(),
+            z: 1.0.into(),
+        },
+        1,
+    );
+    circuit.velocity_in().push(vel(1, (0.5, 0.0, 0.0)), 1);
+
+    step_named(&mut circuit, "step_down_allowance_follows_config");
+
+    let pos_out: Vec<NewPosition> = circuit
+        .new_position_out()
+        .consolidate()
+        .iter()
+        .map(|t| t.0)
+        .collect();
+    let position = expect_single(pos_out.as_slice(), "position output");
+    assert_relative_eq!(position.z.into_inner(), -4.0);
+}
+
+#[test]
+fn walks_under_a_bridge() {
+    let mut circuit = new_circuit().expect("failed to build DBSP circuit");
+
+    circuit.block_in().push(block(1, (0, 0, 0)), 1);
+    circuit.block_in().push(block(2, (1, 0, 0)), 1);
+    circuit.block_in().push(block(3, (1, 0, 2)), 1);
+    circuit.position_in().push(
+        Position {
+            entity: 1,
+            x: 0.8.into(),
+            y: 0.5.into(),
+            z: 1.0.into(),
+        },
+        1,
+    );
+    circuit.velocity_in().push(vel(1, (0.5, 0.0, 0.0)), 1);
+
+    step_named(&mut circuit, "walks_under_a_bridge");
+
+    let pos_out: Vec<NewPosition> = circuit
+        .new_position_out()
+        .consolidate()
+        .iter()
+        .map(|t| t.0)
+        .collect();
+    let position = expect_single(pos_out.as_slice(), "position output");
+    assert_relative_eq!(position.x.into_inner(), 0.8 + apply_ground_friction(0.5));
+    assert_relative_eq!(position.z.into_inner(), 1.0);
+}
+
+#[test]
+fn falls_when_the_block_below_is_removed() {
+    let mut circuit = new_circuit().expect("failed to build DBSP circuit");
+
+    circuit.block_in().push(block(1, (0, 0, 0)), 1);
+    circuit.block_in().push(block(2, (0, 0, 1)), 1);
+    circuit.block_in().push(block(3, (0, 0, 2)), 1);
+    circuit.position_in().push(
+        Position {
+            entity: 1,
+            x: 0.5.into(),
+            y: 0.5.into(),
+            z: 3.0.into(),
+        },
+        1,
+    );
+    circuit.velocity_in().push(vel(1, (0.0, 0.0, 0.0)), 1);
+    step_named(
+        &mut circuit,
+        "falls_when_the_block_below_is_removed: standing",
+    );
+
+    circuit.block_in().push(block(2, (0, 0, 1)), -1);
+    circuit.block_in().push(block(3, (0, 0, 2)), -1);
+    step_named(
+        &mut circuit,
+        "falls_when_the_block_below_is_removed: removed",
+    );
+
+    let pos_out: Vec<NewPosition> = circuit
+        .new_position_out()
+        .consolidate()
+        .iter()
+        .filter(|(_, (), weight)| *weight > 0)
+        .map(|t| t.0)
+        .collect();
+    let position = expect_single(pos_out.as_slice(), "position output");
+    assert_relative_eq!(position.z.into_inner(), 3.0 + GRAVITY_PULL);
+}
+
+#[rstest]
+#[case::stops_at_ceiling(4.0, 3.0)]
+#[case::below_ceiling(2.0, 2.5)]
+fn ceiling_stops_upward_motion(#[case] start_vz: f64, #[case] expected_z: f64) {
+    let mut circuit = new_circuit().expect("failed to build DBSP circuit");
+
+    circuit.block_in().push(block(1, (0, 0, 0)), 1);
+    circuit.block_in().push(block(2, (0, 0, 4)), 1);
+    circuit.position_in().push(
+        Position {
+            entity: 1,
+            x: 0.5.into(),
+            y: 0.5.into(),
+            z: 1.5.into(),
+        },
+        1,
+    );
+    circuit.velocity_in().push(vel(1, (0.0, 0.0, start_vz)), 1);
+
+    step_named(&mut circuit, "ceiling_stops_upward_motion");
+
+    let pos_out: Vec<NewPosition> = circuit
+        .new_position_out()
+        .consolidate()
+        .iter()
+        .map(|t| t.0)
+        .collect();
+    let position = expect_single(pos_out.as_slice(), "position output");
+    assert_relative_eq!(position.z.into_inner(), expected_z);
+}
+
+/// Runs one tick of `delta_time` seconds for an entity at `(x, 0.5, z)`
+/// moving at `velocity` over a single block, returning its new position and
+/// velocity.
+fn tick_of(delta_time: f64, x: f64, z: f64, velocity: (f64, f64, f64)) -> (Position, Velocity) {
+    let mut circuit = new_circuit().expect("failed to build DBSP circuit");
+    circuit.set_physics_config(crate::PhysicsConfig {
+        delta_time: delta_time.into(),
+        ..crate::PhysicsConfig::default()
+    });
+    circuit.block_in().push(block(1, (0, 0, 0)), 1);
+    circuit.position_in().push(
+        Position {
+            entity: 1,
+            x: x.into(),
+            y: 0.5.into(),
+            z: z.into(),
+        },
+        1,
+    );
+    circuit.velocity_in().push(vel(1, velocity), 1);
+
+    step_named(&mut circuit, "tick_of");
+
+    let pos_out: Vec<NewPosition> = circuit
+        .new_position_out()
+        .consolidate()
+        .iter()
+        .map(|t| t.0)
+        .collect();
+    let vel_out: Vec<NewVelocity> = circuit
+        .new_velocity_out()
+        .consolidate()
+        .iter()
+        .map(|t| t.0)
+        .collect();
+    (
+        *expect_single(pos_out.as_slice(), "position output"),
+        *expect_single(vel_out.as_slice(), "velocity output"),
+    )
+}
+
+#[rstest]
+#[case::whole_second(1.0)]
+#[case::half_second(0.5)]
+#[case::sixtieth(1.0 / 60.0)]
+fn standing_motion_scales_with_delta_time(#[case] delta_time: f64) {
+    let (position, velocity) = tick_of(delta_time, 0.1, 1.0, (0.5, 0.0, 0.0));
+
+    let damped = apply_friction_over(0.5, GROUND_FRICTION, delta_time);
+    assert_relative_eq!(velocity.vx.into_inner(), damped);
+    assert_relative_eq!(position.x.into_inner(), 0.1 + damped * delta_time);
+    assert_relative_eq!(position.z.into_inner(), 1.0);
+}
+
+#[rstest]
+#[case::whole_second(1.0)]
+#[case::half_second(0.5)]
+#[case::sixtieth(1.0 / 60.0)]
+fn falling_motion_scales_with_delta_time(#[case] delta_time: f64) {
+    let (position, velocity) = tick_of(delta_time, 0.5, 5.0, (0.25, 0.0, 0.0));
+
+    let vz = GRAVITY_PULL * delta_time;
+    let vx = apply_friction_over(0.25, AIR_FRICTION, delta_time);
+    assert_relative_eq!(velocity.vz.into_inner(), vz);
+    assert_relative_eq!(velocity.vx.into_inner(), vx);
+    assert_relative_eq!(position.z.into_inner(), 5.0 + vz * delta_time);
+    assert_relative_eq!(position.x.into_inner(), 0.5 + vx * delta_time);
+}
+
+#[rstest]
+#[case::default_coefficient(None, AIR_FRICTION)]
+#[case::override_coefficient(Some(0.5), 0.5)]
+#[case::no_drag(Some(0.0), 0.0)]
+fn airborne_air_drag(#[case] coefficient: Option<f64>, #[case] expected_drag: f64) {
     let mut circuit = new_circuit().expect("failed to build DBSP circuit");
 
     circuit.block_in().push(block(1, (0, 0, 0)), 1);
@@ -300,9 +622,18 @@ fn airborne_preserves_velocity() {
         },
         1,
     );
-    circuit.velocity_in().push(vel(1, (1.0, 0.0, 0.0)), 1);
+    circuit.velocity_in().push(vel(1, (1.0, -0.5, 0.0)), 1);
+    if let Some(value) = coefficient {
+        circuit.drag_in().push(
+            Drag {
+                entity: 1,
+                coefficient: value.into(),
+            },
+            1,
+        );
+    }
 
-    step_named(&mut circuit, "airborne_preserves_velocity");
+    step_named(&mut circuit, "airborne_air_drag");
 
     let vel_out: Vec<NewVelocity> = circuit
         .new_velocity_out()
@@ -311,8 +642,11 @@ fn airborne_preserves_velocity() {
         .map(|t| t.0)
         .collect();
     let velocity = expect_single(vel_out.as_slice(), "velocity output");
-    assert_relative_eq!(velocity.vx.into_inner(), 1.0);
-    assert_relative_eq!(velocity.vy.into_inner(), 0.0);
+    assert_relative_eq!(velocity.vx.into_inner(), apply_friction(1.0, expected_drag));
+    assert_relative_eq!(
+        velocity.vy.into_inner(),
+        apply_friction(-0.5, expected_drag)
+    );
     assert_relative_eq!(velocity.vz.into_inner(), GRAVITY_PULL);
 }
 
@@ -367,3 +701,90 @@ fn terminal_velocity_clamping(#[case] start_vz: f64, #[case] expected_vz: f64) {
 fn default_mass_is_positive() {
     assert!(crate::DEFAULT_MASS > 0.0, "DEFAULT_MASS must be > 0.0");
 }
+
+fn impulse(entity: i64, (dvx, dvy, dvz): (f64, f64, f64)) -> Impulse {
+    Impulse {
+        entity,
+        dvx: dvx.into(),
+        dvy: dvy.into(),
+        dvz: dvz.into(),
+    }
+}
+
+#[rstest]
+#[case::jump((0.0, 0.0, 3.0), (0.5, 3.0), (0.0, 3.0 + GRAVITY_PULL))]
+#[case::knock_back((0.5, 0.0, 0.0), (0.5 + apply_ground_friction(0.5), 1.0), (apply_ground_friction(0.5), 0.0))]
+#[case::downward_push((0.0, 0.0, -3.0), (0.5, 1.0), (0.0, 0.0))]
+fn impulse_on_standing_entity(
+    #[case] dv: (f64, f64, f64),
+    #[case] expected_position: (f64, f64),
+    #[case] expected_velocity: (f64, f64),
+) {
+    let (expected_x, expected_z) = expected_position;
+    let (expected_vx, expected_vz) = expected_velocity;
+    let mut circuit = new_circuit().expect("failed to build DBSP circuit");
+
+    circuit.block_in().push(block(1, (0, 0, 0)), 1);
+    circuit.position_in().push(
+        Position {
+            entity: 1,
+            x: 0.5.into(),
+            y: 0.5.into(),
+            z: 1.0.into(),
+        },
+        1,
+    );
+    circuit.velocity_in().push(vel(1, (0.0, 0.0, 0.0)), 1);
+    circuit.impulse_in().push(impulse(1, dv), 1);
+
+    step_named(&mut circuit, "impulse_on_standing_entity");
+
+    let pos_out: Vec<NewPosition> = circuit
+        .new_position_out()
+        .consolidate()
+        .iter()
+        .map(|t| t.0)
+        .collect();
+    let position = expect_single(pos_out.as_slice(), "position output");
+    assert_relative_eq!(position.x.into_inner(), expected_x);
+    assert_relative_eq!(position.z.into_inner(), expected_z);
+
+    let vel_out: Vec<NewVelocity> = circuit
+        .new_velocity_out()
+        .consolidate()
+        .iter()
+        .map(|t| t.0)
+        .collect();
+    let velocity = expect_single(vel_out.as_slice(), "velocity output");
+    assert_relative_eq!(velocity.vx.into_inner(), expected_vx);
+    assert_relative_eq!(velocity.vz.into_inner(), expected_vz);
+}
+
+#[test]
+fn impulse_adds_to_airborne_velocity() {
+    let mut circuit = new_circuit().expect("failed to build DBSP circuit");
+
+    circuit.block_in().push(block(1, (0, 0, 0)), 1);
+    circuit.position_in().push(
+        Position {
+            entity: 1,
+            x: 0.5.into(),
+            y: 0.5.into(),
+            z: 5.0.into(),
+        },
+        1,
+    );
+    circuit.velocity_in().push(vel(1, (0.0, 0.0, -2.0)), 1);
+    circuit.impulse_in().push(impulse(1, (0.0, 0.0, 2.5)), 1);
+
+    step_named(&mut circuit, "impulse_adds_to_airborne_velocity");
+
+    let vel_out: Vec<NewVelocity> = circuit
+        .new_velocity_out()
+        .consolidate()
+        .iter()
+        .map(|t| t.0)
+        .collect();
+    let velocity = expect_single(vel_out.as_slice(), "velocity output");
+    assert_relative_eq!(velocity.vz.into_inner(), 0.5 + GRAVITY_PULL);
+}
@@ -4,7 +4,9 @@ use crate::components::{Block, BlockSlope};
 use crate::dbsp_circuit::step_named;
 use crate::dbsp_circuit::streams::test_utils::{block, new_circuit, pos, slope};
 use crate::dbsp_circuit::{Position, PositionFloor};
+use approx::assert_relative_eq;
 use rstest::rstest;
+use test_utils::expect_single;
 
 fn pf(position: Position, z_floor: f64) -> PositionFloor {
     PositionFloor {
@@ -30,7 +32,7 @@ fn pf(position: Position, z_floor: f64) -> PositionFloor {
     vec![block(1, (-1, -1, 0))],
     vec![slope(1, (1.0, 0.0))],
     vec![pos(2, (-0.8, -0.2, 3.0))],
-    vec![pf(pos(2, (-0.8, -0.2, 3.0)),1.5)],
+    vec![pf(pos(2, (-0.8, -0.2, 3.0)),1.2)],
 )]
 fn position_floor_cases(
     #[case] blocks: Vec<Block>,
@@ -62,6 +64,48 @@ fn position_floor_cases(
     assert_eq!(vals, exp);
 }
 
+#[rstest]
+#[case::low_edge(0.0, 1.0)]
+#[case::quarter(0.25, 1.25)]
+#[case::centre(0.5, 1.5)]
+#[case::three_quarters(0.75, 1.75)]
+fn sloped_floor_follows_position(#[case] x: f64, #[case] expected: f64) {
+    let mut circuit = new_circuit().expect("failed to build DBSP circuit");
+    circuit.block_in().push(block(1, (0, 0, 0)), 1);
+    circuit.block_slope_in().push(slope(1, (1.0, 0.0)), 1);
+    circuit.position_in().push(pos(1, (x, 0.5, 3.0)), 1);
+    step_named(&mut circuit, "sloped_floor_follows_position");
+    let vals: Vec<PositionFloor> = circuit
+        .position_floor_out()
+        .consolidate()
+        .iter()
+        .map(|(pf, (), _timestamp)| pf)
+        .collect();
+    let floor = expect_single(vals.as_slice(), "position floor output");
+    assert_relative_eq!(floor.z_floor.into_inner(), expected);
+}
+
+#[rstest]
+#[case::under_bridge(1.0, Some(1.0))]
+#[case::in_the_gap(2.5, Some(1.0))]
+#[case::on_the_deck(4.0, Some(4.0))]
+#[case::embedded_in_deck(3.5, Some(4.0))]
+#[case::below_the_ground(-0.5, None)]
+fn bridge_floor_depends_on_height(#[case] z: f64, #[case] expected: Option<f64>) {
+    let mut circuit = new_circuit().expect("failed to build DBSP circuit");
+    circuit.block_in().push(block(1, (0, 0, 0)), 1);
+    circuit.block_in().push(block(2, (0, 0, 3)), 1);
+    circuit.position_in().push(pos(1, (0.5, 0.5, z)), 1);
+    step_named(&mut circuit, "bridge_floor_depends_on_height");
+    let vals: Vec<f64> = circuit
+        .position_floor_out()
+        .consolidate()
+        .iter()
+        .map(|(pf, (), _timestamp)| pf.z_floor.into_inner())
+        .collect();
+    assert_eq!(vals, expected.into_iter().collect::<Vec<_>>());
+}
+
 #[test]
 fn multiple_positions_same_grid_cell() {
     let mut circuit = new_circuit().expect("failed to build DBSP circuit");
@@ -4,19 +4,28 @@
 //! helpers for building the overall circuit.
 
 pub(super) mod behaviour;
+pub(super) mod collision;
+pub(super) mod config;
 pub(super) mod floor;
 pub(super) mod health;
 pub(super) mod kinematics;
+pub(super) mod platform;
+pub(super) mod vector_sum;
 
 #[cfg(test)]
 pub mod test_utils;
 
 pub use behaviour::{
-    apply_movement, fear_level_stream, movement_decision_stream, movement_decision_streams,
+    apply_movement, fear_level_stream, fleeing_goal_stream, movement_decision_stream,
+    movement_decision_streams, movement_steps, steering_goal_stream, steering_streams,
+    threat_fear_stream, SteeringContext,
 };
-pub use floor::{floor_height_stream, highest_block_pair};
-pub use health::{fall_damage_stream, health_delta_stream};
+pub use collision::{apply_separation, separation_stream, wall_collision_stream};
+pub use floor::{floor_height_stream, highest_block_pair, layered_floor_height_stream};
+pub use health::{fall_damage_stream, health_delta_stream, kill_plane_damage_stream};
 pub use kinematics::{
-    new_position_stream, new_velocity_stream, position_floor_stream, standing_motion_stream,
-    PositionFloor,
+    air_drag_stream, impulse_velocity_stream, new_position_stream, new_velocity_stream,
+    position_floor_stream, standing_motion_stream, swept_fall_stream, void_position_stream,
+    PositionFloor, SweptFall,
 };
+pub use platform::{platform_floor_stream, platform_rider_stream};
@@ -0,0 +1,109 @@
+//! Moving platform streams.
+//!
+//! A platform is a group of [`PlatformBlock`] records travelling at their own
+//! velocity. These helpers derive the floor surfaces a platform contributes
+//! once it has moved for the tick, and carry the entities standing on it
+//! along with it.
+
+use dbsp::{
+    operator::{Max, Min},
+    typed_batch::OrdZSet,
+    RootCircuit, Stream,
+};
+use ordered_float::OrderedFloat;
+
+use crate::numeric::floor_to_i32;
+use crate::{BLOCK_CENTRE_OFFSET, BLOCK_TOP_OFFSET, GRACE_DISTANCE};
+
+use crate::dbsp_circuit::{FloorHeightAt, PlatformBlock, Position};
+
+/// Displacement of a platform over one tick, in blocks.
+type Displacement = (OrderedFloat<f64>, OrderedFloat<f64>, OrderedFloat<f64>);
+
+/// Grid cell holding most of the block's footprint.
+fn block_cell(block: &PlatformBlock) -> (i32, i32) {
+    let centre = |coord: OrderedFloat<f64>| floor_to_i32(coord + BLOCK_CENTRE_OFFSET);
+    (centre(block.x), centre(block.y))
+}
+
+/// Pairs each floor surface of the platforms with its platform's velocity.
+///
+/// Blocks are grouped per platform by [`block_cell`]; each column yields one
+/// flat surface on top of its highest block whose band starts at the
+/// underside of its lowest, so gaps inside a platform column count as solid.
+fn platform_surfaces(
+    blocks: &Stream<RootCircuit, OrdZSet<PlatformBlock>>,
+) -> Stream<RootCircuit, OrdZSet<(FloorHeightAt, Displacement)>> {
+    let columns = blocks.map_index(|b| {
+        let (x, y) = block_cell(b);
+        ((b.platform, x, y, (b.vx, b.vy, b.vz)), b.z)
+    });
+    columns.aggregate(Max).join(
+        &columns.aggregate(Min),
+        |&(_, x, y, velocity), &top, &bottom| {
+            let surface = FloorHeightAt {
+                x,
+                y,
+                z: top + BLOCK_TOP_OFFSET,
+                grad_x: OrderedFloat(0.0),
+                grad_y: OrderedFloat(0.0),
+                base: bottom,
+                ceiling: None,
+            };
+            (surface, velocity)
+        },
+    )
+}
+
+/// Derives the floor surfaces of moving platforms at the end of the tick.
+///
+/// Every block is [advanced](PlatformBlock::advanced) by its platform's
+/// velocity before the columns are built, so carried riders, walkers and
+/// falling entities all meet the platform where it will be once the tick has
+/// run. The surfaces are uncapped; stack them with the terrain through
+/// [`layered_floor_height_stream`](super::floor::layered_floor_height_stream).
+#[must_use]
+pub fn platform_floor_stream(
+    blocks: &Stream<RootCircuit, OrdZSet<PlatformBlock>>,
+) -> Stream<RootCircuit, OrdZSet<FloorHeightAt>> {
+    platform_surfaces(&blocks.map(PlatformBlock::advanced)).map(|(surface, _)| *surface)
+}
+
+/// Carries entities standing on a moving platform along with it.
+///
+/// An entity rides a platform when, at the start of the tick, a platform
+/// surface in its cell [supports](FloorHeightAt::supports) it and it is
+/// within [`GRACE_DISTANCE`] of the deck. Riders are displaced by the
+/// platform's velocity before standing motion runs, so they keep their
+/// footing on the surfaces from [`platform_floor_stream`] and then walk,
+/// fall or collide from there. Other positions pass through unchanged; riders
+/// are swapped out by subtraction so record weights carry over.
+#[must_use]
+pub fn platform_rider_stream(
+    positions: &Stream<RootCircuit, OrdZSet<Position>>,
+    blocks: &Stream<RootCircuit, OrdZSet<PlatformBlock>>,
+) -> Stream<RootCircuit, OrdZSet<Position>> {
+    let riders = positions
+        .map_index(|p| ((floor_to_i32(p.x), floor_to_i32(p.y)), *p))
+        .join(
+            &platform_surfaces(blocks).map_index(|&(fh, velocity)| ((fh.x, fh.y), (fh, velocity))),
+            |_, p, &(fh, velocity)| (*p, rides(p, &fh).then_some(velocity)),
+        )
+        .flat_map(|&(p, carried_by)| carried_by.map(|velocity| (p, velocity)));
+    let carried = riders.map(|&(p, (vx, vy, vz))| Position {
+        x: p.x + vx,
+        y: p.y + vy,
+        z: p.z + vz,
+        ..p
+    });
+    positions.minus(&riders.map(|(p, _)| *p)).plus(&carried)
+}
+
+/// Returns `true` when `surface` carries an entity at `position`.
+fn rides(position: &Position, surface: &FloorHeightAt) -> bool {
+    surface.supports(position.z)
+        && position.z.into_inner() <= surface.z.into_inner() + GRACE_DISTANCE
+}
+
+#[cfg(test)]
+mod tests;
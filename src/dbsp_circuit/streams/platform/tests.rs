@@ -0,0 +1,151 @@
+//! Tests for moving platform surfaces and the riders they carry.
+
+use crate::dbsp_circuit::step_named;
+use crate::dbsp_circuit::streams::test_utils::{block, new_circuit, pos, vel};
+use crate::dbsp_circuit::{FloorHeightAt, NewPosition, NewVelocity, PlatformBlock};
+use approx::assert_relative_eq;
+use rstest::rstest;
+use test_utils::expect_single;
+
+fn platform_block((x, y, z): (f64, f64, f64), (vx, vy, vz): (f64, f64, f64)) -> PlatformBlock {
+    PlatformBlock {
+        platform: 7,
+        x: x.into(),
+        y: y.into(),
+        z: z.into(),
+        vx: vx.into(),
+        vy: vy.into(),
+        vz: vz.into(),
+    }
+}
+
+fn surfaces_at(floors: &[FloorHeightAt], cell: (i32, i32)) -> Vec<FloorHeightAt> {
+    let mut found: Vec<_> = floors
+        .iter()
+        .filter(|fh| (fh.x, fh.y) == cell)
+        .copied()
+        .collect();
+    found.sort_by_key(|fh| fh.base);
+    found
+}
+
+#[rstest]
+#[case::lift(platform_block((0.0, 0.0, 0.0), (0.0, 0.0, 0.5)), (0, 0), 1.5, 0.5)]
+#[case::ferry(platform_block((2.0, 0.0, 1.0), (1.0, 0.0, 0.0)), (3, 0), 2.0, 1.0)]
+#[case::half_cell_rounds_to_nearest(platform_block((0.0, 0.0, 0.0), (0.4, 0.0, 0.0)), (0, 0), 1.0, 0.0)]
+fn platform_surface_moves_with_its_velocity(
+    #[case] platform: PlatformBlock,
+    #[case] cell: (i32, i32),
+    #[case] expected_z: f64,
+    #[case] expected_base: f64,
+) {
+    let mut circuit = new_circuit().expect("failed to build DBSP circuit");
+    circuit.platform_block_in().push(platform, 1);
+
+    step_named(&mut circuit, "platform_surface_moves_with_its_velocity");
+
+    let floors: Vec<FloorHeightAt> = circuit
+        .floor_height_out()
+        .consolidate()
+        .iter()
+        .map(|(fh, (), _)| fh)
+        .collect();
+    let surface = expect_single(floors.as_slice(), "platform surface");
+    assert_eq!((surface.x, surface.y), cell);
+    assert_relative_eq!(surface.z.into_inner(), expected_z);
+    assert_relative_eq!(surface.base.into_inner(), expected_base);
+    assert_eq!(surface.ceiling, None);
+}
+
+#[test]
+fn platform_caps_the_terrain_beneath_it() {
+    let mut circuit = new_circuit().expect("failed to build DBSP circuit");
+    circuit.block_in().push(block(1, (0, 0, 0)), 1);
+    circuit
+        .platform_block_in()
+        .push(platform_block((0.0, 0.0, 3.0), (0.0, 0.0, 0.0)), 1);
+    circuit
+        .platform_block_in()
+        .push(platform_block((0.0, 0.0, 4.0), (0.0, 0.0, 0.0)), 1);
+
+    step_named(&mut circuit, "platform_caps_the_terrain_beneath_it");
+
+    let floors: Vec<FloorHeightAt> = circuit
+        .floor_height_out()
+        .consolidate()
+        .iter()
+        .map(|(fh, (), _)| fh)
+        .collect();
+    let column = surfaces_at(&floors, (0, 0));
+    let [ground, deck] = column.as_slice() else {
+        panic!("expected ground and platform deck, got {column:?}");
+    };
+    assert_relative_eq!(ground.z.into_inner(), 1.0);
+    assert_eq!(ground.ceiling, Some(3.0.into()));
+    assert_relative_eq!(deck.z.into_inner(), 5.0);
+    assert_eq!(deck.ceiling, None);
+}
+
+#[rstest]
+#[case::ferry_carries_rider((1.0, 0.0, 0.0), (1.5, 0.5, 1.0))]
+#[case::lift_raises_rider((0.0, 0.0, 0.5), (0.5, 0.5, 1.5))]
+#[case::lift_lowers_rider((0.0, 0.0, -0.5), (0.5, 0.5, 0.5))]
+fn rider_inherits_platform_displacement(
+    #[case] velocity: (f64, f64, f64),
+    #[case] expected: (f64, f64, f64),
+) {
+    let mut circuit = new_circuit().expect("failed to build DBSP circuit");
+    circuit.block_in().push(block(1, (0, 0, -5)), 1);
+    circuit
+        .platform_block_in()
+        .push(platform_block((0.0, 0.0, 0.0), velocity), 1);
+    circuit.position_in().push(pos(1, (0.5, 0.5, 1.0)), 1);
+    circuit.velocity_in().push(vel(1, (0.0, 0.0, 0.0)), 1);
+
+    step_named(&mut circuit, "rider_inherits_platform_displacement");
+
+    let positions: Vec<NewPosition> = circuit
+        .new_position_out()
+        .consolidate()
+        .iter()
+        .map(|(p, (), _)| p)
+        .collect();
+    let velocities: Vec<NewVelocity> = circuit
+        .new_velocity_out()
+        .consolidate()
+        .iter()
+        .map(|(v, (), _)| v)
+        .collect();
+    let position = expect_single(positions.as_slice(), "position output");
+    let new_velocity = expect_single(velocities.as_slice(), "velocity output");
+    assert_relative_eq!(position.x.into_inner(), expected.0);
+    assert_relative_eq!(position.y.into_inner(), expected.1);
+    assert_relative_eq!(position.z.into_inner(), expected.2);
+    assert_relative_eq!(new_velocity.vz.into_inner(), 0.0);
+}
+
+#[rstest]
+#[case::standing_beside((1.5, 0.5, 1.0), (1.5, 0.5, 1.0))]
+#[case::airborne_above((0.5, 0.5, 3.0), (0.5, 0.5, 2.0))]
+fn non_riders_are_not_carried(#[case] start: (f64, f64, f64), #[case] expected: (f64, f64, f64)) {
+    let mut circuit = new_circuit().expect("failed to build DBSP circuit");
+    circuit.block_in().push(block(1, (1, 0, 0)), 1);
+    circuit
+        .platform_block_in()
+        .push(platform_block((0.0, 0.0, 0.0), (0.0, 1.0, 0.0)), 1);
+    circuit.position_in().push(pos(1, start), 1);
+    circuit.velocity_in().push(vel(1, (0.0, 0.0, 0.0)), 1);
+
+    step_named(&mut circuit, "non_riders_are_not_carried");
+
+    let positions: Vec<NewPosition> = circuit
+        .new_position_out()
+        .consolidate()
+        .iter()
+        .map(|(p, (), _)| p)
+        .collect();
+    let position = expect_single(positions.as_slice(), "position output");
+    assert_relative_eq!(position.x.into_inner(), expected.0);
+    assert_relative_eq!(position.y.into_inner(), expected.1);
+    assert_relative_eq!(position.z.into_inner(), expected.2);
+}
@@ -0,0 +1,73 @@
+//! Per-entity sums of 2D vectors.
+//!
+//! Physical separation folds the pushes an entity receives, and steering folds
+//! its weighted terms, into one vector per entity. Both share the fold below.
+
+use dbsp::{
+    algebra::Semigroup,
+    operator::Fold,
+    typed_batch::{OrdIndexedZSet, OrdZSet},
+    DBData, RootCircuit, Stream,
+};
+use ordered_float::OrderedFloat;
+
+crate::dbsp_copy_record! {
+    /// Running total of the vectors folded for one entity.
+    pub(crate) struct VectorSum {
+        /// Summed X component.
+        pub(crate) dx: OrderedFloat<f64>,
+        /// Summed Y component.
+        pub(crate) dy: OrderedFloat<f64>,
+    }
+}
+
+impl VectorSum {
+    /// Folds one vector in, counted `weight` times.
+    fn add(&mut self, (dx, dy): (OrderedFloat<f64>, OrderedFloat<f64>), weight: i64) {
+        #[expect(
+            clippy::cast_precision_loss,
+            reason = "Z-set weights are tiny, so the conversion is exact in practice"
+        )]
+        let scaled = weight as f64;
+        self.dx = OrderedFloat(self.dx.into_inner() + dx.into_inner() * scaled);
+        self.dy = OrderedFloat(self.dy.into_inner() + dy.into_inner() * scaled);
+    }
+}
+
+#[derive(Clone)]
+struct VectorSumSemigroup;
+
+impl Semigroup<VectorSum> for VectorSumSemigroup {
+    fn combine(left: &VectorSum, right: &VectorSum) -> VectorSum {
+        VectorSum {
+            dx: OrderedFloat(left.dx.into_inner() + right.dx.into_inner()),
+            dy: OrderedFloat(left.dy.into_inner() + right.dy.into_inner()),
+        }
+    }
+}
+
+/// Sums the vectors of `terms` per entity.
+///
+/// `split` returns the entity each record belongs to and the `(dx, dy)` vector
+/// it contributes. Entities without records emit no sum.
+pub(crate) fn sum_vectors<T, F>(
+    terms: &Stream<RootCircuit, OrdZSet<T>>,
+    split: F,
+) -> Stream<RootCircuit, OrdIndexedZSet<i64, VectorSum>>
+where
+    T: DBData,
+    F: Fn(&T) -> (i64, (OrderedFloat<f64>, OrderedFloat<f64>)) + 'static,
+{
+    terms.map_index(split).aggregate(Fold::<
+        (OrderedFloat<f64>, OrderedFloat<f64>),
+        VectorSum,
+        VectorSumSemigroup,
+        _,
+        _,
+    >::new(
+        VectorSum::default(),
+        |acc: &mut VectorSum, vector: &(OrderedFloat<f64>, OrderedFloat<f64>), weight: i64| {
+            acc.add(*vector, weight);
+        },
+    ))
+}
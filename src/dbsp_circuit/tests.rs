@@ -376,3 +376,487 @@ fn lethal_damage_sets_death_flag(#[case] params: LethalCase) {
         },
     );
 }
+
+#[rstest]
+fn falling_below_kill_plane_is_lethal(#[values(1, 4)] workers: usize) {
+    let mut circuit = DbspCircuit::with_workers(workers).expect("failed to build DBSP circuit");
+    circuit.position_in().push(
+        Position {
+            entity: 1,
+            x: 0.5.into(),
+            y: 0.5.into(),
+            z: (crate::KILL_PLANE_Z + 0.5).into(),
+        },
+        1,
+    );
+    circuit.velocity_in().push(
+        Velocity {
+            entity: 1,
+            vx: 0.0.into(),
+            vy: 0.0.into(),
+            vz: 0.0.into(),
+        },
+        1,
+    );
+    circuit.health_state_in().push(
+        HealthState {
+            entity: 1,
+            current: 40,
+            max: 100,
+        },
+        1,
+    );
+
+    step_named(&mut circuit, "falling_below_kill_plane_is_lethal");
+
+    let deltas: Vec<HealthDelta> = circuit
+        .health_delta_out()
+        .consolidate()
+        .iter()
+        .map(|(delta, (), _)| delta)
+        .collect();
+    let delta = test_utils::expect_single(&deltas, "kill plane health delta");
+    assert_eq!(delta.entity, 1);
+    assert_eq!(delta.delta, -40);
+    assert!(delta.death);
+}
+
+#[rstest]
+fn fast_fall_lands_and_damages_on_same_tick(#[values(1, 4)] workers: usize) {
+    let mut circuit = DbspCircuit::with_workers(workers).expect("failed to build DBSP circuit");
+    circuit.block_in().push(
+        crate::components::Block {
+            id: 1,
+            x: 0,
+            y: 0,
+            z: 0,
+        },
+        1,
+    );
+    circuit.position_in().push(
+        Position {
+            entity: 1,
+            x: 0.5.into(),
+            y: 0.5.into(),
+            z: 10.0.into(),
+        },
+        1,
+    );
+    circuit.velocity_in().push(
+        Velocity {
+            entity: 1,
+            vx: 0.0.into(),
+            vy: 0.0.into(),
+            vz: (1.0 - crate::TERMINAL_VELOCITY).into(),
+        },
+        1,
+    );
+    circuit.health_state_in().push(
+        HealthState {
+            entity: 1,
+            current: 100,
+            max: 100,
+        },
+        1,
+    );
+
+    step_named(&mut circuit, "fast_fall_lands_and_damages_on_same_tick");
+
+    let positions: Vec<Position> = circuit
+        .new_position_out()
+        .consolidate()
+        .iter()
+        .map(|(pos, (), _)| pos)
+        .collect();
+    let landed = test_utils::expect_single(&positions, "landed position");
+    // The fall is clamped to the top of the block rather than passing through.
+    approx::assert_relative_eq!(landed.z.into_inner(), 1.0);
+
+    let deltas: Vec<HealthDelta> = circuit
+        .health_delta_out()
+        .consolidate()
+        .iter()
+        .map(|(delta, (), _)| delta)
+        .collect();
+    let delta = test_utils::expect_single(&deltas, "landing health delta");
+    let expected =
+        (crate::TERMINAL_VELOCITY - crate::SAFE_LANDING_SPEED) * crate::FALL_DAMAGE_SCALE;
+    approx::assert_relative_eq!(f64::from(delta.delta), -expected.floor());
+    assert_eq!(delta.at_tick, 0);
+}
+
+/// Vertical velocities the circuit inserted on the last step.
+fn inserted_vertical_velocities(circuit: &DbspCircuit) -> Vec<f64> {
+    circuit
+        .new_velocity_out()
+        .consolidate()
+        .iter()
+        .filter(|(_, (), weight)| *weight > 0)
+        .map(|(vel, (), _)| vel.vz.into_inner())
+        .collect()
+}
+
+#[rstest]
+fn physics_config_change_applies_on_next_step(#[values(1, 4)] workers: usize) {
+    let mut circuit = DbspCircuit::with_workers(workers).expect("failed to build DBSP circuit");
+    circuit.position_in().push(
+        Position {
+            entity: 1,
+            x: 0.5.into(),
+            y: 0.5.into(),
+            z: 10.0.into(),
+        },
+        1,
+    );
+    circuit.velocity_in().push(
+        Velocity {
+            entity: 1,
+            vx: 0.0.into(),
+            vy: 0.0.into(),
+            vz: 0.0.into(),
+        },
+        1,
+    );
+
+    step_named(&mut circuit, "default configuration");
+    let initial = inserted_vertical_velocities(&circuit);
+    let default_vz = test_utils::expect_single(&initial, "default velocity");
+    approx::assert_relative_eq!(*default_vz, crate::GRAVITY_PULL);
+
+    circuit.clear_inputs();
+    circuit.set_physics_config(crate::PhysicsConfig {
+        gravity_pull: (-3.0).into(),
+        ..crate::PhysicsConfig::default()
+    });
+    step_named(&mut circuit, "updated configuration");
+    let updated = inserted_vertical_velocities(&circuit);
+    let updated_vz = test_utils::expect_single(&updated, "updated velocity");
+    approx::assert_relative_eq!(*updated_vz, -3.0);
+}
+
+/// Records the circuit inserted on the last step, ignoring retractions.
+fn inserted<T: dbsp::DBData>(output: &dbsp::OutputHandle<dbsp::typed_batch::OrdZSet<T>>) -> Vec<T> {
+    output
+        .consolidate()
+        .iter()
+        .filter(|(_, (), weight)| *weight > 0)
+        .map(|(record, (), _)| record)
+        .collect()
+}
+
+/// Feeds each step's outputs back in as the next step's inputs for `ticks`
+/// steps, returning the final state and every damage delta emitted.
+fn run_ticks(
+    circuit: &mut DbspCircuit,
+    mut position: Position,
+    mut velocity: Velocity,
+    ticks: usize,
+) -> (Position, Velocity, Vec<HealthDelta>) {
+    let mut damage = Vec::new();
+    for _ in 0..ticks {
+        step_named(circuit, "run_ticks");
+        damage.extend(
+            inserted(circuit.health_delta_out())
+                .into_iter()
+                .filter(|delta| delta.delta < 0),
+        );
+        // An unchanged record produces no delta, so keep the previous state.
+        let next_position = inserted(circuit.new_position_out())
+            .first()
+            .map_or(position, |moved| *moved);
+        let next_velocity = inserted(circuit.new_velocity_out())
+            .first()
+            .map_or(velocity, |moved| *moved);
+        circuit.position_in().push(position, -1);
+        circuit.velocity_in().push(velocity, -1);
+        circuit.position_in().push(next_position, 1);
+        circuit.velocity_in().push(next_velocity, 1);
+        position = next_position;
+        velocity = next_velocity;
+    }
+    (position, velocity, damage)
+}
+
+#[rstest]
+fn walking_off_a_ledge_falls_and_damages(#[values(1, 4)] workers: usize) {
+    let mut circuit = DbspCircuit::with_workers(workers).expect("failed to build DBSP circuit");
+    circuit.block_in().push(
+        crate::components::Block {
+            id: 1,
+            x: 0,
+            y: 0,
+            z: 29,
+        },
+        1,
+    );
+    for x in 1..=8 {
+        circuit.block_in().push(
+            crate::components::Block {
+                id: i64::from(x) + 1,
+                x,
+                y: 0,
+                z: 0,
+            },
+            1,
+        );
+    }
+    circuit.health_state_in().push(
+        HealthState {
+            entity: 1,
+            current: 100,
+            max: 100,
+        },
+        1,
+    );
+
+    let position = Position {
+        entity: 1,
+        x: 0.8.into(),
+        y: 0.5.into(),
+        z: 30.0.into(),
+    };
+    let velocity = Velocity {
+        entity: 1,
+        vx: 0.5.into(),
+        vy: 0.0.into(),
+        vz: 0.0.into(),
+    };
+    circuit.position_in().push(position, 1);
+    circuit.velocity_in().push(velocity, 1);
+
+    let (landed, _, damage) = run_ticks(&mut circuit, position, velocity, 12);
+    approx::assert_relative_eq!(landed.z.into_inner(), 1.0);
+    let delta = test_utils::expect_single(&damage, "ledge fall damage");
+    assert_eq!(delta.entity, 1);
+}
+
+#[rstest]
+#[case::hop(3.0, false)]
+#[case::high_jump(9.0, true)]
+fn jumping_entity_lands_through_the_fall_path(
+    #[case] dvz: f64,
+    #[case] damaged: bool,
+    #[values(1, 4)] workers: usize,
+) {
+    let mut circuit = DbspCircuit::with_workers(workers).expect("failed to build DBSP circuit");
+    circuit.block_in().push(
+        crate::components::Block {
+            id: 1,
+            x: 0,
+            y: 0,
+            z: 0,
+        },
+        1,
+    );
+    circuit.health_state_in().push(
+        HealthState {
+            entity: 1,
+            current: 100,
+            max: 100,
+        },
+        1,
+    );
+    let position = Position {
+        entity: 1,
+        x: 0.5.into(),
+        y: 0.5.into(),
+        z: 1.0.into(),
+    };
+    let velocity = Velocity {
+        entity: 1,
+        vx: 0.0.into(),
+        vy: 0.0.into(),
+        vz: 0.0.into(),
+    };
+    circuit.position_in().push(position, 1);
+    circuit.velocity_in().push(velocity, 1);
+    let jump = crate::dbsp_circuit::Impulse {
+        entity: 1,
+        dvx: 0.0.into(),
+        dvy: 0.0.into(),
+        dvz: dvz.into(),
+    };
+    circuit.impulse_in().push(jump, 1);
+
+    let (airborne, moving, mut damage) = run_ticks(&mut circuit, position, velocity, 1);
+    approx::assert_relative_eq!(airborne.z.into_inner(), dvz + crate::GRAVITY_PULL + 1.0);
+    // Inputs are deltas, so the impulse must be retracted to last one tick.
+    circuit.impulse_in().push(jump, -1);
+    let (landed, _, landing_damage) = run_ticks(&mut circuit, airborne, moving, 23);
+    damage.extend(landing_damage);
+
+    approx::assert_relative_eq!(landed.z.into_inner(), 1.0);
+    assert_eq!(!damage.is_empty(), damaged, "damage: {damage:?}");
+}
+
+/// Every record of `output` from the last step with its weight.
+fn weighted<T: dbsp::DBData>(
+    output: &dbsp::OutputHandle<dbsp::typed_batch::OrdZSet<T>>,
+) -> Vec<(T, i64)> {
+    output
+        .consolidate()
+        .iter()
+        .map(|(record, (), weight)| (record, weight))
+        .collect()
+}
+
+/// Outputs of one step of [`skirmish_outputs`].
+type StepOutputs = (
+    Vec<(Position, i64)>,
+    Vec<(Velocity, i64)>,
+    Vec<(HealthDelta, i64)>,
+    Vec<(PositionFloor, i64)>,
+);
+
+/// Pushes a healthy unit heading for the far side of the skirmish map.
+fn push_skirmish_unit(circuit: &DbspCircuit, entity: i64) {
+    #[expect(clippy::cast_precision_loss, reason = "Small test identifiers.")]
+    let offset = entity as f64;
+    let entity_id = u64::try_from(entity).expect("identifiers are positive");
+    circuit.position_in().push(
+        Position {
+            entity,
+            x: (0.5 + offset % 4.0).into(),
+            y: (0.5 + offset / 4.0).into(),
+            z: (1.0 + offset % 3.0).into(),
+        },
+        1,
+    );
+    circuit.velocity_in().push(
+        Velocity {
+            entity,
+            vx: 0.0.into(),
+            vy: 0.0.into(),
+            vz: 0.0.into(),
+        },
+        1,
+    );
+    circuit.extent_in().push(
+        Extent {
+            entity,
+            radius: 0.4.into(),
+        },
+        1,
+    );
+    circuit.target_in().push(
+        Target {
+            entity,
+            x: (5.0 - offset % 5.0).into(),
+            y: 2.5.into(),
+        },
+        1,
+    );
+    circuit.health_state_in().push(
+        HealthState {
+            entity: entity_id,
+            current: 100,
+            max: 100,
+        },
+        1,
+    );
+}
+
+/// Steps a scene of units walking, falling, jumping and crowding each other
+/// on `circuit`, returning every step's outputs.
+fn skirmish_outputs(mut circuit: DbspCircuit) -> Vec<StepOutputs> {
+    for x in 0..6 {
+        for y in 0..6 {
+            circuit.block_in().push(
+                crate::components::Block {
+                    id: i64::from(x * 6 + y),
+                    x,
+                    y,
+                    z: i32::from(x == 0),
+                },
+                1,
+            );
+        }
+    }
+    for entity in 0..12_i64 {
+        push_skirmish_unit(&circuit, entity);
+    }
+    circuit.impulse_in().push(
+        crate::dbsp_circuit::Impulse {
+            entity: 3,
+            dvx: 0.0.into(),
+            dvy: 0.0.into(),
+            dvz: 4.0.into(),
+        },
+        1,
+    );
+
+    (0..6)
+        .map(|_| {
+            step_named(&mut circuit, "skirmish");
+            (
+                weighted(circuit.new_position_out()),
+                weighted(circuit.new_velocity_out()),
+                weighted(circuit.health_delta_out()),
+                weighted(circuit.position_floor_out()),
+            )
+        })
+        .collect()
+}
+
+fn circuit_with_workers(workers: usize) -> DbspCircuit {
+    DbspCircuit::with_workers(workers).expect("failed to build DBSP circuit")
+}
+
+#[test]
+fn worker_count_does_not_change_outputs() {
+    let single = skirmish_outputs(circuit_with_workers(1));
+    assert!(
+        single.iter().any(|(positions, ..)| !positions.is_empty()),
+        "the scene should move its units"
+    );
+    assert_eq!(skirmish_outputs(circuit_with_workers(4)), single);
+}
+
+#[rstest]
+fn pipelined_steps_match_synchronous_steps(#[values(1, 2)] workers: usize) {
+    let pipelined = DbspCircuit::pipelined(workers).expect("failed to build DBSP circuit");
+    assert!(pipelined.is_pipelined());
+    assert_eq!(
+        skirmish_outputs(pipelined),
+        skirmish_outputs(circuit_with_workers(1))
+    );
+}
+
+#[test]
+fn records_pushed_during_a_pipelined_step_wait_for_the_next() {
+    let mut circuit = DbspCircuit::pipelined(1).expect("failed to build DBSP circuit");
+    circuit.block_in().push(
+        crate::components::Block {
+            id: 1,
+            x: 0,
+            y: 0,
+            z: 0,
+        },
+        1,
+    );
+    circuit.start_step();
+    assert!(circuit.is_stepping());
+    circuit.position_in().push(
+        Position {
+            entity: 1,
+            x: 0.5.into(),
+            y: 0.5.into(),
+            z: 1.0.into(),
+        },
+        1,
+    );
+    circuit.finish_step().expect("step failed");
+    assert!(!circuit.is_stepping());
+    assert!(weighted(circuit.position_floor_out()).is_empty());
+
+    circuit.start_step();
+    let outcome = loop {
+        if let Some(outcome) = circuit.poll_step() {
+            break outcome;
+        }
+        std::thread::yield_now();
+    };
+    outcome.expect("step failed");
+    let floors = weighted(circuit.position_floor_out());
+    assert_eq!(floors.len(), 1, "floors: {floors:?}");
+}
@@ -15,6 +15,8 @@
 
 use ordered_float::OrderedFloat;
 
+use crate::BLOCK_CENTRE_OFFSET;
+
 /// Stable identifier shared between Bevy and the DBSP circuit.
 pub type EntityId = u64;
 /// Authoritative simulation tick counter propagated with health deltas.
@@ -46,6 +48,8 @@ pub enum DamageSource {
     Fall,
     /// Script-driven healing or scripted damage applied upstream.
     Script,
+    /// Lethal damage applied when an entity falls below the kill plane.
+    OutOfBounds,
     /// Placeholder for bespoke downstream discriminators.
     Other {
         /// User-defined discriminator.
@@ -194,14 +198,208 @@ crate::dbsp_copy_record! {
 }
 
 crate::dbsp_copy_record! {
-    /// Floor height at a grid cell, accounting for slopes.
+    /// Floor surface at a grid cell, accounting for slopes.
+    ///
+    /// `z` is the height at the centre of the cell. The gradients describe how
+    /// the surface rises across the cell so [`FloorHeightAt::sample`] can
+    /// evaluate it under an entity's exact position.
+    ///
+    /// A cell holds one surface per vertical run of blocks, so bridges and
+    /// overhangs give several records for the same `(x, y)`. Each surface
+    /// supports the heights from the `base` of its run up to its `ceiling`,
+    /// and these bands never overlap within a cell.
     pub struct FloorHeightAt {
         /// Grid X coordinate of the evaluated floor.
         pub x: i32,
         /// Grid Y coordinate of the evaluated floor.
         pub y: i32,
-        /// Floor height including slope adjustments.
+        /// Floor height at the cell centre, including slope adjustments.
+        pub z: OrderedFloat<f64>,
+        /// Rise of the surface per block along the X axis.
+        pub grad_x: OrderedFloat<f64>,
+        /// Rise of the surface per block along the Y axis.
+        pub grad_y: OrderedFloat<f64>,
+        /// Underside of the lowest block in the run beneath the surface.
+        pub base: OrderedFloat<f64>,
+        /// Underside of the next run above the surface, or `None` when the
+        /// column is open to the sky.
+        pub ceiling: Option<OrderedFloat<f64>>,
+    }
+}
+
+impl FloorHeightAt {
+    /// Returns `true` when this surface supports an entity at height `z`.
+    ///
+    /// The band runs from the `base` of the solid run, so an entity sunk into
+    /// the blocks is lifted onto the surface, up to but excluding the
+    /// `ceiling`, where the band of the next surface begins.
+    #[must_use]
+    pub fn supports(&self, z: OrderedFloat<f64>) -> bool {
+        z >= self.base && self.ceiling.is_none_or(|ceiling| z < ceiling)
+    }
+
+    /// Floor height at the continuous coordinates `(x, y)`.
+    ///
+    /// Only the fractional parts of `x` and `y` are used, so the coordinates
+    /// are expected to lie within this cell. Flat cells return `z` everywhere.
+    ///
+    /// # Examples
+    /// ```
+    /// use lille::dbsp_circuit::FloorHeightAt;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let ramp = FloorHeightAt {
+    ///     x: 2,
+    ///     y: 0,
+    ///     z: OrderedFloat(1.5),
+    ///     grad_x: OrderedFloat(1.0),
+    ///     grad_y: OrderedFloat(0.0),
+    ///     base: OrderedFloat(0.0),
+    ///     ceiling: None,
+    /// };
+    /// assert_eq!(ramp.sample(OrderedFloat(2.25), OrderedFloat(0.5)), OrderedFloat(1.25));
+    /// assert_eq!(ramp.sample(OrderedFloat(2.5), OrderedFloat(0.9)), OrderedFloat(1.5));
+    /// ```
+    #[must_use]
+    pub fn sample(&self, x: OrderedFloat<f64>, y: OrderedFloat<f64>) -> OrderedFloat<f64> {
+        let offset = |coord: OrderedFloat<f64>| {
+            let value = coord.into_inner();
+            value - value.floor() - BLOCK_CENTRE_OFFSET
+        };
+        let (dx, dy) = (offset(x), offset(y));
+        OrderedFloat(
+            self.z.into_inner() + self.grad_x.into_inner() * dx + self.grad_y.into_inner() * dy,
+        )
+    }
+}
+
+crate::dbsp_copy_record! {
+    /// Horizontal collision extent of an entity.
+    ///
+    /// Units:
+    /// - `radius` is in blocks (1.0 == one block).
+    ///
+    /// Invariants:
+    /// - At most one `Extent` per `entity` per tick is expected upstream.
+    /// - Radii up to half a block are fully covered by the neighbour search;
+    ///   larger extents only interact with entities in adjacent grid cells.
+    pub struct Extent {
+        /// Entity the extent belongs to.
+        pub entity: i64,
+        /// Radius of the entity's collision circle.
+        pub radius: OrderedFloat<f64>,
+    }
+}
+
+crate::dbsp_copy_record! {
+    /// Per-entity air drag coefficient.
+    ///
+    /// Units:
+    /// - `coefficient` is the unitless fraction of horizontal velocity lost
+    ///   per tick while airborne, clamped to `[0, 1]` when applied.
+    ///
+    /// Invariants:
+    /// - At most one `Drag` per `entity` per tick is expected upstream.
+    /// - Entities without a record use
+    ///   [`PhysicsConfig::air_friction`](crate::PhysicsConfig::air_friction).
+    pub struct Drag {
+        /// Entity the coefficient belongs to.
+        pub entity: i64,
+        /// Fraction of horizontal velocity lost per airborne tick.
+        pub coefficient: OrderedFloat<f64>,
+    }
+}
+
+crate::dbsp_copy_record! {
+    /// Instantaneous change of velocity applied to an entity for one tick.
+    ///
+    /// Unlike a [`Force`], an impulse is not divided by mass and bypasses the
+    /// standing branch: an upward impulse lifts a grounded entity off its
+    /// floor, so jumps and knock-ups fall back through the unsupported path.
+    ///
+    /// Units:
+    /// - `dvx`, `dvy`, `dvz` are blocks per tick.
+    ///
+    /// Invariants:
+    /// - At most one `Impulse` per `entity` per tick is expected upstream.
+    pub struct Impulse {
+        /// Entity receiving the impulse.
+        pub entity: i64,
+        /// Change of velocity along the X axis.
+        pub dvx: OrderedFloat<f64>,
+        /// Change of velocity along the Y axis.
+        pub dvy: OrderedFloat<f64>,
+        /// Change of velocity along the Z axis.
+        pub dvz: OrderedFloat<f64>,
+    }
+}
+
+impl Impulse {
+    /// Returns `true` when the impulse lifts its entity off the ground.
+    #[must_use]
+    pub fn launches(&self) -> bool {
+        self.dvz.into_inner() > 0.0
+    }
+}
+
+crate::dbsp_copy_record! {
+    /// One block of a moving platform at the start of the tick.
+    ///
+    /// Blocks sharing a `platform` move together: each tick the platform's
+    /// floor surfaces advance by its velocity and entities standing on it are
+    /// carried along. Like a [`Block`](crate::components::Block), the record
+    /// names the lower corner of a unit cube, but at continuous coordinates.
+    ///
+    /// Units:
+    /// - `x`, `y`, `z` are in blocks (1.0 == one block).
+    /// - `vx`, `vy`, `vz` are blocks per tick.
+    ///
+    /// Invariants:
+    /// - Every block of a platform carries the same velocity.
+    /// - Platforms do not overlap one another.
+    pub struct PlatformBlock {
+        /// Platform the block belongs to.
+        pub platform: i64,
+        /// X coordinate of the block's lower corner.
+        pub x: OrderedFloat<f64>,
+        /// Y coordinate of the block's lower corner.
+        pub y: OrderedFloat<f64>,
+        /// Z coordinate of the block's underside.
         pub z: OrderedFloat<f64>,
+        /// Platform velocity along the X axis.
+        pub vx: OrderedFloat<f64>,
+        /// Platform velocity along the Y axis.
+        pub vy: OrderedFloat<f64>,
+        /// Platform velocity along the Z axis.
+        pub vz: OrderedFloat<f64>,
+    }
+}
+
+impl PlatformBlock {
+    /// Returns the block where its platform will be at the end of the tick.
+    #[must_use]
+    pub fn advanced(&self) -> Self {
+        Self {
+            x: self.x + self.vx,
+            y: self.y + self.vy,
+            z: self.z + self.vz,
+            ..*self
+        }
+    }
+}
+
+crate::dbsp_copy_record! {
+    /// Correction pushing an entity out of overlap with its neighbours.
+    ///
+    /// Units:
+    /// - `dx`, `dy` are world-units per tick.
+    pub struct Separation {
+        /// Entity to push.
+        pub entity: i64,
+        /// Correction along the X axis.
+        pub dx: OrderedFloat<f64>,
+        /// Correction along the Y axis.
+        pub dy: OrderedFloat<f64>,
     }
 }
 
@@ -223,6 +421,28 @@ crate::dbsp_copy_record! {
     }
 }
 
+crate::dbsp_copy_record! {
+    /// Next waypoint on the path an entity follows towards its [`Target`].
+    ///
+    /// Computed outside the circuit by the A* pathfinder and pushed in place of
+    /// the raw target, so movement steers around walls rather than straight at
+    /// the destination.
+    ///
+    /// Units:
+    /// - `x`, `y` are world coordinates in blocks (1.0 == one block).
+    ///
+    /// Invariants:
+    /// - One active `PathGoal` per `entity` per tick is expected upstream.
+    pub struct PathGoal {
+        /// Entity identifier to steer.
+        pub entity: i64,
+        /// X coordinate of the waypoint.
+        pub x: OrderedFloat<f64>,
+        /// Y coordinate of the waypoint.
+        pub y: OrderedFloat<f64>,
+    }
+}
+
 // `FearLevel` must remain non-`Copy` to avoid implicit duplication and to
 // permit future non-`Copy` fields. A compile-time test asserts this type never
 // gains `Copy` accidentally.
@@ -253,15 +473,86 @@ crate::dbsp_record! {
     }
 }
 
+crate::dbsp_copy_record! {
+    /// Civilian unit whose fear the circuit derives from nearby [`Baddie`]s.
+    ///
+    /// Units:
+    /// - `fraidiness` is unitless. It scales both the distance at which
+    ///   threats are noticed and the fear they cause.
+    ///
+    /// Invariants:
+    /// - At most one `Civvy` per `entity` per tick is expected upstream.
+    pub struct Civvy {
+        /// Entity identifier of the civilian.
+        pub entity: i64,
+        /// How easily the civilian is frightened.
+        pub fraidiness: OrderedFloat<f64>,
+    }
+}
+
+crate::dbsp_copy_record! {
+    /// Hostile unit that frightens nearby [`Civvy`] entities.
+    ///
+    /// Units:
+    /// - `meanness` is unitless and scales the fear the baddie causes.
+    ///
+    /// Invariants:
+    /// - At most one `Baddie` per `entity` per tick is expected upstream.
+    pub struct Baddie {
+        /// Entity identifier of the baddie.
+        pub entity: i64,
+        /// How frightening the baddie is.
+        pub meanness: OrderedFloat<f64>,
+    }
+}
+
+crate::dbsp_copy_record! {
+    /// Fear a civilian derives from the baddies around it.
+    ///
+    /// Units:
+    /// - `level` ∈ [0.0, 1.0] where higher implies greater fear.
+    /// - `x`, `y` are world coordinates in blocks of the threats' centre,
+    ///   weighted by the fear each causes.
+    ///
+    /// Invariants:
+    /// - At most one `ThreatFear` per `entity` per tick.
+    pub struct ThreatFear {
+        /// Frightened civilian.
+        pub entity: i64,
+        /// Fear caused by every baddie in range.
+        pub level: OrderedFloat<f64>,
+        /// X coordinate the civilian flees from.
+        pub x: OrderedFloat<f64>,
+        /// Y coordinate the civilian flees from.
+        pub y: OrderedFloat<f64>,
+    }
+}
+
+impl ThreatFear {
+    /// The fear level this record contributes to [`FearLevel`].
+    #[must_use]
+    pub const fn fear_level(&self) -> FearLevel {
+        FearLevel {
+            entity: self.entity,
+            level: self.level,
+        }
+    }
+}
+
 crate::dbsp_copy_record! {
     /// Decided unit movement vector for an entity.
     ///
     /// Units:
-    /// - `dx`, `dy` are world-units per tick.
+    /// - `dx`, `dy` are world-units per second; the circuit scales them by
+    ///   [`PhysicsConfig::delta_time`](crate::PhysicsConfig::delta_time) into
+    ///   a per-tick step.
     ///
     /// Semantics:
-    /// - The vector is normalised with a maximum magnitude of one; diagonal
-    ///   movement is not faster than axis-aligned movement.
+    /// - The vector has a maximum magnitude of one; diagonal movement is not
+    ///   faster than axis-aligned movement.
+    /// - Seek and flee decisions are unit vectors. The steering stage weights
+    ///   them, so a blended decision may be shorter, for example while an
+    ///   entity slows down on arrival.
     ///
     /// Invariants:
     /// - At most one `MovementDecision` per `entity` per tick is expected
@@ -269,13 +560,45 @@ crate::dbsp_copy_record! {
     pub struct MovementDecision {
         /// Entity to move.
         pub entity: i64,
-        /// Unit vector X component of the intended movement.
+        /// X component of the intended movement.
         pub dx: OrderedFloat<f64>,
-        /// Unit vector Y component of the intended movement.
+        /// Y component of the intended movement.
         pub dy: OrderedFloat<f64>,
     }
 }
 
+crate::dbsp_copy_record! {
+    /// Diagnostic record reporting that an entity has reached its target.
+    ///
+    /// Emitted by the steering stage for each approaching entity standing
+    /// within [`ARRIVAL_TOLERANCE`](crate::ARRIVAL_TOLERANCE) of its goal at
+    /// the start of the tick. Such entities receive no seek movement, so they
+    /// hold their position rather than oscillating around the target.
+    ///
+    /// Units:
+    /// - `x`, `y` are world coordinates in blocks (1.0 == one block).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lille::dbsp_circuit::Arrived;
+    ///
+    /// let arrived = Arrived {
+    ///     entity: 1,
+    ///     x: 2.5.into(),
+    ///     y: 0.5.into(),
+    /// };
+    /// assert_eq!(arrived.entity, 1);
+    /// ```
+    pub struct Arrived {
+        /// Entity that reached its target.
+        pub entity: i64,
+        /// Target X coordinate.
+        pub x: OrderedFloat<f64>,
+        /// Target Y coordinate.
+        pub y: OrderedFloat<f64>,
+    }
+}
+
 crate::dbsp_copy_record! {
     /// Diagnostic record reporting that several movement decisions for one
     /// entity were collapsed into a single normalized vector.
@@ -2,15 +2,20 @@
 
 use std::{collections::HashSet, mem};
 
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
+#[cfg(feature = "map")]
+use bevy_ecs_tiled::prelude::{MapCreated, TiledEvent};
 
 use crate::components::{
-    Block, BlockSlope, DdlogId, ForceComp, Health, Target as TargetComp, VelocityComp,
+    Block, BlockSlope, DdlogId, Door, DragComp, ExtentComp, ForceComp, Health, ImpulseComp,
+    MovingPlatform, PathGoal as PathGoalComp, Target as TargetComp, UnitType, VelocityComp,
 };
-use crate::dbsp_circuit::{DamageEvent, DbspCircuit, HealthState};
+use crate::dbsp_circuit::{DamageEvent, DbspCircuit, Impulse, PlatformBlock};
 #[cfg(feature = "map")]
 use crate::map::{PlayerSpawn, SpawnPoint};
 use crate::world_handle::WorldHandle;
+use crate::PhysicsConfig;
 
 use super::{DamageInbox, DbspState, IdQueries};
 
@@ -25,6 +30,101 @@ type EntityRow<'w> = (
 
 mod sync;
 
+/// Filter matching entities whose mirrored components were added or changed.
+type EntityChanged = Or<(
+    Changed<DdlogId>,
+    Changed<Transform>,
+    Changed<VelocityComp>,
+    Changed<TargetComp>,
+    Changed<PathGoalComp>,
+    Changed<Health>,
+    Changed<ForceComp>,
+    Changed<ExtentComp>,
+    Changed<DragComp>,
+    Changed<UnitType>,
+)>;
+
+/// Entity rows and the entities whose mirrored components changed.
+///
+/// The change filter reads `Health` while the rows write it, so the two
+/// queries share a [`ParamSet`].
+type EntityRows<'w, 's> = ParamSet<
+    'w,
+    's,
+    (
+        Query<'w, 's, EntityRow<'static>>,
+        Query<'w, 's, Entity, (With<DdlogId>, EntityChanged)>,
+    ),
+>;
+
+/// Queries over the entities mirrored into the circuit.
+///
+/// Only entities matched by `changed` or reported by one of the removal
+/// readers are looked up each frame, so idle entities cost nothing.
+#[derive(SystemParam)]
+pub struct EntityQueries<'w, 's> {
+    /// Rows of identified entities, and those whose mirrored components were
+    /// added or changed.
+    pub rows: EntityRows<'w, 's>,
+    /// Forces applied to identified entities.
+    pub forces: Query<'w, 's, (&'static DdlogId, &'static ForceComp)>,
+    /// Collision radii of identified entities.
+    pub extents: Query<'w, 's, (&'static DdlogId, &'static ExtentComp)>,
+    /// Drag coefficients of identified entities.
+    pub drags: Query<'w, 's, (&'static DdlogId, &'static DragComp)>,
+    /// Pathfinder waypoints of identified entities.
+    pub path_goals: Query<'w, 's, (&'static DdlogId, &'static PathGoalComp)>,
+    /// Behavioural archetypes of identified entities.
+    pub units: Query<'w, 's, (&'static DdlogId, &'static UnitType)>,
+    /// Entities that lost their `DdlogId`, including despawned ones.
+    pub removed_ids: RemovedComponents<'w, 's, DdlogId>,
+    /// Entities that lost their velocity.
+    pub removed_velocities: RemovedComponents<'w, 's, VelocityComp>,
+    /// Entities that lost their target.
+    pub removed_targets: RemovedComponents<'w, 's, TargetComp>,
+    /// Entities that lost their waypoint.
+    pub removed_path_goals: RemovedComponents<'w, 's, PathGoalComp>,
+    /// Entities that lost their health.
+    pub removed_health: RemovedComponents<'w, 's, Health>,
+    /// Entities that lost their force.
+    pub removed_forces: RemovedComponents<'w, 's, ForceComp>,
+    /// Entities that lost their collision radius.
+    pub removed_extents: RemovedComponents<'w, 's, ExtentComp>,
+    /// Entities that lost their drag coefficient.
+    pub removed_drags: RemovedComponents<'w, 's, DragComp>,
+    /// Entities that lost their behavioural archetype.
+    pub removed_units: RemovedComponents<'w, 's, UnitType>,
+}
+
+/// Filter matching entities whose block, slope or door was added or changed.
+type TerrainChanged = Or<(Changed<Block>, Changed<BlockSlope>, Changed<Door>)>;
+
+/// Queries over the terrain mirrored into the circuit.
+#[derive(SystemParam)]
+pub struct TerrainQueries<'w, 's> {
+    /// Terrain blocks with their slopes and, for doors, their door state.
+    pub blocks: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Block,
+            Option<&'static BlockSlope>,
+            Option<&'static Door>,
+        ),
+    >,
+    /// Entities whose block, slope or door was added or changed.
+    pub changed: Query<'w, 's, Entity, TerrainChanged>,
+    /// Entities that lost their block.
+    pub removed_blocks: RemovedComponents<'w, 's, Block>,
+    /// Entities that lost their slope.
+    pub removed_slopes: RemovedComponents<'w, 's, BlockSlope>,
+    /// Entities that stopped being doors.
+    pub removed_doors: RemovedComponents<'w, 's, Door>,
+    /// Moving platforms, pushed at their current transform every frame.
+    pub platforms: Query<'w, 's, (Entity, &'static Transform, &'static MovingPlatform)>,
+}
+
 /// Initializes the [`DbspState`] resource in the provided [`World`].
 ///
 /// Call this once during Bevy startup before running any DBSP synchronisation
@@ -38,15 +138,42 @@ pub fn init_dbsp_system(world: &mut World) -> Result<(), dbsp::Error> {
     Ok(())
 }
 
+/// Forwards the [`PhysicsConfig`] resource to the DBSP circuit.
+///
+/// Runs before [`cache_state_for_dbsp_system`] and only touches the circuit
+/// when the resource was inserted or modified, so a tuning change applies
+/// from the next circuit step onwards. A change failing
+/// [`PhysicsConfig::validate`] is logged and ignored, leaving the circuit on
+/// the last valid configuration.
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Bevy systems receive resources by value."
+)]
+pub fn sync_physics_config_system(mut state: NonSendMut<DbspState>, config: Res<PhysicsConfig>) {
+    if !config.is_changed() {
+        return;
+    }
+    if let Err(err) = config.validate() {
+        error!("ignoring physics config change: {err}");
+        return;
+    }
+    state.circuit.set_physics_config(*config);
+}
+
 /// Caches current ECS state into the DBSP circuit inputs.
 ///
-/// This system gathers `Transform`, optional `Velocity`, `Block`, and optional
-/// `Force` components and pushes them into the circuit's input handles. Forces
-/// for entities not present in the current position pass are ignored. It also
-/// updates the internal mapping from DBSP entity identifiers to Bevy entities,
-/// ensuring the lookup is maintained without rebuilding the map each frame. It
-/// also refreshes the [`WorldHandle`] resource with the same cached data for
-/// tests and diagnostics.
+/// This system gathers `Transform`, optional `Velocity`, `Block`, `Door`,
+/// `MovingPlatform`, and optional `Force`, `ImpulseComp`, `ExtentComp`,
+/// `DragComp` and `UnitType` components and pushes them into the circuit's
+/// input handles.
+/// Entity records persist in the circuit, so only entities whose mirrored
+/// components changed since the last pass push a retraction/insertion pair.
+/// Forces, impulses, extents and drag coefficients for entities missing from
+/// the id map are ignored. It also updates the internal mapping from DBSP
+/// entity identifiers to Bevy entities, ensuring the lookup is maintained
+/// without rebuilding the map each frame. It also refreshes the
+/// [`WorldHandle`] resource with the same cached data for tests and
+/// diagnostics.
 #[cfg(not(feature = "map"))]
 #[expect(
     clippy::too_many_arguments,
@@ -54,18 +181,18 @@ pub fn init_dbsp_system(world: &mut World) -> Result<(), dbsp::Error> {
 )]
 pub fn cache_state_for_dbsp_system(
     mut state: NonSendMut<DbspState>,
-    mut entity_query: Query<EntityRow<'_>>,
-    force_query: Query<(Entity, &DdlogId, &ForceComp)>,
-    block_query: Query<(&Block, Option<&BlockSlope>)>,
+    mut entities: EntityQueries,
+    impulse_query: Query<(Entity, &DdlogId, &ImpulseComp)>,
+    mut terrain: TerrainQueries,
     mut id_queries: IdQueries,
     mut damage_inbox: ResMut<DamageInbox>,
     mut world_handle: ResMut<WorldHandle>,
 ) {
     cache_state_for_dbsp_impl(
         &mut state,
-        &mut entity_query,
-        &force_query,
-        &block_query,
+        &mut entities,
+        &impulse_query,
+        &mut terrain,
         &mut id_queries,
         &mut damage_inbox,
         &mut world_handle,
@@ -77,6 +204,8 @@ pub fn cache_state_for_dbsp_system(
 /// This variant includes spawn point synchronisation when the `map` feature is
 /// enabled. It gathers `Transform`, optional `Velocity`, `Block`, `PlayerSpawn`,
 /// and `SpawnPoint` components and pushes them into the circuit's input handles.
+/// A `MapCreated` event makes the pass compare the whole terrain with the
+/// circuit once rather than only the blocks that changed.
 #[cfg(feature = "map")]
 #[expect(
     clippy::too_many_arguments,
@@ -84,22 +213,27 @@ pub fn cache_state_for_dbsp_system(
 )]
 pub fn cache_state_for_dbsp_system(
     mut state: NonSendMut<DbspState>,
-    mut entity_query: Query<EntityRow<'_>>,
-    force_query: Query<(Entity, &DdlogId, &ForceComp)>,
-    block_query: Query<(&Block, Option<&BlockSlope>)>,
+    mut entities: EntityQueries,
+    impulse_query: Query<(Entity, &DdlogId, &ImpulseComp)>,
+    mut terrain: TerrainQueries,
     player_spawn_query: Query<(Entity, &Transform), With<PlayerSpawn>>,
     spawn_point_query: Query<(Entity, &Transform, &SpawnPoint)>,
+    map_created: Option<MessageReader<TiledEvent<MapCreated>>>,
     mut id_queries: IdQueries,
     mut damage_inbox: ResMut<DamageInbox>,
     mut world_handle: ResMut<WorldHandle>,
 ) {
+    // The reader is absent when no Tiled plugin registered the message.
+    if map_created.is_some_and(|mut events| events.read().count() > 0) {
+        state.terrain_reconcile = true;
+    }
     sync::player_spawns(&mut state.circuit, &player_spawn_query);
     sync::spawn_points(&mut state.circuit, &spawn_point_query);
     cache_state_for_dbsp_impl(
         &mut state,
-        &mut entity_query,
-        &force_query,
-        &block_query,
+        &mut entities,
+        &impulse_query,
+        &mut terrain,
         &mut id_queries,
         &mut damage_inbox,
         &mut world_handle,
@@ -112,16 +246,22 @@ pub fn cache_state_for_dbsp_system(
 )]
 fn cache_state_for_dbsp_impl(
     state: &mut DbspState,
-    entity_query: &mut Query<EntityRow<'_>>,
-    force_query: &Query<(Entity, &DdlogId, &ForceComp)>,
-    block_query: &Query<(&Block, Option<&BlockSlope>)>,
+    entities: &mut EntityQueries,
+    impulse_query: &Query<(Entity, &DdlogId, &ImpulseComp)>,
+    terrain: &mut TerrainQueries,
     id_queries: &mut IdQueries,
     damage_inbox: &mut DamageInbox,
     world_handle: &mut WorldHandle,
 ) {
-    world_handle.blocks.clear();
-    world_handle.slopes.clear();
-    world_handle.entities.clear();
+    if state.awaiting_launch {
+        // A pipelined step is still running and this tick's inputs are
+        // already staged. Only note what changed, so the first pass after the
+        // launch compares it, and keep the one-tick records for that pass.
+        sync::id_maps(state, id_queries);
+        sync::defer_terrain(state, terrain);
+        sync::defer_entities(state, entities);
+        return;
+    }
 
     // Start a fresh per-frame rollback log. Its backups are recorded lazily
     // from values this pass already extracts (below) rather than deep-cloning
@@ -130,35 +270,29 @@ fn cache_state_for_dbsp_impl(
     // clears.
     state.begin_frame_rollback();
 
-    let previous_snapshots = collect_previous_health_snapshots(state);
     let pending_damage = mem::take(&mut state.pending_damage_retractions);
+    let pending_impulses = mem::take(&mut state.pending_impulse_retractions);
+    let pending_platforms = mem::take(&mut state.pending_platform_retractions);
     state.expected_health_retractions.clear();
 
-    sync::blocks(&mut state.circuit, block_query, world_handle);
+    sync::terrain(state, terrain, world_handle);
+    sync::platforms(state, &terrain.platforms);
     sync::id_maps(state, id_queries);
-    sync::entities(state, entity_query, world_handle);
-    sync::forces(state, force_query);
+    sync::entities(state, entities, world_handle);
+    sync::impulses(state, impulse_query);
 
-    apply_health_snapshot_retractions(&mut state.circuit, &previous_snapshots);
     apply_damage_retractions(state, &pending_damage);
+    apply_impulse_retractions(&mut state.circuit, &pending_impulses);
+    apply_platform_retractions(&mut state.circuit, &pending_platforms);
 
     ingest_damage_events(state, damage_inbox);
 
-    // Stash the pre-frame health/damage tracking (already moved out above, so no
+    // Stash the pre-frame damage tracking (already moved out above, so no
     // extra clone) for failure rollback.
-    state.stash_frame_rollback(previous_snapshots, pending_damage);
-}
-
-fn collect_previous_health_snapshots(state: &mut DbspState) -> Vec<HealthState> {
-    let snapshots: Vec<_> = state.health_snapshot.values().copied().collect();
-    state.health_snapshot.clear();
-    snapshots
-}
-
-fn apply_health_snapshot_retractions(circuit: &mut DbspCircuit, snapshots: &[HealthState]) {
-    for snapshot in snapshots {
-        circuit.health_state_in().push(*snapshot, -1);
-    }
+    state.stash_frame_rollback(pending_damage);
+    state.stash_impulse_rollback(pending_impulses);
+    state.stash_platform_rollback(pending_platforms);
+    state.awaiting_launch = state.circuit.is_pipelined();
 }
 
 fn apply_damage_retractions(state: &mut DbspState, retractions: &[DamageEvent]) {
@@ -170,6 +304,21 @@ fn apply_damage_retractions(state: &mut DbspState, retractions: &[DamageEvent])
     }
 }
 
+/// Retracts last frame's impulses so each applies for a single tick.
+fn apply_impulse_retractions(circuit: &mut DbspCircuit, impulses: &[Impulse]) {
+    for impulse in impulses {
+        circuit.impulse_in().push(*impulse, -1);
+    }
+}
+
+/// Retracts last frame's platform blocks, which this frame pushes again at
+/// their new positions.
+fn apply_platform_retractions(circuit: &mut DbspCircuit, blocks: &[PlatformBlock]) {
+    for block in blocks {
+        circuit.platform_block_in().push(*block, -1);
+    }
+}
+
 fn ingest_damage_events(state: &mut DbspState, inbox: &mut DamageInbox) {
     let mut sequenced_damage = HashSet::new();
     let mut unsequenced_damage = HashSet::new();
@@ -194,7 +343,8 @@ fn ingest_damage_events(state: &mut DbspState, inbox: &mut DamageInbox) {
 mod tests {
     //! Tests for the DBSP input synchronisation systems.
     use super::*;
-    use crate::dbsp_circuit::DamageSource;
+    use crate::dbsp_circuit::{Baddie, Civvy, DamageSource};
+    use crate::DbspPlugin;
     use rstest::rstest;
 
     fn make_state() -> Result<DbspState, dbsp::Error> {
@@ -260,4 +410,337 @@ mod tests {
         );
         assert_eq!(state.applied_health_duplicates(), 0);
     }
+
+    /// Weights of the floor surfaces the last step added or retracted.
+    fn floor_weights(app: &App) -> Vec<i64> {
+        let state = app.world().non_send_resource::<DbspState>();
+        state
+            .circuit
+            .floor_height_out()
+            .consolidate()
+            .iter()
+            .map(|(_, (), weight)| weight)
+            .collect()
+    }
+
+    #[rstest]
+    fn door_block_follows_the_door_state() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(DbspPlugin::per_frame());
+        let door = app
+            .world_mut()
+            .spawn((
+                Block {
+                    id: 1,
+                    x: 0,
+                    y: 0,
+                    z: 0,
+                },
+                Door::default(),
+            ))
+            .id();
+
+        app.update();
+        assert_eq!(floor_weights(&app), vec![1], "closed door adds its floor");
+
+        app.update();
+        assert!(floor_weights(&app).is_empty(), "idle door pushes nothing");
+
+        app.world_mut()
+            .get_mut::<Door>(door)
+            .expect("door component should exist")
+            .open = true;
+        app.update();
+        assert_eq!(floor_weights(&app), vec![-1], "open door retracts it");
+
+        app.world_mut()
+            .get_mut::<Door>(door)
+            .expect("door component should exist")
+            .open = false;
+        app.update();
+        assert_eq!(floor_weights(&app), vec![1], "closing restores the floor");
+
+        app.world_mut().despawn(door);
+        app.update();
+        assert_eq!(floor_weights(&app), vec![-1], "despawned door retracts it");
+    }
+
+    #[rstest]
+    fn removing_a_slope_flattens_the_floor() {
+        use ordered_float::OrderedFloat;
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(DbspPlugin::per_frame());
+        let block = app
+            .world_mut()
+            .spawn((
+                Block {
+                    id: 1,
+                    x: 0,
+                    y: 0,
+                    z: 0,
+                },
+                BlockSlope {
+                    block_id: 1,
+                    grad_x: OrderedFloat(1.0),
+                    grad_y: OrderedFloat(0.0),
+                },
+            ))
+            .id();
+        app.update();
+
+        app.world_mut().entity_mut(block).remove::<BlockSlope>();
+        app.update();
+
+        let state = app.world().non_send_resource::<DbspState>();
+        let flat: Vec<_> = state
+            .circuit
+            .floor_height_out()
+            .consolidate()
+            .iter()
+            .filter(|(_, (), weight)| *weight > 0)
+            .map(|(floor, (), _)| floor.grad_x.into_inner())
+            .collect();
+        assert_eq!(flat, vec![0.0], "the floor loses its gradient");
+        assert_eq!(
+            state.terrain_blocks.get(&block),
+            Some(&(
+                Block {
+                    id: 1,
+                    x: 0,
+                    y: 0,
+                    z: 0,
+                },
+                None
+            ))
+        );
+    }
+
+    #[rstest]
+    fn reconciling_unchanged_terrain_pushes_nothing() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(DbspPlugin::per_frame());
+        app.world_mut().spawn(Block {
+            id: 1,
+            x: 0,
+            y: 0,
+            z: 0,
+        });
+        app.update();
+
+        app.world_mut()
+            .non_send_resource_mut::<DbspState>()
+            .terrain_reconcile = true;
+        app.update();
+
+        assert!(floor_weights(&app).is_empty());
+        assert_eq!(
+            app.world()
+                .non_send_resource::<DbspState>()
+                .terrain_blocks
+                .len(),
+            1
+        );
+    }
+
+    #[rstest]
+    fn terrain_edits_push_weighted_diffs() {
+        use crate::terrain::TerrainCommands;
+        use bevy::ecs::system::RunSystemOnce;
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(DbspPlugin::per_frame());
+        app.world_mut().spawn(Block {
+            id: 1,
+            x: 0,
+            y: 0,
+            z: 0,
+        });
+
+        app.update();
+        assert_eq!(floor_weights(&app), vec![1], "loaded terrain is pushed");
+
+        app.update();
+        assert!(
+            floor_weights(&app).is_empty(),
+            "static terrain is not re-pushed"
+        );
+
+        app.world_mut()
+            .run_system_once(|mut terrain: TerrainCommands| {
+                terrain.place(Block {
+                    id: 2,
+                    x: 0,
+                    y: 0,
+                    z: 1,
+                });
+            })
+            .expect("place system should run");
+        app.update();
+        let mut raised = floor_weights(&app);
+        raised.sort_unstable();
+        assert_eq!(raised, vec![-1, 1], "placed block raises the floor");
+        assert_eq!(
+            app.world()
+                .non_send_resource::<DbspState>()
+                .terrain_blocks
+                .len(),
+            2
+        );
+
+        app.world_mut()
+            .run_system_once(|mut terrain: TerrainCommands| terrain.remove(2))
+            .expect("remove system should run");
+        app.update();
+        let mut lowered = floor_weights(&app);
+        lowered.sort_unstable();
+        assert_eq!(lowered, vec![-1, 1], "removed block lowers the floor");
+        assert_eq!(
+            app.world()
+                .non_send_resource::<DbspState>()
+                .terrain_blocks
+                .len(),
+            1
+        );
+    }
+
+    /// Weights of the entity positions the last step added to or retracted
+    /// from the floor join. The output system drains `new_position_out`, so
+    /// the untouched join stands in for the position input.
+    fn position_weights(app: &App) -> Vec<i64> {
+        let state = app.world().non_send_resource::<DbspState>();
+        let mut weights: Vec<_> = state
+            .circuit
+            .position_floor_out()
+            .consolidate()
+            .iter()
+            .map(|(_, (), weight)| weight)
+            .collect();
+        weights.sort_unstable();
+        weights
+    }
+
+    /// Builds an app with one healthy entity standing on a block, settled by a
+    /// first update.
+    fn standing_entity_app() -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(DbspPlugin::per_frame());
+        app.world_mut().spawn(Block {
+            id: 1,
+            x: 0,
+            y: 0,
+            z: 0,
+        });
+        let entity = app
+            .world_mut()
+            .spawn((
+                DdlogId(1),
+                Transform::from_xyz(0.0, 0.0, 1.0),
+                VelocityComp::default(),
+                Health {
+                    current: 100,
+                    max: 100,
+                },
+            ))
+            .id();
+        app.update();
+        (app, entity)
+    }
+
+    #[rstest]
+    fn idle_entities_push_nothing() {
+        let (mut app, entity) = standing_entity_app();
+        assert_eq!(position_weights(&app), vec![1], "new entity is pushed");
+
+        app.update();
+        assert!(
+            position_weights(&app).is_empty(),
+            "idle entity is not re-pushed"
+        );
+        let state = app.world().non_send_resource::<DbspState>();
+        assert!(state.entity_records.positions.get(entity).is_some());
+        assert!(state.health_snapshot.get(entity).is_some());
+    }
+
+    #[rstest]
+    fn moved_entities_replace_their_position() {
+        let (mut app, entity) = standing_entity_app();
+        app.update();
+
+        app.world_mut()
+            .get_mut::<Transform>(entity)
+            .expect("entity should have a transform")
+            .translation
+            .x = 0.25;
+        app.update();
+
+        assert_eq!(position_weights(&app), vec![-1, 1]);
+        let state = app.world().non_send_resource::<DbspState>();
+        let position = state
+            .entity_records
+            .positions
+            .get(entity)
+            .expect("moved entity keeps its position record");
+        assert_eq!(position.x, ordered_float::OrderedFloat(0.25));
+    }
+
+    #[rstest]
+    fn despawned_entities_retract_their_records() {
+        let (mut app, entity) = standing_entity_app();
+        app.update();
+
+        app.world_mut().despawn(entity);
+        app.update();
+
+        assert_eq!(position_weights(&app), vec![-1]);
+        let state = app.world().non_send_resource::<DbspState>();
+        assert!(state.entity_records.positions.get(entity).is_none());
+        assert!(state.entity_records.velocities.get(entity).is_none());
+        assert!(state.health_snapshot.get(entity).is_none());
+        assert_eq!(app.world().resource::<WorldHandle>().entity_count(), 0);
+    }
+
+    /// Unit records currently mirrored for `entity`.
+    fn unit_records(app: &App, entity: Entity) -> (Option<Civvy>, Option<Baddie>) {
+        let records = &app.world().non_send_resource::<DbspState>().entity_records;
+        (
+            records.civvies.get(entity).copied(),
+            records.baddies.get(entity).copied(),
+        )
+    }
+
+    #[rstest]
+    fn unit_types_are_mirrored_as_unit_records() {
+        let (mut app, entity) = standing_entity_app();
+        app.world_mut()
+            .entity_mut(entity)
+            .insert(UnitType::Civvy { fraidiness: 0.5 });
+        app.update();
+        let civvy = Civvy {
+            entity: 1,
+            fraidiness: 0.5.into(),
+        };
+        assert_eq!(unit_records(&app, entity), (Some(civvy), None));
+
+        app.world_mut()
+            .entity_mut(entity)
+            .insert(UnitType::Baddie { meanness: 2.0 });
+        app.update();
+        let baddie = Baddie {
+            entity: 1,
+            meanness: 2.0.into(),
+        };
+        assert_eq!(unit_records(&app, entity), (None, Some(baddie)));
+
+        app.world_mut().entity_mut(entity).remove::<UnitType>();
+        app.update();
+        assert_eq!(unit_records(&app, entity), (None, None));
+        let state = app.world().non_send_resource::<DbspState>();
+        assert!(state.entity_records.positions.get(entity).is_some());
+    }
 }
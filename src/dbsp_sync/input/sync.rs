@@ -1,61 +1,141 @@
 //! Helper functions for caching ECS state into the DBSP circuit.
 
+use std::collections::HashSet;
 use std::convert::TryFrom;
 
-use bevy::prelude::{Entity, Query};
 #[cfg(feature = "map")]
-use bevy::prelude::{Transform, With};
-use dbsp::operator::input::ZSetHandle;
+use bevy::prelude::With;
+use bevy::prelude::{Entity, Mut, Query, Transform, Vec2, Vec3};
 use log::{debug, warn};
 
 #[cfg(feature = "map")]
 use crate::map::{PlayerSpawn, SpawnPoint};
 
 use crate::components::{
-    Block, BlockSlope, DdlogId, ForceComp, Health, Target as TargetComp, VelocityComp,
+    Block, BlockSlope, DdlogId, Health, ImpulseComp, MovingPlatform, PathGoal as PathGoalComp,
+    UnitType,
 };
-use crate::dbsp_circuit::{DbspCircuit, Force, HealthState, Position, Target, Velocity};
-use crate::world_handle::{DdlogEntity, WorldHandle};
+use crate::dbsp_circuit::{
+    Baddie, Civvy, DbspCircuit, Drag, Extent, Force, HealthState, Impulse, PathGoal, PlatformBlock,
+    Position, Target, Velocity,
+};
+use crate::dbsp_sync::state::{platform_id, TerrainBlock};
+use crate::world_handle::WorldHandle;
 
-use super::{DbspState, EntityRow, IdQueries};
+use super::{DbspState, EntityQueries, IdQueries, TerrainQueries};
 
-/// Macro to generate component synchronisation wrapper functions.
-macro_rules! sync_component_wrapper {
-    (
-        $(#[$meta:meta])* $fn_name:ident, $row_field:tt, $component_type:ty,
-        $struct_name:ident { $($field:ident),+ }, $input_handle:ident
-    ) => {
-        $(#[$meta])*
-        fn $fn_name(state: &mut DbspState, query: &mut Query<EntityRow<'_>>) {
-            sync_component(
-                state,
-                query,
-                |row| row.$row_field.map(|component: &$component_type| {
-                    ( $(component.$field),+ )
-                }),
-                |entity, ( $( $field ),+ )| $struct_name {
-                    entity,
-                    $( $field: f64::from($field).into() ),+
-                },
-                |circuit: &DbspCircuit| circuit.$input_handle(),
-            );
-        }
-    };
+/// Mirrors terrain changes into the circuit.
+///
+/// Terrain is persistent circuit state: the circuit holds each block of
+/// [`DbspState::terrain_blocks`] exactly once, and only entities whose `Block`,
+/// `BlockSlope` or `Door` was added, changed or removed since the last pass are
+/// compared with it. A block that was placed, changed or closed is pushed at
+/// `+1`, and one that was removed, changed or opened is retracted at `-1`, so
+/// the per-tick cost follows the terrain edits rather than the map size. When
+/// [`DbspState::terrain_reconcile`] is set, as on the frame a map is created,
+/// every terrain entity is compared instead.
+pub(super) fn terrain(
+    state: &mut DbspState,
+    terrain: &mut TerrainQueries,
+    world: &mut WorldHandle,
+) {
+    let mut dirty = terrain_changes(state, terrain);
+    if std::mem::take(&mut state.terrain_reconcile) {
+        dirty.extend(terrain.blocks.iter().map(|(entity, ..)| entity));
+        dirty.extend(state.terrain_blocks.keys().copied());
+    }
+    for entity in dirty {
+        let current = terrain
+            .blocks
+            .get(entity)
+            .ok()
+            .filter(|(_, _, _, door)| door.is_none_or(|d| !d.open))
+            .map(|(_, block, slope, _)| (block.clone(), slope.cloned()));
+        update_terrain_block(state, entity, current, world);
+    }
 }
 
-pub(super) fn blocks(
-    circuit: &mut DbspCircuit,
-    query: &Query<(&Block, Option<&BlockSlope>)>,
+/// Notes the terrain changes of this frame for a later pass, without
+/// touching the circuit.
+pub(super) fn defer_terrain(state: &mut DbspState, terrain: &mut TerrainQueries) {
+    state.terrain_recheck = terrain_changes(state, terrain);
+}
+
+/// Takes [`DbspState::terrain_recheck`] together with the entities whose
+/// terrain components changed or were removed since the last pass.
+fn terrain_changes(state: &mut DbspState, terrain: &mut TerrainQueries) -> HashSet<Entity> {
+    let mut dirty: HashSet<Entity> = std::mem::take(&mut state.terrain_recheck);
+    dirty.extend(terrain.changed.iter());
+    dirty.extend(terrain.removed_blocks.read());
+    dirty.extend(terrain.removed_slopes.read());
+    dirty.extend(terrain.removed_doors.read());
+    dirty
+}
+
+/// Replaces the block the circuit holds for `entity` with `current`,
+/// retracting the old block and pushing the new one when they differ.
+fn update_terrain_block(
+    state: &mut DbspState,
+    entity: Entity,
+    current: Option<TerrainBlock>,
     world: &mut WorldHandle,
 ) {
-    for (block, slope) in query.iter() {
-        circuit.block_in().push(block.clone(), 1);
+    if state.terrain_blocks.get(&entity) == current.as_ref() {
+        return;
+    }
+    state.record_terrain_undo(entity);
+    let previous = match &current {
+        Some(terrain) => state.terrain_blocks.insert(entity, terrain.clone()),
+        None => state.terrain_blocks.remove(&entity),
+    };
+    if let Some((block, slope)) = previous {
+        push_terrain_block(&state.circuit, &block, slope.as_ref(), -1);
+        world.blocks.remove(&entity);
         if let Some(s) = slope {
-            circuit.block_slope_in().push(s.clone(), 1);
+            world.slopes.remove(&s.block_id);
         }
-        world.blocks.push(block.clone());
+    }
+    if let Some((block, slope)) = current {
+        push_terrain_block(&state.circuit, &block, slope.as_ref(), 1);
         if let Some(s) = slope {
-            world.slopes.insert(s.block_id, s.clone());
+            world.slopes.insert(s.block_id, s);
+        }
+        world.blocks.insert(entity, block);
+    }
+}
+
+fn push_terrain_block(
+    circuit: &DbspCircuit,
+    block: &Block,
+    slope: Option<&BlockSlope>,
+    weight: i64,
+) {
+    circuit.block_in().push(block.clone(), weight);
+    if let Some(s) = slope {
+        circuit.block_slope_in().push(s.clone(), weight);
+    }
+}
+
+/// Pushes the blocks of every moving platform at its current transform and
+/// records them for retraction on the next frame, once the platform has moved.
+pub(super) fn platforms(
+    state: &mut DbspState,
+    query: &Query<(Entity, &Transform, &MovingPlatform)>,
+) {
+    for (entity, transform, platform) in query.iter() {
+        let origin = transform.translation;
+        for &[dx, dy, dz] in &platform.blocks {
+            let record = PlatformBlock {
+                platform: platform_id(entity),
+                x: (f64::from(origin.x) + f64::from(dx)).into(),
+                y: (f64::from(origin.y) + f64::from(dy)).into(),
+                z: (f64::from(origin.z) + f64::from(dz)).into(),
+                vx: platform.vx.into(),
+                vy: platform.vy.into(),
+                vz: platform.vz.into(),
+            };
+            state.circuit.platform_block_in().push(record, 1);
+            state.pending_platform_retractions.push(record);
         }
     }
 }
@@ -103,154 +183,278 @@ fn handle_id_conflict(state: &mut DbspState, entity: Entity, id: i64) {
     }
 }
 
+/// Mirrors entity state into the circuit.
+///
+/// Positions, velocities, targets, forces, extents, drags and health
+/// snapshots are persistent circuit state: only entities whose mirrored
+/// components were added, changed or removed since the last pass are compared
+/// with the records in [`DbspState::entity_records`], and a record that
+/// differs is retracted at `-1` and pushed again at `+1`. Idle entities push
+/// nothing, and a despawned entity has all of its records retracted.
 pub(super) fn entities(
     state: &mut DbspState,
-    query: &mut Query<EntityRow<'_>>,
-    world: &mut WorldHandle,
-) {
-    sync_positions(state, query, world);
-    sync_velocities(state, query);
-    sync_targets(state, query);
-    sync_health(state, query);
-    update_world_handle(query, world);
-}
-
-/// Synchronises entity positions with the DBSP circuit.
-fn sync_positions(
-    state: &mut DbspState,
-    query: &mut Query<EntityRow<'_>>,
+    entities: &mut EntityQueries,
     world: &mut WorldHandle,
 ) {
-    let circuit = &state.circuit;
-    for (_, id, transform, _, _, _) in query.iter_mut() {
-        circuit.position_in().push(
-            Position {
-                entity: id.0,
-                x: f64::from(transform.translation.x).into(),
-                y: f64::from(transform.translation.y).into(),
-                z: f64::from(transform.translation.z).into(),
-            },
-            1,
-        );
-        if world.entities.contains_key(&id.0) {
-            // `cache_state_for_dbsp_system` clears the world handle before each
-            // sync pass, so finding a pre-existing entry means stale state
-            // leaked from a previous frame. Removing it keeps the cache
-            // consistent without crashing release builds.
-            warn!(
-                "world handle entry for entity {} existed before sync; removing stale record",
-                id.0
-            );
-            world.entities.remove(&id.0);
+    for entity in entity_changes(state, entities) {
+        let previous_id = state
+            .entity_records
+            .positions
+            .get(entity)
+            .map(|position| position.entity);
+        let current = read_entity(state, entities, entity);
+        let current_id = current.as_ref().map(|records| records.position.entity);
+        if previous_id != current_id {
+            if let Some(id) = previous_id {
+                world.entities.remove(&id);
+            }
+        }
+        if let Some(records) = &current {
+            update_world_entity(world, records);
         }
+        replace_entity_records(state, entity, current.as_ref());
     }
 }
 
-sync_component_wrapper!(
-    /// Synchronises entity velocities with the DBSP circuit.
-    sync_velocities,
-    3,
-    VelocityComp,
-    Velocity { vx, vy, vz },
-    velocity_in
-);
-
-sync_component_wrapper!(
-    /// Synchronises entity targets with the DBSP circuit.
-    sync_targets,
-    4,
-    TargetComp,
-    Target { x, y },
-    target_in
-);
-
-fn create_and_store_health_snapshot(state: &mut DbspState, entity: u64, current: u16, max: u16) {
-    let circuit = &state.circuit;
-    let snapshot = HealthState {
-        entity,
-        current,
-        max,
-    };
-    circuit.health_state_in().push(snapshot, 1);
-    state.health_snapshot.insert(entity, snapshot);
+/// Notes the entity changes of this frame for a later pass, without touching
+/// the circuit.
+pub(super) fn defer_entities(state: &mut DbspState, entities: &mut EntityQueries) {
+    state.entity_recheck = entity_changes(state, entities);
+}
+
+/// Takes [`DbspState::entity_recheck`] together with the entities whose
+/// mirrored components changed or were removed since the last pass.
+fn entity_changes(state: &mut DbspState, entities: &mut EntityQueries) -> HashSet<Entity> {
+    let mut dirty: HashSet<Entity> = std::mem::take(&mut state.entity_recheck);
+    dirty.extend(entities.rows.p1().iter());
+    dirty.extend(entities.removed_ids.read());
+    dirty.extend(entities.removed_velocities.read());
+    dirty.extend(entities.removed_targets.read());
+    dirty.extend(entities.removed_path_goals.read());
+    dirty.extend(entities.removed_health.read());
+    dirty.extend(entities.removed_forces.read());
+    dirty.extend(entities.removed_extents.read());
+    dirty.extend(entities.removed_drags.read());
+    dirty.extend(entities.removed_units.read());
+    dirty
+}
+
+/// Records one entity contributes to the circuit inputs.
+struct CurrentRecords {
+    translation: Vec3,
+    goal: Option<Vec2>,
+    position: Position,
+    velocity: Option<Velocity>,
+    target: Option<Target>,
+    path_goal: Option<PathGoal>,
+    force: Option<Force>,
+    extent: Option<Extent>,
+    drag: Option<Drag>,
+    civvy: Option<Civvy>,
+    baddie: Option<Baddie>,
+    health: Option<HealthState>,
 }
 
-/// Mirrors entity health into the circuit, enforcing clamps and logging once.
-fn sync_health(state: &mut DbspState, query: &mut Query<EntityRow<'_>>) {
-    for (_, id, _, _, _, mut health_opt) in query.iter_mut() {
-        let Some(health) = health_opt.as_deref_mut() else {
-            continue;
-        };
-        let Ok(entity_id) = u64::try_from(id.0) else {
-            warn!("health component for negative id {} skipped", id.0);
-            continue;
-        };
-        let original_current = health.current;
-        let (clamped_current, max_value, was_clamped) = clamp_health_values(health);
-        if was_clamped {
-            debug!(
-                "health current {} clamped to {} for entity {}",
-                original_current, clamped_current, id.0
-            );
+/// Reads the current records of `entity`, clamping its health on the way.
+///
+/// Returns `None` when the entity no longer carries a `DdlogId`, including
+/// when it was despawned. Forces, extents and drag coefficients of entities
+/// missing from the id map are ignored.
+fn read_entity(
+    state: &DbspState,
+    entities: &mut EntityQueries,
+    entity: Entity,
+) -> Option<CurrentRecords> {
+    let mut rows = entities.rows.p0();
+    let (_, ddlog_id, transform, velocity_comp, goal, health_comp) = rows.get_mut(entity).ok()?;
+    let id = ddlog_id.0;
+    let position = Position {
+        entity: id,
+        x: f64::from(transform.translation.x).into(),
+        y: f64::from(transform.translation.y).into(),
+        z: f64::from(transform.translation.z).into(),
+    };
+    let velocity = velocity_comp.map(|v| Velocity {
+        entity: id,
+        vx: f64::from(v.vx).into(),
+        vy: f64::from(v.vy).into(),
+        vz: f64::from(v.vz).into(),
+    });
+    let target = goal.map(|t| Target {
+        entity: id,
+        x: f64::from(t.0.x).into(),
+        y: f64::from(t.0.y).into(),
+    });
+
+    let known = state.id_map.contains_key(&id);
+    let force = entities.forces.get(entity).ok().and_then(|(_, f)| {
+        if !known {
+            warn!("force component for unknown entity {entity:?} ignored");
+            return None;
+        }
+        Some(Force {
+            entity: id,
+            fx: f.force_x.into(),
+            fy: f.force_y.into(),
+            fz: f.force_z.into(),
+            mass: f.mass.map(Into::into),
+        })
+    });
+    let extent = entities.extents.get(entity).ok().and_then(|(_, extent)| {
+        if !known {
+            warn!("extent component for unknown entity {entity:?} ignored");
+            return None;
         }
+        Some(Extent {
+            entity: id,
+            radius: extent.radius.into(),
+        })
+    });
+    let path_goal = read_path_goal(&entities.path_goals, entity, id);
+    let (civvy, baddie) = read_unit(&entities.units, entity, id);
+    let drag = entities.drags.get(entity).ok().and_then(|(_, drag)| {
+        if !known {
+            warn!("drag component for unknown entity {entity:?} ignored");
+            return None;
+        }
+        Some(Drag {
+            entity: id,
+            coefficient: drag.coefficient.into(),
+        })
+    });
+
+    Some(CurrentRecords {
+        translation: transform.translation,
+        goal: goal.map(|t| t.0),
+        position,
+        velocity,
+        target,
+        path_goal,
+        force,
+        extent,
+        drag,
+        civvy,
+        baddie,
+        health: health_comp.and_then(|h| health_snapshot(id, h)),
+    })
+}
+
+/// Reads the pathfinder waypoint of `entity`, if it has one.
+fn read_path_goal(
+    path_goals: &Query<(&DdlogId, &PathGoalComp)>,
+    entity: Entity,
+    id: i64,
+) -> Option<PathGoal> {
+    let (_, waypoint) = path_goals.get(entity).ok()?;
+    Some(PathGoal {
+        entity: id,
+        x: f64::from(waypoint.0.x).into(),
+        y: f64::from(waypoint.0.y).into(),
+    })
+}
+
+/// Splits the [`UnitType`] of `entity` into the civvy or baddie record the
+/// circuit derives fear from.
+fn read_unit(
+    units: &Query<(&DdlogId, &UnitType)>,
+    entity: Entity,
+    id: i64,
+) -> (Option<Civvy>, Option<Baddie>) {
+    match units.get(entity).ok() {
+        Some((_, &UnitType::Civvy { fraidiness })) => (
+            Some(Civvy {
+                entity: id,
+                fraidiness: f64::from(fraidiness).into(),
+            }),
+            None,
+        ),
+        Some((_, &UnitType::Baddie { meanness })) => (
+            None,
+            Some(Baddie {
+                entity: id,
+                meanness: f64::from(meanness).into(),
+            }),
+        ),
+        None => (None, None),
+    }
+}
+
+/// Clamps `health` to its maximum and returns the snapshot to mirror.
+///
+/// The component is only written when clamping changed it, so an unchanged
+/// entity is not reported as changed on the next pass.
+fn health_snapshot(id: i64, mut health: Mut<'_, Health>) -> Option<HealthState> {
+    let Ok(entity) = u64::try_from(id) else {
+        warn!("health component for negative id {id} skipped");
+        return None;
+    };
+    let original_current = health.current;
+    let (clamped_current, max, was_clamped) = clamp_health_values(&health);
+    if was_clamped {
+        debug!("health current {original_current} clamped to {clamped_current} for entity {id}");
         health.current = clamped_current;
-        create_and_store_health_snapshot(state, entity_id, clamped_current, max_value);
     }
+    Some(HealthState {
+        entity,
+        current: clamped_current,
+        max,
+    })
 }
 
-/// Generic helper for syncing entity components to DBSP circuit inputs.
-#[expect(
-    clippy::too_many_arguments,
-    reason = "Helper coordinates multiple callback parameters for flexibility."
-)]
-fn sync_component<T, S, F, G, H>(
-    state: &mut DbspState,
-    query: &mut Query<EntityRow<'_>>,
-    extract_component: F,
-    create_struct: G,
-    get_input_handle: H,
-) where
-    F: Fn(&EntityRow<'_>) -> Option<T>,
-    G: Fn(i64, T) -> S,
-    H: Fn(&DbspCircuit) -> &ZSetHandle<S>,
-    S: Clone + dbsp::DBData,
-{
+/// Makes the circuit hold exactly `current` for `entity`, retracting every
+/// record when it is `None`.
+fn replace_entity_records(state: &mut DbspState, entity: Entity, current: Option<&CurrentRecords>) {
     let circuit = &state.circuit;
-    for (entity, id, transform, velocity, target, mut health_opt) in query.iter_mut() {
-        let entity_key = id.0;
-        let row_view = (
-            entity,
-            id,
-            transform,
-            velocity,
-            target,
-            health_opt.as_deref_mut(),
-        );
-        if let Some(component_data) = extract_component(&row_view) {
-            let input_struct = create_struct(entity_key, component_data);
-            get_input_handle(circuit).push(input_struct, 1);
-        }
-    }
+    let records = &mut state.entity_records;
+    records
+        .positions
+        .replace(circuit.position_in(), entity, current.map(|c| c.position));
+    records.velocities.replace(
+        circuit.velocity_in(),
+        entity,
+        current.and_then(|c| c.velocity),
+    );
+    records
+        .targets
+        .replace(circuit.target_in(), entity, current.and_then(|c| c.target));
+    records.path_goals.replace(
+        circuit.path_goal_in(),
+        entity,
+        current.and_then(|c| c.path_goal),
+    );
+    records
+        .forces
+        .replace(circuit.force_in(), entity, current.and_then(|c| c.force));
+    records
+        .extents
+        .replace(circuit.extent_in(), entity, current.and_then(|c| c.extent));
+    records
+        .drags
+        .replace(circuit.drag_in(), entity, current.and_then(|c| c.drag));
+    records
+        .civvies
+        .replace(circuit.civvy_in(), entity, current.and_then(|c| c.civvy));
+    records
+        .baddies
+        .replace(circuit.baddie_in(), entity, current.and_then(|c| c.baddie));
+    state.health_snapshot.replace(
+        circuit.health_state_in(),
+        entity,
+        current.and_then(|c| c.health),
+    );
 }
 
-/// Rebuilds the cached world representation from the ECS query results.
-fn update_world_handle(query: &mut Query<EntityRow<'_>>, world: &mut WorldHandle) {
-    for (_, id, transform, _, target, health) in query.iter_mut() {
-        let entry = world
-            .entities
-            .entry(id.0)
-            .or_insert_with(DdlogEntity::default);
-        entry.position = transform.translation;
-        entry.target = target.map(|t| t.0);
-        if let Some(h) = health {
-            let (clamped_current, max_value, _) = clamp_health_values(h.as_ref());
-            entry.health_current = clamped_current;
-            entry.health_max = max_value;
-        } else {
-            entry.health_current = 0;
-            entry.health_max = 0;
-        }
-    }
+/// Refreshes the cached world representation of one entity.
+fn update_world_entity(world: &mut WorldHandle, records: &CurrentRecords) {
+    let entry = world.entities.entry(records.position.entity).or_default();
+    entry.position = records.translation;
+    entry.target = records.goal;
+    let (current, max) = records
+        .health
+        .as_ref()
+        .map_or((0, 0), |h| (h.current, h.max));
+    entry.health_current = current;
+    entry.health_max = max;
 }
 
 fn clamp_health_values(health: &Health) -> (u16, u16, bool) {
@@ -262,21 +466,21 @@ fn clamp_health_values(health: &Health) -> (u16, u16, bool) {
     )
 }
 
-pub(super) fn forces(state: &mut DbspState, query: &Query<(Entity, &DdlogId, &ForceComp)>) {
-    for (entity, id, f) in query.iter() {
+/// Pushes every queued impulse and records it for retraction on the next
+/// frame, so each one applies for a single tick.
+pub(super) fn impulses(state: &mut DbspState, query: &Query<(Entity, &DdlogId, &ImpulseComp)>) {
+    for (entity, id, impulse) in query.iter() {
         if state.id_map.contains_key(&id.0) {
-            state.circuit.force_in().push(
-                Force {
-                    entity: id.0,
-                    fx: f.force_x.into(),
-                    fy: f.force_y.into(),
-                    fz: f.force_z.into(),
-                    mass: f.mass.map(Into::into),
-                },
-                1,
-            );
+            let record = Impulse {
+                entity: id.0,
+                dvx: impulse.dvx.into(),
+                dvy: impulse.dvy.into(),
+                dvz: impulse.dvz.into(),
+            };
+            state.circuit.impulse_in().push(record, 1);
+            state.pending_impulse_retractions.push(record);
         } else {
-            warn!("force component for unknown entity {entity:?} ignored");
+            warn!("impulse component for unknown entity {entity:?} ignored");
         }
     }
 }
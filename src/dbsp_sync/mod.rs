@@ -13,18 +13,22 @@ mod plugin;
 mod state;
 
 pub use damage_inbox::DamageInbox;
-pub use input::{cache_state_for_dbsp_system, init_dbsp_system};
+pub use input::{
+    cache_state_for_dbsp_system, init_dbsp_system, sync_physics_config_system, EntityQueries,
+    TerrainQueries,
+};
 #[cfg(feature = "observers-v1-spike")]
 pub use observers_v1::DbspDamageIngress;
-pub use output::apply_dbsp_outputs_system;
-pub use plugin::{DbspPlugin, DbspSyncError, DbspSyncErrorContext};
+pub use output::{apply_dbsp_outputs_system, step_dbsp_circuit_system};
+pub use plugin::{DbspPlugin, DbspSchedule, DbspSet, DbspSyncError, DbspSyncErrorContext};
 pub use state::{DbspState, IdQueries};
 
 #[cfg(test)]
 mod tests {
     //! Tests for the DBSP synchronisation plumbing.
     use super::*;
-    use bevy::prelude::World;
+    use bevy::ecs::schedule::ScheduleLabel;
+    use bevy::prelude::{FixedUpdate, World};
     use rstest::rstest;
 
     #[rstest]
@@ -49,6 +53,9 @@ mod tests {
 
     #[rstest]
     fn plugin_is_default_constructible() {
-        let _: DbspPlugin = DbspPlugin;
+        let plugin = DbspPlugin::default();
+        assert_eq!(plugin.workers, 1);
+        assert!(!plugin.pipelined);
+        assert_eq!(plugin.schedule, FixedUpdate.intern());
     }
 }
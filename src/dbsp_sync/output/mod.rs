@@ -3,10 +3,12 @@
 use bevy::prelude::*;
 use log::{debug, warn};
 
-use crate::components::{DdlogId, Health, VelocityComp};
+use crate::components::{DdlogId, Health, ImpulseComp, MovingPlatform, VelocityComp};
 use crate::dbsp_circuit::Position;
+use crate::nav_grid::NavGrid;
 use crate::world_handle::WorldHandle;
 
+use super::state::{platform_id, StepOutcome};
 use super::{DbspState, DbspSyncError, DbspSyncErrorContext};
 
 mod health;
@@ -191,48 +193,237 @@ fn report_movement_aggregations(state: &DbspState) {
     }
 }
 
+/// Logs each entity the steering stage found on its target this tick.
+///
+/// Arrival is a diagnostic: the stage already stops the entity, so nothing is
+/// written to the ECS. Retractions mark entities leaving their target and are
+/// not reported.
+fn report_arrivals(state: &DbspState) {
+    let arrivals = state.circuit.arrived_out().consolidate();
+    for (arrived, (), weight) in arrivals.iter() {
+        if weight <= 0 {
+            continue;
+        }
+        debug!(
+            "entity {} arrived at ({}, {})",
+            arrived.entity, arrived.x, arrived.y
+        );
+    }
+}
+
+/// Moving platforms, which have no `DdlogId` and so never alias an entity
+/// written through [`DbspWriteQuery`].
+type PlatformQuery<'w, 's> =
+    Query<'w, 's, (Entity, &'static mut Transform, &'static MovingPlatform), Without<DdlogId>>;
+
+/// Advances every platform by its velocity, matching the floor surfaces and
+/// rider positions the circuit derived for this tick.
+fn advance_platforms(platforms: &mut PlatformQuery<'_, '_>) {
+    for (entity, mut transform, platform) in platforms.iter_mut() {
+        let id = platform_id(entity);
+        let moved = Position {
+            entity: id,
+            x: (f64::from(transform.translation.x) + platform.vx).into(),
+            y: (f64::from(transform.translation.y) + platform.vy).into(),
+            z: (f64::from(transform.translation.z) + platform.vz).into(),
+        };
+        write_position(&moved, &mut transform);
+    }
+}
+
+/// Advances the circuit by one tick ahead of [`apply_dbsp_outputs_system`].
+///
+/// A failed step is reported and its inputs rolled back here, together with
+/// the terrain the cache pass mirrored into the [`WorldHandle`], so the output
+/// pass only ever sees a finished step. A pipelined circuit is polled instead:
+/// the system collects the running step once it has finished and otherwise
+/// leaves it computing while the rest of the frame runs.
+pub fn step_dbsp_circuit_system(
+    mut commands: Commands,
+    mut state: NonSendMut<DbspState>,
+    mut world_handle: ResMut<WorldHandle>,
+) {
+    let outcome = take_step(&mut commands, &mut state, &mut world_handle);
+    state.step_outcome = Some(outcome);
+}
+
+/// Steps the circuit, or polls a pipelined one, rolling back on failure.
+fn take_step(
+    commands: &mut Commands,
+    state: &mut DbspState,
+    world: &mut WorldHandle,
+) -> StepOutcome {
+    let result = if state.circuit.is_pipelined() {
+        if !state.circuit.is_stepping() {
+            return StepOutcome::Idle;
+        }
+        match state.circuit.poll_step() {
+            None => return StepOutcome::Running,
+            Some(polled) => polled,
+        }
+    } else {
+        state.step_circuit()
+    };
+    match result {
+        Ok(()) => StepOutcome::Finished,
+        Err(error) => {
+            // For a pipelined circuit the failed step's inputs and those
+            // staged since are both discarded, so nothing is launched until
+            // the next pass gathers them again.
+            roll_back_failed_step(commands, state, world, &error);
+            StepOutcome::Failed
+        }
+    }
+}
+
 /// Applies DBSP outputs back to ECS components.
 ///
-/// Steps the circuit, consolidates new positions and velocities, and updates
-/// the corresponding entities. The [`WorldHandle`] resource is updated with the
-/// latest positions for diagnostics.
+/// Consumes the step taken by [`step_dbsp_circuit_system`], stepping the
+/// circuit itself when run without it, consolidates new positions and
+/// velocities, and updates the corresponding entities, then moves each
+/// platform along. The [`WorldHandle`] resource is updated with the latest
+/// positions for diagnostics, and a [`NavGrid`], when present, with the
+/// terrain that changed.
 ///
 /// Outputs are drained after application to prevent reapplying stale deltas on
 /// subsequent frames.
+///
+/// A pipelined circuit applies the outputs of a step once it has finished, and
+/// then launches the next step on the inputs staged since, so the circuit
+/// computes while the rest of the frame runs. Frames in which the step is still
+/// running apply nothing.
+#[expect(
+    clippy::too_many_arguments,
+    reason = "System boundary requires multiple Bevy resources."
+)]
 pub fn apply_dbsp_outputs_system(
     mut commands: Commands,
     mut state: NonSendMut<DbspState>,
-    mut write_query: DbspWriteQuery<'_, '_>,
+    write_query: DbspWriteQuery<'_, '_>,
+    platform_query: PlatformQuery<'_, '_>,
     mut world_handle: ResMut<WorldHandle>,
+    nav_grid: Option<ResMut<NavGrid>>,
 ) {
-    if let Err(error) = state.step_circuit() {
-        commands.trigger(DbspSyncError::new(
-            DbspSyncErrorContext::Step,
-            error.to_string(),
-        ));
-        // Clear inputs even when stepping fails so the buffered records are not
-        // replayed on the next tick, then roll back the health/damage tracking
-        // that the cache system advanced this frame. Clearing the inputs alone
-        // would leave `health_snapshot`/`pending_damage_retractions` pointing at
-        // records the circuit never accepted, corrupting next frame's
-        // retractions.
-        state.circuit.clear_inputs();
-        state.rollback_frame_tracking();
-        state.step_failure_count += 1;
-        warn!(
-            "dbsp step failed; rolled back frame tracking and cleared inputs \
-             (context: {:?}, failures so far: {}): {error}",
-            DbspSyncErrorContext::Step,
-            state.step_failure_count
-        );
+    let outcome = state
+        .step_outcome
+        .take()
+        .unwrap_or_else(|| take_step(&mut commands, &mut state, &mut world_handle));
+    let mut targets = OutputTargets {
+        write_query,
+        platform_query,
+        world_handle,
+        nav_grid,
+    };
+    if state.circuit.is_pipelined() {
+        apply_pipelined_outputs(&mut commands, &mut state, &mut targets, outcome);
+        return;
+    }
+    if outcome != StepOutcome::Finished {
         return;
     }
+    apply_step_outputs(&mut commands, &mut state, &mut targets);
+    state.circuit.clear_inputs();
+    // The step succeeded and its inputs are now folded into the circuit; drop
+    // the pre-frame tracking backup so it cannot be rolled back later.
+    state.commit_frame_tracking();
+}
+
+/// ECS state the outputs of a step are written to.
+struct OutputTargets<'w, 's> {
+    write_query: DbspWriteQuery<'w, 's>,
+    platform_query: PlatformQuery<'w, 's>,
+    world_handle: ResMut<'w, WorldHandle>,
+    nav_grid: Option<ResMut<'w, NavGrid>>,
+}
+
+/// Applies a finished pipelined step and launches the next one.
+fn apply_pipelined_outputs(
+    commands: &mut Commands,
+    state: &mut DbspState,
+    targets: &mut OutputTargets<'_, '_>,
+    outcome: StepOutcome,
+) {
+    match outcome {
+        // The outputs are not ready yet, or the step failed; keep the frame
+        // moving.
+        StepOutcome::Running | StepOutcome::Failed => return,
+        StepOutcome::Finished => {
+            state.swap_launched_retractions();
+            apply_step_outputs(commands, state, targets);
+            state.swap_launched_retractions();
+            // Only the launched log is dropped: a pass made while the step
+            // ran keeps its own log until its step reports.
+            state.commit_launched_tracking();
+        }
+        StepOutcome::Idle => {}
+    }
+    if state.awaiting_launch {
+        state.launch_frame_tracking();
+        state.circuit.start_step();
+    }
+}
+
+/// Reports a failed step and restores the tracking its inputs advanced.
+fn roll_back_failed_step(
+    commands: &mut Commands,
+    state: &mut DbspState,
+    world: &mut WorldHandle,
+    error: &dbsp::Error,
+) {
+    commands.trigger(DbspSyncError::new(
+        DbspSyncErrorContext::Step,
+        error.to_string(),
+    ));
+    // Clear inputs even when stepping fails so the buffered records are not
+    // replayed on the next tick, then roll back the health/damage tracking
+    // that the cache system advanced this frame. Clearing the inputs alone
+    // would leave `health_snapshot`/`pending_damage_retractions` pointing at
+    // records the circuit never accepted, corrupting next frame's
+    // retractions.
+    state.circuit.clear_inputs();
+    state.rollback_frame_tracking();
+    restore_world_terrain(state, world);
+    state.step_failure_count += 1;
+    warn!(
+        "dbsp step failed; rolled back frame tracking and cleared inputs \
+         (context: {:?}, failures so far: {}): {error}",
+        DbspSyncErrorContext::Step,
+        state.step_failure_count
+    );
+}
 
+/// Mirrors the terrain the circuit still holds back into the [`WorldHandle`]
+/// after a rollback.
+///
+/// The cache pass updates the handle alongside [`DbspState::terrain_blocks`],
+/// so the entities a failed step queued for a terrain recheck are reset to
+/// their restored blocks, and their slopes with them.
+fn restore_world_terrain(state: &DbspState, world: &mut WorldHandle) {
+    for entity in &state.terrain_recheck {
+        if let Some(block) = world.blocks.remove(entity) {
+            world.slopes.remove(&block.id);
+        }
+        let Some((block, slope)) = state.terrain_blocks.get(entity) else {
+            continue;
+        };
+        if let Some(s) = slope {
+            world.slopes.insert(s.block_id, s.clone());
+        }
+        world.blocks.insert(*entity, block.clone());
+    }
+}
+
+/// Writes the outputs of a successful step to the ECS and drains them.
+fn apply_step_outputs(
+    commands: &mut Commands,
+    state: &mut DbspState,
+    targets: &mut OutputTargets<'_, '_>,
+) {
     // Tally the records the weight gates discarded. A single bounded counter,
     // with no per-entity labels, so it stays cheap to sample every frame.
-    let mut skipped = apply_positions(&state, &mut write_query, &mut world_handle);
-    skipped += apply_velocities(&state, &mut write_query);
-    skipped += apply_health_deltas(&mut state, &mut write_query, &mut world_handle);
+    let mut skipped = apply_positions(state, &mut targets.write_query, &mut targets.world_handle);
+    skipped += apply_velocities(state, &mut targets.write_query);
+    skipped += apply_health_deltas(state, &mut targets.write_query, &mut targets.world_handle);
     if skipped > 0 {
         state.skipped_output_count += skipped;
         debug!(
@@ -241,19 +432,31 @@ pub fn apply_dbsp_outputs_system(
             state.skipped_output_count
         );
     }
-    report_movement_aggregations(&state);
+    report_movement_aggregations(state);
+    report_arrivals(state);
+    advance_platforms(&mut targets.platform_query);
+    if let Some(grid) = targets.nav_grid.as_mut() {
+        let floors = state.circuit.floor_height_out().consolidate();
+        grid.apply(floors.iter().map(|(floor, (), weight)| (floor, weight)));
+        let highest = state.circuit.highest_block_out().consolidate();
+        grid.apply_highest_blocks(highest.iter().map(|(block, (), weight)| (block, weight)));
+    }
     let _ = state.circuit.health_delta_out().take_from_all();
 
     // Drain any remaining output so stale values are not reused.
     let _ = state.circuit.new_position_out().take_from_all();
     let _ = state.circuit.new_velocity_out().take_from_all();
     let _ = state.circuit.movement_aggregation_out().take_from_all();
+    let _ = state.circuit.arrived_out().take_from_all();
+
+    // Impulses last a single tick; drop the components the circuit consumed.
+    for impulse in &state.pending_impulse_retractions {
+        if let Some(entity) = state.entity_for_id(impulse.entity) {
+            commands.entity(entity).try_remove::<ImpulseComp>();
+        }
+    }
 
     state.expected_health_retractions.clear();
-    state.circuit.clear_inputs();
-    // The step succeeded and its inputs are now folded into the circuit; drop
-    // the pre-frame tracking backup so it cannot be rolled back later.
-    state.commit_frame_tracking();
 }
 
 #[cfg(test)]
@@ -17,7 +17,7 @@ fn plugin_app() -> App {
     let mut app = App::new();
     app.add_plugins(MinimalPlugins);
     dbsp_test_support::install_error_observer(&mut app);
-    app.add_plugins(DbspPlugin);
+    app.add_plugins(DbspPlugin::per_frame());
     app.world_mut().flush();
     app
 }
@@ -127,9 +127,10 @@ fn failed_step_rolls_back_health_tracking(#[from(plugin_app)] mut app: App) {
         .world()
         .non_send_resource::<DbspState>()
         .health_snapshot
+        .records()
         .clone();
     assert!(
-        snapshot_before.contains_key(&1),
+        snapshot_before.contains_key(&entity),
         "the first frame should record a health snapshot for entity 1"
     );
 
@@ -149,6 +150,7 @@ fn failed_step_rolls_back_health_tracking(#[from(plugin_app)] mut app: App) {
         .world()
         .non_send_resource::<DbspState>()
         .health_snapshot
+        .records()
         .clone();
     assert_eq!(
         snapshot_after, snapshot_before,
@@ -292,3 +294,93 @@ fn unsequenced_damage(amount: u16) -> DamageEvent {
         seq: None,
     }
 }
+
+#[rstest]
+fn failed_step_restores_terrain_blocks(#[from(plugin_app)] mut app: App) {
+    app.world_mut().spawn(Block {
+        id: 1,
+        x: 0,
+        y: 0,
+        z: 0,
+    });
+
+    force_step_failure(&mut app);
+    app.update();
+    assert!(
+        app.world()
+            .non_send_resource::<DbspState>()
+            .terrain_blocks
+            .is_empty(),
+        "the circuit never received the block"
+    );
+    assert_eq!(
+        app.world().resource::<WorldHandle>().block_count(),
+        0,
+        "the world handle mirrors the circuit's terrain"
+    );
+
+    restore_stepper(&mut app);
+    app.update();
+    let state = app.world().non_send_resource::<DbspState>();
+    assert_eq!(state.terrain_blocks.len(), 1);
+    assert_eq!(
+        state
+            .circuit
+            .floor_height_out()
+            .consolidate()
+            .iter()
+            .count(),
+        1,
+        "the block is pushed again after the failed step"
+    );
+}
+
+#[rstest]
+fn failed_step_restores_world_handle_terrain(#[from(plugin_app)] mut app: App) {
+    let slope = |grad_x: f64| BlockSlope {
+        block_id: 1,
+        grad_x: grad_x.into(),
+        grad_y: 0.0.into(),
+    };
+    let entity = app
+        .world_mut()
+        .spawn((
+            Block {
+                id: 1,
+                x: 0,
+                y: 0,
+                z: 0,
+            },
+            slope(0.5),
+        ))
+        .id();
+    app.update();
+
+    app.world_mut().entity_mut(entity).insert((
+        Block {
+            id: 1,
+            x: 0,
+            y: 0,
+            z: 1,
+        },
+        slope(1.0),
+    ));
+    force_step_failure(&mut app);
+    app.update();
+    let terrain = |updated: &App| {
+        let world = updated.world().resource::<WorldHandle>();
+        (
+            world.blocks.get(&entity).map(|block| block.z),
+            world.slopes.get(&1).map(|s| s.grad_x.into_inner()),
+        )
+    };
+    assert_eq!(
+        terrain(&app),
+        (Some(0), Some(0.5)),
+        "the world handle keeps the terrain the circuit still holds"
+    );
+
+    restore_stepper(&mut app);
+    app.update();
+    assert_eq!(terrain(&app), (Some(1), Some(1.0)));
+}
@@ -3,11 +3,12 @@
 mod counters;
 mod edge_cases;
 mod failure_paths;
+mod pipelined;
 mod pure_helpers;
 
 use super::health::{clamped_health_value, ClampedHealth};
 use super::*;
-use crate::components::{Block, DdlogId, Health, UnitType};
+use crate::components::{Block, BlockSlope, DdlogId, Health, UnitType};
 use crate::dbsp_circuit::{
     try_step, DamageEvent, DamageSource, HealthDelta, HealthState, Position, Velocity,
 };
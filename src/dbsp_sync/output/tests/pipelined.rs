@@ -0,0 +1,95 @@
+//! Output application for a circuit stepped on its own thread.
+
+use super::*;
+
+/// Builds an app with a pipelined circuit and an entity above a floor block.
+fn falling_entity_app() -> (App, Entity) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins).add_plugins(DbspPlugin {
+        pipelined: true,
+        ..DbspPlugin::per_frame()
+    });
+    app.world_mut().spawn(Block {
+        id: 1,
+        x: 0,
+        y: 0,
+        z: 0,
+    });
+    let entity = app
+        .world_mut()
+        .spawn((
+            DdlogId(1),
+            Transform::from_xyz(0.5, 0.5, 3.0),
+            VelocityComp::default(),
+        ))
+        .id();
+    (app, entity)
+}
+
+fn height(app: &App, entity: Entity) -> f32 {
+    app.world()
+        .get::<Transform>(entity)
+        .expect("entity should keep its transform")
+        .translation
+        .z
+}
+
+#[rstest]
+fn the_first_frame_launches_the_step_without_waiting() {
+    let (mut app, entity) = falling_entity_app();
+    app.update();
+    let state = app.world().non_send_resource::<DbspState>();
+    assert!(state.circuit.is_pipelined());
+    assert!(
+        state.circuit.is_stepping(),
+        "the step is collected by a later frame"
+    );
+    assert!(!state.awaiting_launch);
+    assert!((height(&app, entity) - 3.0).abs() < f32::EPSILON);
+}
+
+#[rstest]
+fn outputs_are_applied_once_ready() {
+    let (mut app, entity) = falling_entity_app();
+    for _ in 0..500 {
+        app.update();
+        if height(&app, entity) < 3.0 {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    assert!(
+        height(&app, entity) < 3.0,
+        "the entity should fall once a step reports"
+    );
+    let state = app.world().non_send_resource::<DbspState>();
+    assert_eq!(state.step_failures(), 0);
+}
+
+#[rstest]
+fn passes_while_a_step_runs_defer_their_changes() {
+    let (mut app, entity) = falling_entity_app();
+    app.update();
+    app.world_mut()
+        .non_send_resource_mut::<DbspState>()
+        .awaiting_launch = true;
+    app.world_mut()
+        .get_mut::<Transform>(entity)
+        .expect("entity should keep its transform")
+        .translation
+        .x = 0.25;
+
+    // Run only the cache pass, as a frame would while the step computes.
+    app.world_mut()
+        .run_system_once(crate::dbsp_sync::cache_state_for_dbsp_system)
+        .expect("cache system should run");
+
+    let state = app.world().non_send_resource::<DbspState>();
+    assert!(state.entity_recheck.contains(&entity));
+    let position = state
+        .entity_records
+        .positions
+        .get(entity)
+        .expect("the entity was pushed by the first pass");
+    assert!((position.x.into_inner() - 0.5).abs() < f64::EPSILON);
+}
@@ -1,14 +1,19 @@
 //! Bevy plugin wiring DBSP systems into the schedule.
 
+use std::time::Duration;
+
 use bevy::ecs::prelude::On;
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
 use bevy::prelude::*;
 use log::error;
 use thiserror::Error;
 
 use crate::world_handle::init_world_handle_system;
+use crate::PhysicsConfig;
 
 use super::{
-    apply_dbsp_outputs_system, cache_state_for_dbsp_system, init_dbsp_system, DamageInbox,
+    apply_dbsp_outputs_system, cache_state_for_dbsp_system, step_dbsp_circuit_system,
+    sync_physics_config_system, DamageInbox, DbspState,
 };
 
 #[cfg(feature = "observers-v1-spike")]
@@ -55,19 +60,168 @@ fn log_dbsp_error(event: On<DbspSyncError>) {
     error!("DBSP sync error during {context:?}: {detail}");
 }
 
-fn add_dbsp_sync_chain(app: &mut App) {
-    let chain = (cache_state_for_dbsp_system, apply_dbsp_outputs_system).chain();
+/// Keeps the fixed timestep in step with [`PhysicsConfig::delta_time`].
+///
+/// A configuration failing [`PhysicsConfig::validate`], or a tick length too
+/// long or too short for a [`Duration`], is rejected with an error log, and
+/// the fixed clock keeps its previous timestep.
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Bevy systems receive resources by value."
+)]
+fn sync_fixed_timestep_system(config: Res<PhysicsConfig>, time: Option<ResMut<Time<Fixed>>>) {
+    let Some(mut fixed) = time.filter(|_| config.is_changed()) else {
+        return;
+    };
+    if let Err(err) = config.validate() {
+        error!("ignoring physics config change: {err}");
+        return;
+    }
+    let delta_time = config.delta_time.into_inner();
+    match Duration::try_from_secs_f64(delta_time) {
+        Ok(timestep) if !timestep.is_zero() => fixed.set_timestep(timestep),
+        _ => error!("ignoring unrepresentable tick length of {delta_time} seconds"),
+    }
+}
+
+/// Phases of a DBSP synchronisation pass, run in order in the schedule chosen
+/// by [`DbspPlugin::schedule`].
+///
+/// Gameplay systems order against these sets rather than the sync systems
+/// themselves: write circuit inputs `.before(DbspSet::Ingest)` and read the
+/// simulated state `.after(DbspSet::Apply)`.
+///
+/// # Examples
+/// ```no_run
+/// use bevy::prelude::*;
+/// use lille::{DbspPlugin, DbspSet};
+///
+/// fn steer() {}
+/// fn react() {}
+///
+/// App::new()
+///     .add_plugins(MinimalPlugins)
+///     .add_plugins(DbspPlugin::default())
+///     .add_systems(FixedUpdate, (steer.before(DbspSet::Ingest), react.after(DbspSet::Apply)))
+///     .run();
+/// ```
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DbspSet {
+    /// Copies the physics configuration and changed ECS state into the
+    /// circuit's input handles.
+    Ingest,
+    /// Advances the circuit by one tick, or collects a pipelined step.
+    Step,
+    /// Writes the step's outputs back onto ECS components.
+    Apply,
+}
 
-    #[cfg(feature = "observers-v1-spike")]
-    app.add_systems(PostUpdate, chain);
+/// Schedule running the [`DbspSet`] phases, as configured by
+/// [`DbspPlugin::schedule`].
+///
+/// Inserted by the plugin so other plugins can order their systems against
+/// the phases without being told the schedule again.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DbspSchedule(pub InternedScheduleLabel);
 
-    #[cfg(not(feature = "observers-v1-spike"))]
-    app.add_systems(Update, chain);
+fn add_dbsp_sync_chain(app: &mut App, schedule: InternedScheduleLabel) {
+    if schedule == FixedUpdate.intern() {
+        app.add_systems(PreUpdate, sync_fixed_timestep_system);
+    }
+    app.configure_sets(
+        schedule,
+        (DbspSet::Ingest, DbspSet::Step, DbspSet::Apply).chain(),
+    );
+    app.add_systems(
+        schedule,
+        (
+            (sync_physics_config_system, cache_state_for_dbsp_system)
+                .chain()
+                .in_set(DbspSet::Ingest),
+            step_dbsp_circuit_system.in_set(DbspSet::Step),
+            apply_dbsp_outputs_system.in_set(DbspSet::Apply),
+        ),
+    );
 }
 
 /// Bevy plugin installing systems that synchronise DBSP with the ECS world.
-#[derive(Default)]
-pub struct DbspPlugin;
+///
+/// The plugin initialises a default [`PhysicsConfig`] resource unless the app
+/// already holds one, and forwards later changes to the circuit.
+///
+/// By default the circuit advances in `FixedUpdate`, one tick per
+/// [`PhysicsConfig::delta_time`] seconds of game time, so gameplay speed does
+/// not depend on the frame rate. A frame may run several ticks to catch up, or
+/// none at all. Each pass runs the [`DbspSet`] phases in order.
+///
+/// # Examples
+/// ```no_run
+/// use bevy::prelude::*;
+/// use lille::DbspPlugin;
+///
+/// // Spread the circuit step of a large scene across four threads, and step
+/// // it off the main thread while the next tick's inputs are gathered.
+/// App::new()
+///     .add_plugins(MinimalPlugins)
+///     .add_plugins(DbspPlugin {
+///         workers: 4,
+///         pipelined: true,
+///         ..DbspPlugin::default()
+///     })
+///     .run();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DbspPlugin {
+    /// Worker threads evaluating the circuit, as for
+    /// [`DbspCircuit::with_workers`](crate::dbsp_circuit::DbspCircuit::with_workers).
+    /// Every worker count yields the same outputs; the default of one steps
+    /// the circuit on the thread running the sync systems.
+    pub workers: usize,
+    /// Steps the circuit on a dedicated thread, as for
+    /// [`DbspCircuit::pipelined`](crate::dbsp_circuit::DbspCircuit::pipelined).
+    /// Each frame gathers the inputs of the next tick while the current one
+    /// computes, and applies a tick's outputs in the first frame after it
+    /// finishes, so rendering never waits for the step. Entities therefore see
+    /// their own outputs a tick later. The default steps the circuit within
+    /// the frame, which keeps outputs in a deterministic order relative to the
+    /// ECS and is what tests rely on.
+    pub pipelined: bool,
+    /// Schedule running the [`DbspSet`] phases. The default, `FixedUpdate`,
+    /// has the plugin keep Bevy's fixed timestep at
+    /// [`PhysicsConfig::delta_time`]. Any other schedule steps the circuit
+    /// once per run, each tick still covering `delta_time` seconds.
+    pub schedule: InternedScheduleLabel,
+}
+
+impl DbspPlugin {
+    /// Returns a plugin stepping the circuit once per app update, so tests
+    /// can advance the simulation one tick per call to `App::update`.
+    ///
+    /// The passes run in `Update`, or in `PostUpdate` under the
+    /// `observers-v1-spike` feature so damage triggered during `Update` is
+    /// buffered before the same frame's pass drains it.
+    #[must_use]
+    pub fn per_frame() -> Self {
+        #[cfg(feature = "observers-v1-spike")]
+        let schedule = PostUpdate.intern();
+        #[cfg(not(feature = "observers-v1-spike"))]
+        let schedule = Update.intern();
+        Self {
+            schedule,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for DbspPlugin {
+    fn default() -> Self {
+        Self {
+            workers: 1,
+            pipelined: false,
+            schedule: FixedUpdate.intern(),
+        }
+    }
+}
 
 impl Plugin for DbspPlugin {
     fn build(&self, app: &mut App) {
@@ -76,17 +230,27 @@ impl Plugin for DbspPlugin {
         #[cfg(feature = "observers-v1-spike")]
         app.add_observer(observers_v1::buffer_damage_ingress);
 
-        if let Err(e) = init_dbsp_system(app.world_mut()) {
-            app.world_mut().trigger(DbspSyncError::new(
-                DbspSyncErrorContext::Init,
-                e.to_string(),
-            ));
-            return;
+        let built = if self.pipelined {
+            DbspState::pipelined(self.workers)
+        } else {
+            DbspState::with_workers(self.workers)
+        };
+        match built {
+            Ok(state) => app.world_mut().insert_non_send_resource(state),
+            Err(e) => {
+                app.world_mut().trigger(DbspSyncError::new(
+                    DbspSyncErrorContext::Init,
+                    e.to_string(),
+                ));
+                return;
+            }
         }
 
         app.init_resource::<DamageInbox>();
+        app.init_resource::<PhysicsConfig>();
+        app.insert_resource(DbspSchedule(self.schedule));
         app.add_systems(Startup, init_world_handle_system);
-        add_dbsp_sync_chain(app);
+        add_dbsp_sync_chain(app, self.schedule);
     }
 }
 
@@ -96,16 +260,153 @@ mod tests {
     use super::*;
     use crate::dbsp_sync::DbspState;
     use crate::world_handle::WorldHandle;
+    use crate::{DdlogId, VelocityComp};
+    use approx::assert_relative_eq;
+    use bevy::time::TimeUpdateStrategy;
+    use ordered_float::OrderedFloat;
     use rstest::rstest;
 
     #[rstest]
     fn plugin_initialises_resources() {
         let mut app = App::new();
         app.add_plugins(MinimalPlugins);
-        app.add_plugins(DbspPlugin);
+        app.add_plugins(DbspPlugin::default());
         assert!(app.world().contains_resource::<DamageInbox>());
+        assert!(app.world().contains_resource::<PhysicsConfig>());
         assert!(app.world().get_non_send_resource::<DbspState>().is_some());
         app.update();
         assert!(app.world().contains_resource::<WorldHandle>());
     }
+
+    #[rstest]
+    fn plugin_builds_the_configured_worker_count() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(DbspPlugin {
+            workers: 2,
+            ..DbspPlugin::default()
+        });
+        let state = app.world().non_send_resource::<DbspState>();
+        assert_eq!(state.circuit.workers(), 2);
+        app.update();
+    }
+
+    #[rstest]
+    fn circuit_ticks_once_per_delta_time() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(DbspPlugin::default());
+        app.insert_resource(PhysicsConfig {
+            delta_time: OrderedFloat(0.5),
+            ..PhysicsConfig::default()
+        });
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            250,
+        )));
+        let entity = app
+            .world_mut()
+            .spawn((
+                DdlogId(1),
+                Transform::from_xyz(0.0, 0.0, 10.0),
+                VelocityComp::default(),
+            ))
+            .id();
+        let height = |updated: &App| {
+            updated
+                .world()
+                .get::<Transform>(entity)
+                .expect("entity keeps its transform")
+                .translation
+                .z
+        };
+
+        // The first update starts the clock, and a tick needs two more
+        // quarter-second frames to accumulate.
+        app.update();
+        app.update();
+        assert_eq!(
+            app.world().resource::<Time<Fixed>>().timestep(),
+            Duration::from_millis(500)
+        );
+        assert_relative_eq!(height(&app), 10.0);
+        app.update();
+        // Half a second of gravity: vz = -0.5, so z drops by 0.25.
+        assert_relative_eq!(height(&app), 9.75);
+        app.update();
+        assert_relative_eq!(height(&app), 9.75);
+        app.update();
+        assert_relative_eq!(height(&app), 9.25);
+    }
+
+    #[rstest]
+    #[case::zero_tick(PhysicsConfig {
+        delta_time: OrderedFloat(0.0),
+        ..PhysicsConfig::default()
+    })]
+    #[case::negative_arrival_radius(PhysicsConfig {
+        arrival_radius: OrderedFloat(-1.0),
+        ..PhysicsConfig::default()
+    })]
+    fn invalid_config_changes_keep_the_last_good_config(#[case] invalid: PhysicsConfig) {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(DbspPlugin::default());
+        let good = PhysicsConfig {
+            delta_time: OrderedFloat(0.5),
+            ..PhysicsConfig::default()
+        };
+        app.insert_resource(good);
+        app.update();
+        app.world_mut().run_schedule(FixedUpdate);
+
+        app.insert_resource(invalid);
+        app.update();
+        app.world_mut().run_schedule(FixedUpdate);
+
+        assert_eq!(
+            app.world().resource::<Time<Fixed>>().timestep(),
+            Duration::from_millis(500)
+        );
+        let state = app.world().non_send_resource::<DbspState>();
+        assert_eq!(state.circuit.physics_config(), &good);
+    }
+
+    /// Heights read by a system ordered after [`DbspSet::Apply`].
+    #[derive(Resource, Default)]
+    struct ObservedHeights(Vec<f32>);
+
+    fn observe_heights(
+        mut observed: ResMut<ObservedHeights>,
+        query: Query<&Transform, With<DdlogId>>,
+    ) {
+        observed
+            .0
+            .extend(query.iter().map(|transform| transform.translation.z));
+    }
+
+    #[rstest]
+    fn systems_after_apply_see_the_same_pass_outputs() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(DbspPlugin {
+            schedule: PostUpdate.intern(),
+            ..DbspPlugin::default()
+        });
+        app.init_resource::<ObservedHeights>();
+        app.add_systems(PostUpdate, observe_heights.after(DbspSet::Apply));
+        app.world_mut().spawn((
+            DdlogId(1),
+            Transform::from_xyz(0.0, 0.0, 10.0),
+            VelocityComp::default(),
+        ));
+
+        app.update();
+
+        // One tick of gravity at the default one-second step: z drops by 1.
+        let observed = &app.world().resource::<ObservedHeights>().0;
+        let &[height] = observed.as_slice() else {
+            panic!("expected one observation, got {observed:?}");
+        };
+        assert_relative_eq!(height, 9.0);
+    }
 }
@@ -0,0 +1,50 @@
+//! Rollback log of the tracking a cache pass advances.
+
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::Entity;
+
+use crate::dbsp_circuit::{DamageEvent, EntityId, Impulse, PlatformBlock, Tick};
+
+use super::TerrainBlock;
+
+/// Pre-frame values of the tracking a cache pass changed, restored when the
+/// circuit step consuming the frame's inputs fails.
+///
+/// Each entry holds the value from before the first change the log saw, so
+/// restoring the whole log returns the tracking to where it started.
+#[derive(Default)]
+pub(super) struct FrameLog {
+    /// Pre-frame value of `DbspState::pending_damage_retractions`.
+    pub(super) pending_damage: Option<Vec<DamageEvent>>,
+    /// Pre-frame value of `DbspState::pending_impulse_retractions`.
+    pub(super) pending_impulses: Option<Vec<Impulse>>,
+    /// Pre-frame value of `DbspState::pending_platform_retractions`.
+    pub(super) pending_platforms: Option<Vec<PlatformBlock>>,
+    /// Prior `DbspState::terrain_blocks` entry of each touched entity.
+    pub(super) terrain: HashMap<Entity, Option<TerrainBlock>>,
+    /// Prior `DbspState::applied_unsequenced` entry of each touched entity.
+    pub(super) applied_unsequenced: HashMap<EntityId, Option<(Tick, HashSet<DamageEvent>)>>,
+}
+
+impl FrameLog {
+    /// Folds a log recorded after this one into it, keeping this log's older
+    /// value wherever both recorded one.
+    pub(super) fn absorb(&mut self, newer: Self) {
+        if self.pending_damage.is_none() {
+            self.pending_damage = newer.pending_damage;
+        }
+        if self.pending_impulses.is_none() {
+            self.pending_impulses = newer.pending_impulses;
+        }
+        if self.pending_platforms.is_none() {
+            self.pending_platforms = newer.pending_platforms;
+        }
+        for (entity, previous) in newer.terrain {
+            self.terrain.entry(entity).or_insert(previous);
+        }
+        for (entity, previous) in newer.applied_unsequenced {
+            self.applied_unsequenced.entry(entity).or_insert(previous);
+        }
+    }
+}
@@ -5,8 +5,10 @@ use std::collections::{HashMap, HashSet};
 use bevy::ecs::system::SystemParam;
 use bevy::prelude::{Added, Changed, Entity, Query, RemovedComponents};
 
-use crate::components::DdlogId;
-use crate::dbsp_circuit::{try_step, DamageEvent, DbspCircuit, EntityId, HealthState, Tick};
+use crate::components::{Block, BlockSlope, DdlogId};
+use crate::dbsp_circuit::{
+    try_step, DamageEvent, DbspCircuit, EntityId, HealthState, Impulse, PlatformBlock, Tick,
+};
 
 /// Resource storing the DBSP circuit and deduplication state.
 pub struct DbspState {
@@ -27,23 +29,56 @@ pub struct DbspState {
     pub(crate) applied_unsequenced: HashMap<EntityId, (Tick, HashSet<DamageEvent>)>,
     /// Caches the last health state pushed to the circuit for each entity.
     /// Used to generate retractions when health state changes.
-    pub(crate) health_snapshot: HashMap<EntityId, HealthState>,
+    pub(crate) health_snapshot: PushedRecords<HealthState>,
+    /// Position, velocity, target, force, extent, drag and unit records the
+    /// circuit holds for each entity, replaced only when their components
+    /// change.
+    pub(crate) entity_records: EntityRecords,
+    /// Entities whose records must be compared again on the next pass even
+    /// without a component change, because a failed step undid their diff.
+    pub(crate) entity_recheck: HashSet<Entity>,
     /// Tracks damage events that were retracted in the current frame.
     /// Used to filter out corresponding health deltas to avoid double-application.
     pub(crate) expected_health_retractions: HashSet<(EntityId, Tick, Option<u32>)>,
     /// Damage events pending retraction at the start of the next frame.
     pub(crate) pending_damage_retractions: Vec<DamageEvent>,
-    /// Pre-frame health snapshots the cache pass drains out of
-    /// [`Self::health_snapshot`], stashed (not cloned) so a failed circuit step
-    /// can rebuild the map from them.
-    health_snapshot_backup: Option<Vec<HealthState>>,
-    /// Pre-frame value of [`Self::pending_damage_retractions`] the cache pass
-    /// takes, restored on a failed circuit step.
-    pending_damage_backup: Option<Vec<DamageEvent>>,
-    /// Undo log of [`Self::applied_unsequenced`] entries mutated during the
-    /// cache pass. Records each touched entity's prior value once, so a failed
-    /// step can restore it without deep-cloning the whole map every frame.
-    applied_unsequenced_undo: HashMap<EntityId, Option<(Tick, HashSet<DamageEvent>)>>,
+    /// Impulses pushed into the circuit this frame. They are retracted at the
+    /// start of the next frame, and the output system removes their
+    /// `ImpulseComp` components once the step succeeds.
+    pub(crate) pending_impulse_retractions: Vec<Impulse>,
+    /// Moving platform blocks pushed into the circuit this frame. They are
+    /// retracted at the start of the next frame, when the platforms have moved,
+    /// and the output system advances each platform once the step succeeds.
+    pub(crate) pending_platform_retractions: Vec<PlatformBlock>,
+    /// Terrain blocks currently held by the circuit, keyed by the entity
+    /// carrying them: every static block and the block of every closed door.
+    /// Blocks are pushed when they appear and retracted when they are removed,
+    /// changed or opened, so the circuit holds each exactly once.
+    pub(crate) terrain_blocks: HashMap<Entity, TerrainBlock>,
+    /// Entities whose terrain must be compared again on the next pass even
+    /// without a component change, because a failed step undid their diff.
+    pub(crate) terrain_recheck: HashSet<Entity>,
+    /// Set when a map is created so the next pass compares every terrain
+    /// entity rather than only the changed ones.
+    pub(crate) terrain_reconcile: bool,
+    /// Rollback log of the current cache pass: the pre-frame pending
+    /// retractions it took, and the prior [`Self::terrain_blocks`] and
+    /// [`Self::applied_unsequenced`] entry of each entity it touched, recorded
+    /// once so a failed step can restore them without deep-cloning the maps.
+    frame_log: FrameLog,
+    /// Rollback log of the frames whose inputs a still-running pipelined step
+    /// consumes, moved out of [`Self::frame_log`] when the step was launched.
+    launched_log: FrameLog,
+    /// [`Self::expected_health_retractions`] of the launched step, consulted
+    /// while its outputs are applied.
+    launched_health_retractions: HashSet<(EntityId, Tick, Option<u32>)>,
+    /// Set when a cache pass has staged inputs that no step has consumed yet.
+    /// A pipelined circuit gathers one frame of inputs per tick, so later
+    /// passes only note the changes they observe until the step is launched.
+    pub(crate) awaiting_launch: bool,
+    /// Result of the step taken in [`DbspSet::Step`](super::DbspSet::Step),
+    /// consumed by the output pass of the same tick.
+    pub(crate) step_outcome: Option<StepOutcome>,
     /// Running count of duplicate health/damage events filtered.
     /// Used for diagnostics and monitoring deduplication effectiveness.
     pub(crate) health_duplicate_count: u64,
@@ -56,6 +91,19 @@ pub struct DbspState {
     pub(crate) skipped_output_count: u64,
 }
 
+/// What the step phase of a synchronisation pass left for the output pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StepOutcome {
+    /// A step finished and its outputs are ready to apply.
+    Finished,
+    /// The step failed and its inputs were rolled back.
+    Failed,
+    /// A pipelined step is still computing.
+    Running,
+    /// No pipelined step was in flight.
+    Idle,
+}
+
 /// Convenience wrapper exposing queries required to track `DdlogId` changes.
 #[derive(SystemParam)]
 pub struct IdQueries<'w, 's> {
@@ -74,23 +122,56 @@ impl DbspState {
     /// Returns a DBSP error if the underlying circuit fails to construct.
     #[must_use = "DbspState initialisation may fail; handle the Result"]
     pub fn new() -> Result<Self, dbsp::Error> {
-        Ok(Self {
-            circuit: DbspCircuit::new()?,
+        Self::with_workers(1)
+    }
+
+    /// Creates a new [`DbspState`] whose circuit is evaluated by `workers`
+    /// threads, as built by [`DbspCircuit::with_workers`].
+    ///
+    /// # Errors
+    /// Returns a DBSP error if the underlying circuit fails to construct.
+    #[must_use = "DbspState initialisation may fail; handle the Result"]
+    pub fn with_workers(workers: usize) -> Result<Self, dbsp::Error> {
+        Ok(Self::from_circuit(DbspCircuit::with_workers(workers)?))
+    }
+
+    /// Creates a new [`DbspState`] whose circuit steps on a dedicated thread,
+    /// as built by [`DbspCircuit::pipelined`].
+    ///
+    /// # Errors
+    /// Returns a DBSP error if the underlying circuit fails to construct.
+    #[must_use = "DbspState initialisation may fail; handle the Result"]
+    pub fn pipelined(workers: usize) -> Result<Self, dbsp::Error> {
+        Ok(Self::from_circuit(DbspCircuit::pipelined(workers)?))
+    }
+
+    fn from_circuit(circuit: DbspCircuit) -> Self {
+        Self {
+            circuit,
             stepper: try_step,
             id_map: HashMap::new(),
             rev_map: HashMap::new(),
             applied_health: HashMap::new(),
             applied_unsequenced: HashMap::new(),
-            health_snapshot: HashMap::new(),
+            health_snapshot: PushedRecords::default(),
+            entity_records: EntityRecords::default(),
+            entity_recheck: HashSet::new(),
             expected_health_retractions: HashSet::new(),
             pending_damage_retractions: Vec::new(),
-            health_snapshot_backup: None,
-            pending_damage_backup: None,
-            applied_unsequenced_undo: HashMap::new(),
+            pending_impulse_retractions: Vec::new(),
+            pending_platform_retractions: Vec::new(),
+            terrain_blocks: HashMap::new(),
+            terrain_recheck: HashSet::new(),
+            terrain_reconcile: false,
+            frame_log: FrameLog::default(),
+            launched_log: FrameLog::default(),
+            launched_health_retractions: HashSet::new(),
+            awaiting_launch: false,
+            step_outcome: None,
             health_duplicate_count: 0,
             step_failure_count: 0,
             skipped_output_count: 0,
-        })
+        }
     }
 
     /// Looks up the Bevy [`Entity`] for a DBSP identifier.
@@ -169,9 +250,11 @@ impl DbspState {
     /// 1. [`begin_frame_rollback`](Self::begin_frame_rollback) clears the
     ///    previous frame's rollback log at the top of the cache pass.
     /// 2. [`record_unsequenced_undo`](Self::record_unsequenced_undo) captures an
-    ///    `applied_unsequenced` entry's pre-frame value *before* it is mutated.
-    /// 3. [`stash_frame_rollback`](Self::stash_frame_rollback) saves the health
-    ///    snapshot and pending-damage values the cache pass already extracted.
+    ///    `applied_unsequenced` entry's pre-frame value *before* it is mutated,
+    ///    and replacing a `health_snapshot` record logs its pre-frame value the
+    ///    same way.
+    /// 3. [`stash_frame_rollback`](Self::stash_frame_rollback) saves the
+    ///    pending-damage values the cache pass already extracted.
     /// 4. Then exactly one of, after the circuit step:
     ///    - [`commit_frame_tracking`](Self::commit_frame_tracking) on success —
     ///      discards the log; the advanced tracking stands.
@@ -181,6 +264,13 @@ impl DbspState {
     /// Backups reuse values the cache pass already moved out of the live state,
     /// so no frame deep-clones the whole tracking state.
     ///
+    /// A pipelined circuit launches the step between 3 and 4 with
+    /// [`launch_frame_tracking`](Self::launch_frame_tracking), and resolves it
+    /// once it reports — possibly after the next cache pass has begun a log of
+    /// its own — with
+    /// [`commit_launched_tracking`](Self::commit_launched_tracking) or the
+    /// same rollback.
+    ///
     /// # Examples
     ///
     /// Successful frame keeps the advanced tracking:
@@ -188,7 +278,7 @@ impl DbspState {
     /// state.begin_frame_rollback();
     /// state.record_unsequenced_undo(entity);          // capture pre-frame entry
     /// state.applied_unsequenced.insert(entity, next); // cache pass mutates it
-    /// state.stash_frame_rollback(prev_snapshots, prev_pending);
+    /// state.stash_frame_rollback(prev_pending);
     /// // ... step succeeds ...
     /// state.commit_frame_tracking();                  // applied_unsequenced == next
     /// ```
@@ -198,7 +288,7 @@ impl DbspState {
     /// state.begin_frame_rollback();
     /// state.record_unsequenced_undo(entity);
     /// state.applied_unsequenced.insert(entity, next);
-    /// state.stash_frame_rollback(prev_snapshots, prev_pending);
+    /// state.stash_frame_rollback(prev_pending);
     /// // ... step fails; inputs cleared ...
     /// state.rollback_frame_tracking();                // entry back to pre-frame value
     /// ```
@@ -211,30 +301,51 @@ impl DbspState {
     /// (fresh frame) and [`Self::commit_frame_tracking`] (frame committed) so
     /// both paths stay identical as tracking fields evolve.
     fn clear_frame_rollback(&mut self) {
-        self.health_snapshot_backup = None;
-        self.pending_damage_backup = None;
-        self.applied_unsequenced_undo.clear();
+        self.frame_log = FrameLog::default();
+        self.health_snapshot.commit();
+        self.entity_records.commit();
     }
 
-    /// Stashes the pre-frame health snapshots and pending damage retractions —
-    /// values the cache pass has already extracted from the live state — so a
-    /// failed step can restore them without an extra clone.
+    /// Stashes the pre-frame pending damage retractions — values the cache pass
+    /// has already extracted from the live state — so a failed step can
+    /// restore them without an extra clone.
     ///
     /// Idempotent within a frame, like
     /// [`record_unsequenced_undo`](Self::record_unsequenced_undo): only the
     /// first call after [`begin_frame_rollback`](Self::begin_frame_rollback)
     /// stores anything, so a repeat call cannot overwrite the true pre-frame
     /// values with already-advanced ones.
-    pub(crate) fn stash_frame_rollback(
-        &mut self,
-        health_snapshot: Vec<HealthState>,
-        pending_damage: Vec<DamageEvent>,
-    ) {
-        if self.health_snapshot_backup.is_none() {
-            self.health_snapshot_backup = Some(health_snapshot);
+    pub(crate) fn stash_frame_rollback(&mut self, pending_damage: Vec<DamageEvent>) {
+        if self.frame_log.pending_damage.is_none() {
+            self.frame_log.pending_damage = Some(pending_damage);
+        }
+    }
+
+    /// Stashes the pre-frame pending impulse retractions so a failed step can
+    /// restore them. Idempotent within a frame, like
+    /// [`stash_frame_rollback`](Self::stash_frame_rollback).
+    pub(crate) fn stash_impulse_rollback(&mut self, pending_impulses: Vec<Impulse>) {
+        if self.frame_log.pending_impulses.is_none() {
+            self.frame_log.pending_impulses = Some(pending_impulses);
         }
-        if self.pending_damage_backup.is_none() {
-            self.pending_damage_backup = Some(pending_damage);
+    }
+
+    /// Stashes the pre-frame platform blocks so a failed step can restore
+    /// them. Idempotent within a frame, like
+    /// [`stash_frame_rollback`](Self::stash_frame_rollback).
+    pub(crate) fn stash_platform_rollback(&mut self, pending_platforms: Vec<PlatformBlock>) {
+        if self.frame_log.pending_platforms.is_none() {
+            self.frame_log.pending_platforms = Some(pending_platforms);
+        }
+    }
+
+    /// Records the pre-frame [`Self::terrain_blocks`] entry for `entity` once
+    /// per frame, before the cache pass changes it, like
+    /// [`record_unsequenced_undo`](Self::record_unsequenced_undo).
+    pub(crate) fn record_terrain_undo(&mut self, entity: Entity) {
+        if !self.frame_log.terrain.contains_key(&entity) {
+            let previous = self.terrain_blocks.get(&entity).cloned();
+            self.frame_log.terrain.insert(entity, previous);
         }
     }
 
@@ -242,9 +353,9 @@ impl DbspState {
     /// once per frame, before the cache pass mutates it, so a failed step can
     /// undo the change. Repeat calls for the same entity in a frame are no-ops.
     pub(crate) fn record_unsequenced_undo(&mut self, entity: EntityId) {
-        if !self.applied_unsequenced_undo.contains_key(&entity) {
+        if !self.frame_log.applied_unsequenced.contains_key(&entity) {
             let previous = self.applied_unsequenced.get(&entity).cloned();
-            self.applied_unsequenced_undo.insert(entity, previous);
+            self.frame_log.applied_unsequenced.insert(entity, previous);
         }
     }
 
@@ -254,6 +365,45 @@ impl DbspState {
         self.clear_frame_rollback();
     }
 
+    /// Hands the frame rollback log to a pipelined step about to consume the
+    /// staged inputs, so the next cache pass can log its own changes while the
+    /// step runs.
+    ///
+    /// The launched log is resolved with
+    /// [`commit_launched_tracking`](Self::commit_launched_tracking) once the
+    /// step succeeds. When it fails,
+    /// [`rollback_frame_tracking`](Self::rollback_frame_tracking) restores the
+    /// tracking from before the launched step, undoing the later pass too,
+    /// whose staged inputs are cleared with it.
+    pub(crate) fn launch_frame_tracking(&mut self) {
+        let staged = std::mem::take(&mut self.frame_log);
+        self.launched_log.absorb(staged);
+        self.health_snapshot.launch();
+        self.entity_records.launch();
+        self.launched_health_retractions = std::mem::take(&mut self.expected_health_retractions);
+        self.awaiting_launch = false;
+    }
+
+    /// Discards the launched step's rollback log once that step has succeeded,
+    /// leaving the log of any pass made since in place.
+    pub(crate) fn commit_launched_tracking(&mut self) {
+        self.launched_log = FrameLog::default();
+        self.health_snapshot.commit_launched();
+        self.entity_records.commit_launched();
+        self.launched_health_retractions.clear();
+    }
+
+    /// Exchanges [`Self::expected_health_retractions`] with the launched
+    /// step's markers, so its health deltas are filtered against the
+    /// retractions it consumed rather than those staged since. Calling it
+    /// again restores the staged markers.
+    pub(crate) const fn swap_launched_retractions(&mut self) {
+        std::mem::swap(
+            &mut self.expected_health_retractions,
+            &mut self.launched_health_retractions,
+        );
+    }
+
     /// Restores the pre-frame health/damage tracking after a failed step whose
     /// circuit inputs were cleared without being applied, keeping the Rust-side
     /// bookkeeping consistent with the circuit's actual records. A no-op when no
@@ -266,17 +416,48 @@ impl DbspState {
     /// correction — it keeps the rollback complete instead of relying on the
     /// cache-precedes-output ordering holding forever.
     pub(crate) fn rollback_frame_tracking(&mut self) {
-        if let Some(snapshots) = self.health_snapshot_backup.take() {
-            self.health_snapshot = snapshots
-                .into_iter()
-                .map(|snapshot| (snapshot.entity, snapshot))
-                .collect();
-        }
-        if let Some(pending) = self.pending_damage_backup.take() {
+        // A launched step's log predates the current pass's, so its values
+        // win where both logged an entry.
+        let mut log = std::mem::take(&mut self.launched_log);
+        log.absorb(std::mem::take(&mut self.frame_log));
+        self.awaiting_launch = false;
+        self.launched_health_retractions.clear();
+        // Entity records changed this frame went out with the cleared inputs,
+        // so the circuit still holds their pre-frame values.
+        self.health_snapshot.rollback(&mut self.entity_recheck);
+        self.entity_records.rollback(&mut self.entity_recheck);
+        if let Some(pending) = log.pending_damage {
             self.pending_damage_retractions = pending;
         }
+        // The previous frame's impulses are still in the circuit, while this
+        // frame's went out with the cleared inputs. Their components stay in
+        // place, so they are pushed again on the next frame.
+        if let Some(pending) = log.pending_impulses {
+            self.pending_impulse_retractions = pending;
+        }
+        // Likewise the circuit still holds last frame's platform blocks, and
+        // the platforms did not advance, so this frame's blocks are pushed
+        // again unchanged.
+        if let Some(pending) = log.pending_platforms {
+            self.pending_platform_retractions = pending;
+        }
+        // The terrain diff went out with the cleared inputs, so the circuit
+        // still holds the pre-frame terrain blocks. The component changes that
+        // triggered the diff have been observed, so the touched entities are
+        // compared again on the next pass.
+        for (entity, previous) in log.terrain {
+            match previous {
+                Some(terrain) => {
+                    self.terrain_blocks.insert(entity, terrain);
+                }
+                None => {
+                    self.terrain_blocks.remove(&entity);
+                }
+            }
+            self.terrain_recheck.insert(entity);
+        }
         self.expected_health_retractions.clear();
-        for (entity, previous) in std::mem::take(&mut self.applied_unsequenced_undo) {
+        for (entity, previous) in log.applied_unsequenced {
             match previous {
                 Some(entry) => {
                     self.applied_unsequenced.insert(entity, entry);
@@ -303,5 +484,22 @@ impl DbspState {
     }
 }
 
+/// A terrain block together with its optional slope, as mirrored into the
+/// circuit.
+pub(crate) type TerrainBlock = (Block, Option<BlockSlope>);
+
+/// Identifier of a moving platform's `PlatformBlock` records.
+///
+/// Platforms carry no `DdlogId`, so their Bevy entity index names them.
+pub(crate) fn platform_id(entity: Entity) -> i64 {
+    i64::from(entity.index_u32())
+}
+
+mod frame_log;
+mod records;
+
+use frame_log::FrameLog;
+pub(crate) use records::{EntityRecords, PushedRecords};
+
 #[cfg(test)]
 mod tests;
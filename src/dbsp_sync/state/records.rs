@@ -0,0 +1,184 @@
+//! Per-entity records mirrored into circuit inputs.
+
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::Entity;
+
+use crate::dbsp_circuit::{
+    Baddie, Civvy, Drag, Extent, Force, PathGoal, Position, StagedZSet, Target, Velocity,
+};
+
+/// Records last pushed into one circuit input, one per Bevy entity.
+///
+/// Circuit inputs are deltas, so a record stays in the circuit until it is
+/// retracted. [`Self::replace`] retracts an entity's previous record at `-1`
+/// and pushes its new one at `+1`, keeping the circuit holding exactly the
+/// tracked records, and logs the change so a failed step can undo it.
+pub(crate) struct PushedRecords<R> {
+    records: HashMap<Entity, R>,
+    /// Pre-frame record of each entity changed since the last commit.
+    undo: HashMap<Entity, Option<R>>,
+    /// Pre-frame record of each entity changed by the frames whose step is
+    /// still running, moved out of `undo` when that step was launched.
+    launched: HashMap<Entity, Option<R>>,
+}
+
+impl<R> Default for PushedRecords<R> {
+    fn default() -> Self {
+        Self {
+            records: HashMap::new(),
+            undo: HashMap::new(),
+            launched: HashMap::new(),
+        }
+    }
+}
+
+impl<R: Clone + PartialEq + dbsp::DBData> PushedRecords<R> {
+    /// Returns the record the circuit holds for `entity`.
+    pub(crate) fn get(&self, entity: Entity) -> Option<&R> {
+        self.records.get(&entity)
+    }
+
+    /// Returns the tracked records keyed by entity.
+    #[cfg(test)]
+    pub(crate) const fn records(&self) -> &HashMap<Entity, R> {
+        &self.records
+    }
+
+    /// Returns `true` when the circuit holds no records from this tracker.
+    #[cfg(test)]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Makes `record` the one the circuit holds for `entity`, pushing the
+    /// retraction/insertion pair through `input`. `None` retracts the entity's
+    /// record; an unchanged record pushes nothing.
+    pub(crate) fn replace(&mut self, input: &StagedZSet<R>, entity: Entity, record: Option<R>) {
+        if self.records.get(&entity) == record.as_ref() {
+            return;
+        }
+        if !self.undo.contains_key(&entity) {
+            self.undo.insert(entity, self.records.get(&entity).cloned());
+        }
+        let previous = match &record {
+            Some(next) => self.records.insert(entity, next.clone()),
+            None => self.records.remove(&entity),
+        };
+        if let Some(old) = previous {
+            input.push(old, -1);
+        }
+        if let Some(next) = record {
+            input.push(next, 1);
+        }
+    }
+
+    /// Drops the undo log once a successful step has committed the frame.
+    pub(crate) fn commit(&mut self) {
+        self.undo.clear();
+    }
+
+    /// Hands the undo log to the step about to be launched, so changes made
+    /// while it runs start a fresh log.
+    pub(crate) fn launch(&mut self) {
+        for (entity, previous) in std::mem::take(&mut self.undo) {
+            self.launched.entry(entity).or_insert(previous);
+        }
+    }
+
+    /// Drops the launched step's undo log once that step has succeeded.
+    pub(crate) fn commit_launched(&mut self) {
+        self.launched.clear();
+    }
+
+    /// Restores the pre-frame records after a failed step and adds each
+    /// touched entity to `recheck`, so the next pass compares it again even
+    /// though its component changes have already been observed. Changes made
+    /// since a launched step started are undone too, returning every record to
+    /// its value before that step.
+    pub(crate) fn rollback(&mut self, recheck: &mut HashSet<Entity>) {
+        // The launched log holds the older values, so it is restored last.
+        let undo = std::mem::take(&mut self.undo);
+        let launched = std::mem::take(&mut self.launched);
+        for (entity, previous) in undo.into_iter().chain(launched) {
+            match previous {
+                Some(record) => {
+                    self.records.insert(entity, record);
+                }
+                None => {
+                    self.records.remove(&entity);
+                }
+            }
+            recheck.insert(entity);
+        }
+    }
+}
+
+/// Records the circuit holds for each entity carrying a `DdlogId`.
+#[derive(Default)]
+pub(crate) struct EntityRecords {
+    pub(crate) positions: PushedRecords<Position>,
+    pub(crate) velocities: PushedRecords<Velocity>,
+    pub(crate) targets: PushedRecords<Target>,
+    pub(crate) path_goals: PushedRecords<PathGoal>,
+    pub(crate) forces: PushedRecords<Force>,
+    pub(crate) extents: PushedRecords<Extent>,
+    pub(crate) drags: PushedRecords<Drag>,
+    pub(crate) civvies: PushedRecords<Civvy>,
+    pub(crate) baddies: PushedRecords<Baddie>,
+}
+
+impl EntityRecords {
+    /// Drops every tracker's undo log.
+    pub(crate) fn commit(&mut self) {
+        self.positions.commit();
+        self.velocities.commit();
+        self.targets.commit();
+        self.path_goals.commit();
+        self.forces.commit();
+        self.extents.commit();
+        self.drags.commit();
+        self.civvies.commit();
+        self.baddies.commit();
+    }
+
+    /// Hands every tracker's undo log to the step about to be launched.
+    pub(crate) fn launch(&mut self) {
+        self.positions.launch();
+        self.velocities.launch();
+        self.targets.launch();
+        self.path_goals.launch();
+        self.forces.launch();
+        self.extents.launch();
+        self.drags.launch();
+        self.civvies.launch();
+        self.baddies.launch();
+    }
+
+    /// Drops every tracker's launched undo log.
+    pub(crate) fn commit_launched(&mut self) {
+        self.positions.commit_launched();
+        self.velocities.commit_launched();
+        self.targets.commit_launched();
+        self.path_goals.commit_launched();
+        self.forces.commit_launched();
+        self.extents.commit_launched();
+        self.drags.commit_launched();
+        self.civvies.commit_launched();
+        self.baddies.commit_launched();
+    }
+
+    /// Restores every tracker's pre-frame records, collecting the touched
+    /// entities into `recheck`.
+    pub(crate) fn rollback(&mut self, recheck: &mut HashSet<Entity>) {
+        self.positions.rollback(recheck);
+        self.velocities.rollback(recheck);
+        self.targets.rollback(recheck);
+        self.path_goals.rollback(recheck);
+        self.forces.rollback(recheck);
+        self.extents.rollback(recheck);
+        self.drags.rollback(recheck);
+        self.civvies.rollback(recheck);
+        self.baddies.rollback(recheck);
+    }
+}
@@ -23,6 +23,8 @@ fn new_state_starts_empty(#[from(state)] state_result: Result<DbspState, dbsp::E
     assert!(state.health_snapshot.is_empty());
     assert!(state.expected_health_retractions.is_empty());
     assert!(state.pending_damage_retractions.is_empty());
+    assert!(state.pending_impulse_retractions.is_empty());
+    assert!(state.pending_platform_retractions.is_empty());
     assert_eq!(state.applied_health_duplicates(), 0);
 }
 
@@ -64,64 +66,108 @@ fn rollback_restores_health_snapshot_and_pending_damage(
         max: 100,
     };
     let pending = damage_event(3, 1);
-    state.health_snapshot.insert(3, snapshot);
+    let entity = Entity::from_bits(3);
+    state
+        .health_snapshot
+        .replace(state.circuit.health_state_in(), entity, Some(snapshot));
     state.pending_damage_retractions.push(pending);
 
-    // Simulate a cache pass: back up, drain/advance the live tracking.
+    // Simulate a cache pass: drain/advance the live tracking.
     state.begin_frame_rollback();
-    let previous_snapshots: Vec<_> = state.health_snapshot.values().copied().collect();
-    state.health_snapshot.clear();
     let previous_pending = std::mem::take(&mut state.pending_damage_retractions);
-    state.health_snapshot.insert(
-        3,
-        HealthState {
+    state.health_snapshot.replace(
+        state.circuit.health_state_in(),
+        entity,
+        Some(HealthState {
             entity: 3,
             current: 10,
             max: 100,
-        },
+        }),
     );
     state.pending_damage_retractions.push(damage_event(3, 2));
-    state.stash_frame_rollback(previous_snapshots, previous_pending);
+    state.stash_frame_rollback(previous_pending);
 
     state.rollback_frame_tracking();
 
-    assert_eq!(state.health_snapshot.get(&3), Some(&snapshot));
+    assert_eq!(state.health_snapshot.get(entity), Some(&snapshot));
     assert_eq!(state.pending_damage_retractions, vec![pending]);
+    assert!(state.entity_recheck.contains(&entity));
+}
+
+fn impulse(entity: i64, dvz: f64) -> Impulse {
+    Impulse {
+        entity,
+        dvx: 0.0.into(),
+        dvy: 0.0.into(),
+        dvz: dvz.into(),
+    }
+}
+
+#[rstest]
+#[case::rollback(false, impulse(3, 1.0))]
+#[case::commit(true, impulse(3, 2.0))]
+fn pending_impulses_follow_the_frame_outcome(
+    #[from(state)] state_result: Result<DbspState, dbsp::Error>,
+    #[case] step_succeeded: bool,
+    #[case] expected: Impulse,
+) {
+    let mut state = state_result.expect("failed to initialise DbspState for tests");
+    state.pending_impulse_retractions.push(impulse(3, 1.0));
+
+    state.begin_frame_rollback();
+    let previous = std::mem::take(&mut state.pending_impulse_retractions);
+    state.pending_impulse_retractions.push(impulse(3, 2.0));
+    state.stash_impulse_rollback(previous);
+
+    if step_succeeded {
+        state.commit_frame_tracking();
+    } else {
+        state.rollback_frame_tracking();
+    }
+
+    assert_eq!(state.pending_impulse_retractions, vec![expected]);
 }
 
 /// `stash_frame_rollback` keeps the first pre-frame values it is given and
 /// ignores later calls within the same frame, so a repeat call cannot latch
-/// already-advanced state. Removing either `is_none` guard makes the rollback
-/// below restore the second (wrong) values and fails this test.
+/// already-advanced state; the health snapshot likewise logs only its first
+/// pre-frame value. Removing either guard makes the rollback below restore the
+/// second (wrong) values and fails this test.
 #[rstest]
 fn stash_frame_rollback_keeps_first_values(
     #[from(state)] state_result: Result<DbspState, dbsp::Error>,
 ) {
     let mut state = state_result.expect("failed to initialise DbspState for tests");
-    let first_snapshot = HealthState {
+    let entity = Entity::from_bits(3);
+    let snapshot = |current| HealthState {
         entity: 3,
-        current: 50,
+        current,
         max: 100,
     };
     let first_pending = damage_event(3, 1);
-    // Distinct from the first pair, so a lost guard is observable.
-    let second_snapshot = HealthState {
-        entity: 3,
-        current: 10,
-        max: 100,
-    };
+    // Distinct from the first values, so a lost guard is observable.
     let second_pending = damage_event(3, 2);
+    state
+        .health_snapshot
+        .replace(state.circuit.health_state_in(), entity, Some(snapshot(50)));
+    state.commit_frame_tracking();
 
     state.begin_frame_rollback();
-    state.stash_frame_rollback(vec![first_snapshot], vec![first_pending]);
-    state.stash_frame_rollback(vec![second_snapshot], vec![second_pending]);
+    state
+        .health_snapshot
+        .replace(state.circuit.health_state_in(), entity, Some(snapshot(10)));
+    state.stash_frame_rollback(vec![first_pending]);
+    state
+        .health_snapshot
+        .replace(state.circuit.health_state_in(), entity, Some(snapshot(5)));
+    state.stash_frame_rollback(vec![second_pending]);
 
     state.rollback_frame_tracking();
 
     assert_eq!(
-        state.health_snapshot.get(&3),
-        Some(&first_snapshot),
-        "rollback must restore the health snapshot from the first stash"
+        state.health_snapshot.get(entity),
+        Some(&snapshot(50)),
+        "rollback must restore the health snapshot from before the frame"
     );
     assert_eq!(
         state.pending_damage_retractions,
@@ -130,6 +176,58 @@ fn stash_frame_rollback_keeps_first_values(
     );
 }
 
+/// A pipelined step reports only after the next cache pass has advanced the
+/// tracking again. Committing the launched step must keep that pass's log, so
+/// a later failure still restores the tracking the launched step produced,
+/// while a failure of the launched step undoes both passes.
+#[rstest]
+#[case::launched_step_succeeds(true)]
+#[case::launched_step_fails(false)]
+fn launched_step_resolves_around_the_next_pass(
+    #[from(state)] state_result: Result<DbspState, dbsp::Error>,
+    #[case] launched_succeeded: bool,
+) {
+    let mut state = state_result.expect("failed to initialise DbspState for tests");
+    let entity = Entity::from_bits(3);
+    let snapshot = |current| HealthState {
+        entity: 3,
+        current,
+        max: 100,
+    };
+
+    // The pass whose inputs the launched step consumes.
+    state.begin_frame_rollback();
+    let before_launch = std::mem::take(&mut state.pending_impulse_retractions);
+    state.pending_impulse_retractions.push(impulse(3, 1.0));
+    state.stash_impulse_rollback(before_launch);
+    state
+        .health_snapshot
+        .replace(state.circuit.health_state_in(), entity, Some(snapshot(50)));
+    state.launch_frame_tracking();
+
+    // The pass made while that step runs.
+    state.begin_frame_rollback();
+    let launched = std::mem::take(&mut state.pending_impulse_retractions);
+    state.pending_impulse_retractions.push(impulse(3, 2.0));
+    state.stash_impulse_rollback(launched);
+    state
+        .health_snapshot
+        .replace(state.circuit.health_state_in(), entity, Some(snapshot(10)));
+
+    if launched_succeeded {
+        state.commit_launched_tracking();
+        // The step consuming the later pass fails.
+        state.rollback_frame_tracking();
+        assert_eq!(state.health_snapshot.get(entity), Some(&snapshot(50)));
+        assert_eq!(state.pending_impulse_retractions, vec![impulse(3, 1.0)]);
+    } else {
+        state.rollback_frame_tracking();
+        assert!(state.health_snapshot.get(entity).is_none());
+        assert!(state.pending_impulse_retractions.is_empty());
+    }
+    assert!(state.entity_recheck.contains(&entity));
+}
+
 /// Bounded state-transition matrix over the rollback-relevant combinations:
 /// whether the entity had a prior `applied_unsequenced` entry, whether the
 /// undo was recorded once or twice (the second must be a no-op), and whether
@@ -168,7 +266,7 @@ fn applied_unsequenced_rollback_matrix(
         // already-advanced entry.
         state.record_unsequenced_undo(entity);
     }
-    state.stash_frame_rollback(Vec::new(), Vec::new());
+    state.stash_frame_rollback(Vec::new());
 
     if commit {
         state.commit_frame_tracking();
@@ -243,7 +341,7 @@ mod properties {
     #[derive(Clone, Debug, PartialEq, Eq)]
     struct Tracking {
         applied_unsequenced: HashMap<EntityId, (Tick, HashSet<DamageEvent>)>,
-        health_snapshot: HashMap<EntityId, HealthState>,
+        health_snapshot: HashMap<Entity, HealthState>,
         pending_damage_retractions: Vec<DamageEvent>,
     }
 
@@ -251,7 +349,7 @@ mod properties {
         fn of(state: &DbspState) -> Self {
             Self {
                 applied_unsequenced: state.applied_unsequenced.clone(),
-                health_snapshot: state.health_snapshot.clone(),
+                health_snapshot: state.health_snapshot.records().clone(),
                 pending_damage_retractions: state.pending_damage_retractions.clone(),
             }
         }
@@ -278,20 +376,20 @@ mod properties {
             }
             Action::Stash(value) => {
                 // The cache pass extracts the live values, then advances them.
-                let previous_snapshots: Vec<_> = state.health_snapshot.values().copied().collect();
                 let previous_pending = std::mem::take(&mut state.pending_damage_retractions);
-                state.health_snapshot.insert(
-                    EntityId::from(value),
-                    HealthState {
+                state.health_snapshot.replace(
+                    state.circuit.health_state_in(),
+                    Entity::from_bits(u64::from(value) + 1),
+                    Some(HealthState {
                         entity: EntityId::from(value),
                         current: u16::from(value),
                         max: 100,
-                    },
+                    }),
                 );
                 state
                     .pending_damage_retractions
                     .push(damage_event(EntityId::from(value), Tick::from(value)));
-                state.stash_frame_rollback(previous_snapshots, previous_pending);
+                state.stash_frame_rollback(previous_pending);
             }
             Action::Commit => state.commit_frame_tracking(),
             Action::Rollback => state.rollback_frame_tracking(),
@@ -12,11 +12,15 @@ mod macros;
 #[cfg(feature = "map")]
 #[cfg_attr(docsrs, doc(cfg(feature = "map")))]
 pub mod map;
+pub mod nav_grid;
 pub mod numeric;
+pub mod pathfinding;
 pub mod physics;
+pub mod physics_config;
 #[cfg(feature = "render")]
 #[cfg_attr(docsrs, doc(cfg(feature = "render")))]
 pub mod presentation;
+pub mod terrain;
 pub mod vector_math;
 pub mod world_handle;
 pub use constants::*;
@@ -34,15 +38,19 @@ pub mod __macro_deps {
 
 // Re-export commonly used items
 pub use actor::Actor;
-pub use components::{DdlogId, ForceComp, Health, Target, UnitType, VelocityComp};
+pub use components::{
+    DdlogId, DragComp, ExtentComp, ForceComp, Health, ImpulseComp, PathGoal, Target, UnitType,
+    VelocityComp,
+};
 pub use dbsp_circuit::{
-    DbspCircuit, FearLevel, FloorHeightAt, Force, HighestBlockAt, MovementDecision, NewPosition,
-    Position, PositionFloor, Target as DbspTarget,
+    DbspCircuit, FearLevel, FloorHeightAt, Force, HighestBlockAt, Impulse, MovementDecision,
+    NewPosition, PathGoal as DbspPathGoal, Position, PositionFloor, Target as DbspTarget,
 };
 pub use dbsp_circuit::{NewVelocity, Velocity};
 pub use dbsp_sync::{
-    apply_dbsp_outputs_system, cache_state_for_dbsp_system, init_dbsp_system, DamageInbox,
-    DbspPlugin, DbspSyncError, DbspSyncErrorContext,
+    apply_dbsp_outputs_system, cache_state_for_dbsp_system, init_dbsp_system,
+    step_dbsp_circuit_system, DamageInbox, DbspPlugin, DbspSchedule, DbspSet, DbspSyncError,
+    DbspSyncErrorContext,
 };
 pub use entity::{BadGuy, WorldEntity};
 /// Legacy alias for [`WorldEntity`]; prefer the new name.
@@ -56,12 +64,15 @@ pub use logging::init as init_logging;
 #[cfg_attr(docsrs, doc(cfg(feature = "map")))]
 pub use map::LilleMapPlugin;
 pub use physics::{applied_acceleration, apply_ground_friction};
+pub use physics_config::{PhysicsConfig, PhysicsConfigError};
 #[cfg(feature = "render")]
 #[cfg_attr(docsrs, doc(cfg(feature = "render")))]
 pub use presentation::{
-    camera_pan_system, compute_pan_direction, CameraController, CameraSettings, PanInput,
-    PresentationPlugin,
+    camera_pan_system, compute_pan_direction, interpolate_rendered_positions,
+    record_simulated_positions, restore_simulated_positions, CameraController, CameraSettings,
+    PanInput, PresentationPlugin, PreviousSimulatedPosition, SimulatedPosition,
 };
+pub use terrain::TerrainCommands;
 pub use vector_math::{vec_mag, vec_normalize};
 pub use world_handle::{init_world_handle_system, WorldHandle};
 
@@ -78,6 +89,7 @@ pub mod prelude {
     pub use crate::DbspCircuit;
     pub use crate::DbspPlugin;
     pub use crate::FloorHeightAt;
+    pub use crate::PhysicsConfig;
     pub use crate::PositionFloor;
     pub use crate::Velocity;
     pub use ordered_float::OrderedFloat;
@@ -31,7 +31,7 @@ fn main() -> Result<()> {
     init_logging(args.verbose)?;
     let mut app = App::new();
     app.add_plugins(DefaultPlugins.build().disable::<LogPlugin>());
-    app.add_plugins(DbspPlugin);
+    app.add_plugins(DbspPlugin::default());
     app.add_plugins(PresentationPlugin);
 
     #[cfg(feature = "map")]
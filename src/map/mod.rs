@@ -6,8 +6,8 @@
 //! - It registers `bevy_ecs_tiled::TiledPlugin` so `.tmx` assets can load.
 //! - It spawns a root entity with a `TiledMap` component, which triggers the
 //!   `bevy_ecs_tiled` spawn pipeline (layers, tilemaps, etc).
-//! - It attaches `Block` components to tiles marked `Collidable` so they
-//!   participate in DBSP physics.
+//! - It attaches `Block` components to tiles marked `Collidable` or `Door` so
+//!   they participate in DBSP physics.
 //!
 //! The DBSP circuit remains the sole source of truth for any inferred behaviour
 //! in the game world; this module translates authored data into typed
@@ -23,6 +23,8 @@ pub use translate::attach_collision_blocks;
 use bevy::prelude::*;
 use bevy_ecs_tiled::prelude::{TiledMapAsset, TiledPlugin};
 
+use crate::components::Door;
+
 /// Default Tiled map asset path for the “primary” isometric map.
 pub const PRIMARY_ISOMETRIC_MAP_PATH: &str = "maps/primary-isometric.tmx";
 
@@ -227,6 +229,7 @@ impl Plugin for LilleMapPlugin {
 
         app.insert_resource(LilleMapPluginInstalled);
         app.register_type::<Collidable>()
+            .register_type::<Door>()
             .register_type::<SlopeProperties>()
             .register_type::<PlayerSpawn>()
             .register_type::<SpawnPoint>()
@@ -10,7 +10,7 @@
 use bevy::prelude::*;
 use bevy_ecs_tiled::prelude::{MapCreated, TiledEvent};
 
-use crate::components::{DdlogId, Health, UnitType, VelocityComp};
+use crate::components::{DdlogId, ExtentComp, Health, UnitType, VelocityComp};
 use crate::map::{
     MapSpawned, Player, PlayerSpawn, PlayerSpawnConsumed, SpawnPoint, SpawnPointConsumed,
 };
@@ -46,6 +46,8 @@ pub struct PlayerBundle {
     pub health: Health,
     /// Linear velocity (initialised to zero).
     pub velocity: VelocityComp,
+    /// Collision extent for separation from other units.
+    pub extent: ExtentComp,
 }
 
 impl PlayerBundle {
@@ -85,6 +87,7 @@ impl PlayerBundle {
                 max: 100,
             },
             velocity: VelocityComp::default(),
+            extent: ExtentComp::default(),
         }
     }
 }
@@ -107,6 +110,8 @@ pub struct NpcBundle {
     pub health: Health,
     /// Linear velocity (initialised to zero).
     pub velocity: VelocityComp,
+    /// Collision extent for separation from other units.
+    pub extent: ExtentComp,
     /// Behavioural archetype.
     pub unit_type: UnitType,
 }
@@ -141,6 +146,7 @@ impl NpcBundle {
             name: Name::new(name),
             health,
             velocity: VelocityComp::default(),
+            extent: ExtentComp::default(),
             unit_type,
         }
     }
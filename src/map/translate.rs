@@ -2,7 +2,8 @@
 //!
 //! This module bridges Tiled map annotations with the DBSP physics circuit by
 //! attaching engine components (such as `Block` and `BlockSlope`) to entities
-//! that carry authoring markers (such as `Collidable` and `SlopeProperties`).
+//! that carry authoring markers (such as `Collidable`, `Door` and
+//! `SlopeProperties`).
 //!
 //! The translation happens once per map load, triggered by the
 //! `TiledEvent<MapCreated>` event. This ensures all tiles are spawned and their
@@ -12,16 +13,19 @@ use bevy::prelude::*;
 use bevy_ecs_tiled::prelude::{MapCreated, TilePos, TiledEvent};
 use ordered_float::OrderedFloat;
 
-use crate::components::{Block, BlockSlope};
+use crate::components::{Block, BlockSlope, Door};
 use crate::map::{Collidable, SlopeProperties};
 
-/// Attaches `Block` and `BlockSlope` components to entities marked `Collidable`.
+/// Attaches `Block` and `BlockSlope` components to entities marked `Collidable`
+/// or `Door`.
 ///
 /// This system listens for `TiledEvent<MapCreated>` and iterates over all
-/// entities with `Collidable` that lack a `Block` component. For each, it
-/// derives block coordinates from `TilePos` and inserts a new `Block`. If the
-/// entity also has `SlopeProperties` (authored in Tiled), a `BlockSlope`
-/// component is attached with the gradient data linked to the parent block.
+/// entities with `Collidable` or `Door` that lack a `Block` component. For
+/// each, it derives block coordinates from `TilePos` and inserts a new
+/// `Block`. If the entity also has `SlopeProperties` (authored in Tiled), a
+/// `BlockSlope` component is attached with the gradient data linked to the
+/// parent block. A door's block is only mirrored into the circuit while the
+/// door is closed.
 ///
 /// The system is idempotent: entities that already have `Block` are skipped,
 /// making it safe to run multiple times.
@@ -55,7 +59,7 @@ pub fn attach_collision_blocks(
     mut map_events: MessageReader<TiledEvent<MapCreated>>,
     collidable_tiles: Query<
         (Entity, &TilePos, Option<&SlopeProperties>),
-        (With<Collidable>, Without<Block>),
+        (Or<(With<Collidable>, With<Door>)>, Without<Block>),
     >,
     mut block_id_counter: Local<i64>,
 ) {
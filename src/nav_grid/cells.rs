@@ -0,0 +1,110 @@
+//! Chunked storage for the floor surfaces of the navigation grid.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::dbsp_circuit::FloorHeightAt;
+
+/// Side length, in cells, of the square chunks [`FloorCells`] shares between
+/// copies.
+const CHUNK_SIZE: i32 = 16;
+
+/// Surfaces of the cells of one chunk, lowest first.
+type Chunk = HashMap<(i32, i32), Vec<FloorHeightAt>>;
+
+/// Floor surfaces of each grid cell, keyed by `(x, y)`.
+///
+/// Cells are grouped into square chunks held behind [`Arc`]s. A clone shares
+/// every chunk, and a later change copies only the chunk holding the changed
+/// cell, so snapshots taken for path searches and flow fields stay cheap while
+/// the grid keeps changing.
+///
+/// # Examples
+/// ```
+/// use lille::dbsp_circuit::FloorHeightAt;
+/// use lille::nav_grid::FloorCells;
+///
+/// let floor = FloorHeightAt {
+///     x: 0,
+///     y: 0,
+///     z: 1.0.into(),
+///     grad_x: 0.0.into(),
+///     grad_y: 0.0.into(),
+///     base: 0.0.into(),
+///     ceiling: None,
+/// };
+/// let mut cells = FloorCells::new();
+/// cells.insert((0, 0), vec![floor]);
+/// let snapshot = cells.clone();
+/// cells.remove((0, 0));
+/// assert!(snapshot.contains_key((0, 0)));
+/// assert!(!cells.contains_key((0, 0)));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct FloorCells {
+    chunks: HashMap<(i32, i32), Arc<Chunk>>,
+}
+
+impl FloorCells {
+    /// Creates an empty set of cells.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Surfaces of `cell`, or `None` when it holds none.
+    #[must_use]
+    pub fn get(&self, cell: (i32, i32)) -> Option<&[FloorHeightAt]> {
+        self.chunks
+            .get(&chunk_of(cell))?
+            .get(&cell)
+            .map(Vec::as_slice)
+    }
+
+    /// Returns `true` when `cell` holds surfaces.
+    #[must_use]
+    pub fn contains_key(&self, cell: (i32, i32)) -> bool {
+        self.get(cell).is_some()
+    }
+
+    /// Sets the surfaces of `cell`, returning those it held before.
+    pub fn insert(
+        &mut self,
+        cell: (i32, i32),
+        surfaces: Vec<FloorHeightAt>,
+    ) -> Option<Vec<FloorHeightAt>> {
+        let chunk = self.chunks.entry(chunk_of(cell)).or_default();
+        Arc::make_mut(chunk).insert(cell, surfaces)
+    }
+
+    /// Removes `cell`, returning the surfaces it held.
+    pub fn remove(&mut self, cell: (i32, i32)) -> Option<Vec<FloorHeightAt>> {
+        let key = chunk_of(cell);
+        let chunk = self.chunks.get_mut(&key)?;
+        if !chunk.contains_key(&cell) {
+            return None;
+        }
+        let removed = Arc::make_mut(chunk).remove(&cell);
+        if chunk.is_empty() {
+            self.chunks.remove(&key);
+        }
+        removed
+    }
+
+    /// Cells holding surfaces, in no particular order.
+    pub fn keys(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.chunks.values().flat_map(|chunk| chunk.keys().copied())
+    }
+
+    /// Surfaces of `cell` for editing, inserting an empty list when it holds
+    /// none.
+    pub(crate) fn column_mut(&mut self, cell: (i32, i32)) -> &mut Vec<FloorHeightAt> {
+        let chunk = self.chunks.entry(chunk_of(cell)).or_default();
+        Arc::make_mut(chunk).entry(cell).or_default()
+    }
+}
+
+/// Chunk holding `cell`.
+const fn chunk_of((x, y): (i32, i32)) -> (i32, i32) {
+    (x.div_euclid(CHUNK_SIZE), y.div_euclid(CHUNK_SIZE))
+}
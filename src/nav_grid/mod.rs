@@ -0,0 +1,336 @@
+//! Navigation grid mirrored from the circuit's terrain outputs.
+//!
+//! The circuit is the authority on terrain: it derives the highest block of
+//! every column and the floor surfaces entities stand on, including slopes,
+//! bridges and moving platforms. [`NavGrid`] folds those outputs into one
+//! resource recording, for each grid cell, whether it can be walked on, how
+//! high its floor lies and what moving to each neighbour costs. Pathfinding,
+//! AI placement and debug overlays read it instead of re-deriving passability
+//! from the raw blocks.
+
+use std::collections::HashMap;
+use std::f64::consts::SQRT_2;
+use std::sync::Arc;
+
+use bevy::math::DVec2;
+use bevy::prelude::*;
+use ordered_float::OrderedFloat;
+
+use crate::dbsp_circuit::{FloorHeightAt, HighestBlockAt};
+use crate::BLOCK_CENTRE_OFFSET;
+
+mod cells;
+
+pub use cells::FloorCells;
+
+/// Neighbour offsets, orthogonal before diagonal.
+pub const NEIGHBOURS: [(i32, i32); 8] = [
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+/// A floor surface a walker can stand on: its cell and height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NavNode {
+    /// Grid cell holding the surface.
+    pub cell: (i32, i32),
+    /// Height of the surface at the cell centre.
+    pub z: OrderedFloat<f64>,
+}
+
+/// A single move between neighbouring surfaces.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NavEdge {
+    /// Surface the move lands on.
+    pub to: NavNode,
+    /// Cost of the move: `1.0` orthogonally and `√2` diagonally.
+    pub cost: f64,
+}
+
+/// Terrain the navigation systems share, kept in step with the circuit.
+///
+/// When the resource is present, the DBSP output systems fold every step's
+/// `floor_height_out` and `highest_block_out` deltas into it, so it always
+/// holds the terrain the circuit last computed and only the cells that
+/// changed are touched.
+/// [`PathfindingPlugin`](crate::pathfinding::PathfindingPlugin) initialises
+/// it; other consumers call `init_resource::<NavGrid>()` before the first step
+/// so no delta is missed. The floor surfaces are held in shared chunks, letting
+/// path searches run on worker threads against a snapshot while the grid
+/// keeps changing; see [`FloorCells`].
+///
+/// A cell is walkable when it has at least one floor surface. Its height is
+/// that of its top surface and its slope is the gradient the circuit derived
+/// from the [`BlockSlope`](crate::components::BlockSlope) of the block
+/// beneath. Moves between neighbours follow the rules the circuit applies to
+/// walking entities; see [`NavGrid::edges`].
+///
+/// Every floor change bumps [`NavGrid::generation`], and each cell with a
+/// floor remembers the generation that last touched it, so a planned path can
+/// tell whether the terrain along it has moved on.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct NavGrid {
+    cells: FloorCells,
+    highest: HashMap<(i32, i32), i32>,
+    revisions: HashMap<(i32, i32), u64>,
+    generation: u64,
+}
+
+impl NavGrid {
+    /// Applies a floor-surface delta, returning `true` when anything changed.
+    ///
+    /// Positive weights add the surface to its cell and negative weights
+    /// retract it; zero weights are ignored. All changes in one call share a
+    /// single new generation.
+    pub fn apply<I>(&mut self, delta: I) -> bool
+    where
+        I: IntoIterator<Item = (FloorHeightAt, i64)>,
+    {
+        let mut changes = delta
+            .into_iter()
+            .filter(|&(_, weight)| weight != 0)
+            .peekable();
+        if changes.peek().is_none() {
+            return false;
+        }
+        self.generation += 1;
+        let mut emptied = Vec::new();
+        for (surface, weight) in changes {
+            let key = (surface.x, surface.y);
+            self.revisions.insert(key, self.generation);
+            let column = self.cells.column_mut(key);
+            if weight > 0 {
+                column.push(surface);
+                column.sort_by_key(|s| s.base);
+            } else {
+                column.retain(|s| *s != surface);
+            }
+            if column.is_empty() {
+                emptied.push(key);
+            }
+        }
+        // Only cells this delta retracted from can have been left empty, and
+        // a later insertion in the same delta may have refilled them.
+        for key in emptied {
+            if self.cells.get(key).is_some_and(<[_]>::is_empty) {
+                self.cells.remove(key);
+                self.revisions.remove(&key);
+            }
+        }
+        true
+    }
+
+    /// Applies a highest-block delta.
+    ///
+    /// Retractions are applied before insertions, so a column whose top block
+    /// moved reports its new height whatever order the delta lists them in.
+    /// Walkability follows the floor surfaces alone, so these changes leave
+    /// the generation untouched.
+    pub fn apply_highest_blocks<I>(&mut self, delta: I)
+    where
+        I: IntoIterator<Item = (HighestBlockAt, i64)>,
+    {
+        let (added, retracted): (Vec<_>, Vec<_>) = delta
+            .into_iter()
+            .filter(|&(_, weight)| weight != 0)
+            .partition(|&(_, weight)| weight > 0);
+        for (block, _) in retracted {
+            let key = (block.x, block.y);
+            if self.highest.get(&key) == Some(&block.z) {
+                self.highest.remove(&key);
+            }
+        }
+        for (block, _) in added {
+            self.highest.insert((block.x, block.y), block.z);
+        }
+    }
+
+    /// Surfaces of the cell `(x, y)`, lowest first.
+    #[must_use]
+    pub fn surfaces(&self, x: i32, y: i32) -> &[FloorHeightAt] {
+        self.cells.get((x, y)).unwrap_or_default()
+    }
+
+    /// Surface of the cell `(x, y)` whose band holds the height `z`.
+    #[must_use]
+    pub fn surface_at(&self, x: i32, y: i32, z: f64) -> Option<&FloorHeightAt> {
+        supporting(&self.cells, (x, y), z)
+    }
+
+    /// Returns `true` when the cell `(x, y)` has a floor to stand on.
+    #[must_use]
+    pub fn is_walkable(&self, x: i32, y: i32) -> bool {
+        self.cells.contains_key((x, y))
+    }
+
+    /// Height of the top floor surface of the cell `(x, y)`.
+    #[must_use]
+    pub fn height(&self, x: i32, y: i32) -> Option<f64> {
+        self.top(x, y).map(|surface| surface.z.into_inner())
+    }
+
+    /// Gradient of the top floor surface of the cell `(x, y)`.
+    #[must_use]
+    pub fn slope(&self, x: i32, y: i32) -> Option<DVec2> {
+        self.top(x, y)
+            .map(|surface| DVec2::new(surface.grad_x.into_inner(), surface.grad_y.into_inner()))
+    }
+
+    /// Height of the highest block in the column `(x, y)`.
+    #[must_use]
+    pub fn highest_block(&self, x: i32, y: i32) -> Option<i32> {
+        self.highest.get(&(x, y)).copied()
+    }
+
+    /// Cells with a floor to stand on, in no particular order.
+    pub fn walkable_cells(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.cells.keys()
+    }
+
+    /// Moves a walker standing on `node` can make to a neighbouring cell.
+    ///
+    /// See [`edges`] for the rules applied.
+    pub fn edges(&self, node: NavNode, max_step: f64) -> impl Iterator<Item = NavEdge> + '_ {
+        edges(&self.cells, node, max_step)
+    }
+
+    /// Cost of moving from `node` onto the neighbouring cell `to`, or `None`
+    /// when the move is not allowed or `to` is not a neighbour.
+    #[must_use]
+    pub fn traversal_cost(&self, node: NavNode, to: (i32, i32), max_step: f64) -> Option<f64> {
+        let offset = (to.0 - node.cell.0, to.1 - node.cell.1);
+        NEIGHBOURS
+            .contains(&offset)
+            .then(|| step(&self.cells, node, offset, max_step))
+            .flatten()
+            .map(|edge| edge.cost)
+    }
+
+    /// Counter bumped by every change to the floor surfaces.
+    #[must_use]
+    pub const fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns `true` when the cell `(x, y)` changed after `generation`.
+    ///
+    /// Cells keep their revision only while they have a floor, so a cell
+    /// without one always counts as changed: anything planned across it
+    /// stood on a floor that has since gone.
+    #[must_use]
+    pub fn changed_since(&self, x: i32, y: i32, generation: u64) -> bool {
+        self.revisions
+            .get(&(x, y))
+            .is_none_or(|&revision| revision > generation)
+    }
+
+    /// Cells with a floor that changed after `generation`, in no particular
+    /// order.
+    ///
+    /// Cells that lost their last floor are not listed; check
+    /// [`NavGrid::is_walkable`] for those.
+    pub fn changes_since(&self, generation: u64) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.revisions
+            .iter()
+            .filter(move |&(_, &revision)| revision > generation)
+            .map(|(&cell, _)| cell)
+    }
+
+    /// Shares the current surfaces with a path search.
+    ///
+    /// The snapshot shares every chunk with the grid, so taking one copies no
+    /// surfaces, and later changes copy only the chunks they touch.
+    pub(crate) fn snapshot(&self) -> Arc<FloorCells> {
+        Arc::new(self.cells.clone())
+    }
+
+    fn top(&self, x: i32, y: i32) -> Option<&FloorHeightAt> {
+        self.cells.get((x, y))?.last()
+    }
+}
+
+/// Moves a walker standing on `node` can make to a neighbouring cell of
+/// `cells`.
+///
+/// A move lands on the surface whose band holds the walker's height,
+/// mirroring how the circuit resolves the floor under a moving entity, and is
+/// only allowed when that surface lies within `max_step` of the walker's. A
+/// diagonal move also needs both orthogonal moves open, so it never cuts the
+/// corner of a wall.
+pub fn edges(
+    cells: &FloorCells,
+    node: NavNode,
+    max_step: f64,
+) -> impl Iterator<Item = NavEdge> + '_ {
+    NEIGHBOURS
+        .into_iter()
+        .filter_map(move |offset| step(cells, node, offset, max_step))
+}
+
+/// The move from `node` by `offset`, if it is allowed.
+pub(crate) fn step(
+    cells: &FloorCells,
+    node: NavNode,
+    (dx, dy): (i32, i32),
+    max_step: f64,
+) -> Option<NavEdge> {
+    let edge = |to, cost| NavEdge { to, cost };
+    if dx == 0 || dy == 0 {
+        return step_onto(cells, node, (dx, dy), max_step).map(|to| edge(to, 1.0));
+    }
+    step_onto(cells, node, (dx, 0), max_step)?;
+    step_onto(cells, node, (0, dy), max_step)?;
+    step_onto(cells, node, (dx, dy), max_step).map(|to| edge(to, SQRT_2))
+}
+
+/// Surface reached by moving from `node` by `offset`, if it can be walked
+/// onto.
+fn step_onto(
+    cells: &FloorCells,
+    node: NavNode,
+    (dx, dy): (i32, i32),
+    max_step: f64,
+) -> Option<NavNode> {
+    let cell = (node.cell.0 + dx, node.cell.1 + dy);
+    let surface = supporting(cells, cell, node.z.into_inner())?;
+    ((surface.z.into_inner() - node.z.into_inner()).abs() <= max_step)
+        .then_some(NavNode { cell, z: surface.z })
+}
+
+/// Surface of `cell` whose band holds the height `z`.
+pub(crate) fn supporting(cells: &FloorCells, cell: (i32, i32), z: f64) -> Option<&FloorHeightAt> {
+    cells
+        .get(cell)?
+        .iter()
+        .find(|surface| surface.supports(OrderedFloat(z)))
+}
+
+/// Grid cell containing the continuous coordinates `(x, y)`.
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "world coordinates lie well within the i32 grid"
+)]
+#[must_use]
+pub const fn cell_of(point: Vec2) -> (i32, i32) {
+    (point.x.floor() as i32, point.y.floor() as i32)
+}
+
+/// Centre of the grid cell `(x, y)` in world coordinates.
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "cell centres are small enough to be exact in f32"
+)]
+#[must_use]
+pub fn cell_centre((x, y): (i32, i32)) -> Vec2 {
+    let centre = |coord: i32| (f64::from(coord) + BLOCK_CENTRE_OFFSET) as f32;
+    Vec2::new(centre(x), centre(y))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,243 @@
+//! Tests for the navigation grid and its synchronisation with the circuit.
+
+use std::f64::consts::SQRT_2;
+
+use bevy::math::DVec2;
+use bevy::prelude::*;
+use ordered_float::OrderedFloat;
+use rstest::rstest;
+
+use super::*;
+use crate::components::{Block, BlockSlope};
+use crate::DbspPlugin;
+
+/// Flat surface of the cell `(x, y)` resting on a run starting at `base`.
+fn floor(x: i32, y: i32, z: f64, base: f64) -> FloorHeightAt {
+    FloorHeightAt {
+        x,
+        y,
+        z: z.into(),
+        grad_x: 0.0.into(),
+        grad_y: 0.0.into(),
+        base: base.into(),
+        ceiling: None,
+    }
+}
+
+fn hb(x: i32, y: i32, z: i32) -> HighestBlockAt {
+    HighestBlockAt { x, y, z }
+}
+
+fn node(cell: (i32, i32), z: f64) -> NavNode {
+    NavNode {
+        cell,
+        z: OrderedFloat(z),
+    }
+}
+
+/// Grid with ground at height 1 over `0..=2` squared and the cell `(1, 1)`
+/// raised to height 3.
+fn raised_centre() -> NavGrid {
+    let mut grid = NavGrid::default();
+    let floors = (0..=2)
+        .flat_map(|x| (0..=2).map(move |y| (x, y)))
+        .map(|(x, y)| {
+            let z = if (x, y) == (1, 1) { 3.0 } else { 1.0 };
+            (floor(x, y, z, 0.0), 1)
+        });
+    grid.apply(floors);
+    grid
+}
+
+#[rstest]
+fn grid_tracks_added_and_retracted_surfaces() {
+    let mut grid = NavGrid::default();
+    assert!(grid.apply([(floor(0, 0, 1.0, 0.0), 1), (floor(1, 0, 2.0, 0.0), 1)]));
+    let after_insert = grid.generation();
+    assert_eq!(grid.surfaces(0, 0), [floor(0, 0, 1.0, 0.0)]);
+
+    assert!(grid.apply([(floor(1, 0, 2.0, 0.0), -1), (floor(1, 0, 1.0, 0.0), 1)]));
+    assert_eq!(grid.surfaces(1, 0), [floor(1, 0, 1.0, 0.0)]);
+    assert!(grid.changed_since(1, 0, after_insert));
+    assert!(!grid.changed_since(0, 0, after_insert));
+    assert_eq!(
+        grid.surface_at(1, 0, 1.0).map(|s| s.z.into_inner()),
+        Some(1.0)
+    );
+}
+
+#[rstest]
+fn empty_delta_leaves_the_generation() {
+    let mut grid = NavGrid::default();
+    assert!(!grid.apply([(floor(0, 0, 1.0, 0.0), 0)]));
+    assert_eq!(grid.generation(), 0);
+}
+
+#[rstest]
+fn retracting_the_last_surface_makes_a_cell_unwalkable() {
+    let mut grid = NavGrid::default();
+    grid.apply([(floor(0, 0, 1.0, 0.0), 1)]);
+    assert!(grid.is_walkable(0, 0));
+
+    grid.apply([(floor(0, 0, 1.0, 0.0), -1)]);
+    assert!(!grid.is_walkable(0, 0));
+    assert_eq!(grid.height(0, 0), None);
+    assert_eq!(grid.walkable_cells().count(), 0);
+    assert!(grid.revisions.is_empty(), "the cell's revision is pruned");
+    assert!(grid.changed_since(0, 0, grid.generation()));
+}
+
+#[rstest]
+fn refilling_a_cell_in_the_same_delta_keeps_it() {
+    let mut grid = NavGrid::default();
+    grid.apply([(floor(0, 0, 1.0, 0.0), 1)]);
+    grid.apply([(floor(0, 0, 1.0, 0.0), -1), (floor(0, 0, 2.0, 0.0), 1)]);
+    assert_eq!(grid.surfaces(0, 0), [floor(0, 0, 2.0, 0.0)]);
+    assert!(!grid.changed_since(0, 0, grid.generation()));
+}
+
+#[rstest]
+fn height_and_slope_follow_the_top_surface() {
+    let mut grid = NavGrid::default();
+    let bridge = FloorHeightAt {
+        grad_x: 0.5.into(),
+        ..floor(0, 0, 4.0, 3.0)
+    };
+    grid.apply([(floor(0, 0, 1.0, 0.0), 1), (bridge, 1)]);
+
+    assert_eq!(grid.height(0, 0), Some(4.0));
+    assert_eq!(grid.slope(0, 0), Some(DVec2::new(0.5, 0.0)));
+    assert_eq!(
+        grid.surface_at(0, 0, 1.0).map(|s| s.z.into_inner()),
+        Some(1.0)
+    );
+}
+
+#[rstest]
+#[case::replaced_in_order(vec![(hb(0, 0, 2), -1), (hb(0, 0, 5), 1)], Some(5))]
+#[case::replaced_out_of_order(vec![(hb(0, 0, 5), 1), (hb(0, 0, 2), -1)], Some(5))]
+#[case::retracted(vec![(hb(0, 0, 2), -1)], None)]
+#[case::stale_retraction(vec![(hb(0, 0, 7), -1)], Some(2))]
+fn highest_blocks_follow_the_delta(
+    #[case] delta: Vec<(HighestBlockAt, i64)>,
+    #[case] expected: Option<i32>,
+) {
+    let mut grid = NavGrid::default();
+    grid.apply_highest_blocks([(hb(0, 0, 2), 1)]);
+    grid.apply_highest_blocks(delta);
+    assert_eq!(grid.highest_block(0, 0), expected);
+    assert_eq!(grid.generation(), 0);
+}
+
+#[rstest]
+#[case::orthogonal((0, 0), (1, 0), Some(1.0))]
+#[case::two_cells_away((0, 0), (1, 2), None)]
+#[case::not_a_neighbour((0, 0), (2, 0), None)]
+#[case::same_cell((0, 0), (0, 0), None)]
+#[case::onto_a_step_too_high((0, 1), (1, 1), None)]
+#[case::around_the_corner((2, 0), (2, 1), Some(1.0))]
+fn traversal_cost_follows_the_step_rules(
+    #[case] from: (i32, i32),
+    #[case] to: (i32, i32),
+    #[case] expected: Option<f64>,
+) {
+    let grid = raised_centre();
+    assert_eq!(grid.traversal_cost(node(from, 1.0), to, 1.0), expected);
+}
+
+#[rstest]
+fn diagonals_never_cut_a_corner() {
+    let grid = raised_centre();
+    // (0, 0) -> (1, 1) climbs the raised cell; (0, 1) -> (1, 0) passes it.
+    assert_eq!(grid.traversal_cost(node((0, 0), 1.0), (1, 1), 1.0), None);
+    assert_eq!(grid.traversal_cost(node((0, 1), 1.0), (1, 0), 1.0), None);
+
+    let mut open = NavGrid::default();
+    open.apply([
+        (floor(0, 0, 1.0, 0.0), 1),
+        (floor(1, 0, 1.0, 0.0), 1),
+        (floor(0, 1, 1.0, 0.0), 1),
+        (floor(1, 1, 1.0, 0.0), 1),
+    ]);
+    assert_eq!(
+        open.traversal_cost(node((0, 0), 1.0), (1, 1), 1.0),
+        Some(SQRT_2)
+    );
+    assert_eq!(open.edges(node((0, 0), 1.0), 1.0).count(), 3);
+}
+
+#[rstest]
+fn cells_map_to_their_centres() {
+    assert_eq!(cell_of(Vec2::new(-0.2, 3.7)), (-1, 3));
+    assert_eq!(cell_centre((-1, 3)), Vec2::new(-0.5, 3.5));
+}
+
+#[rstest]
+fn grid_follows_the_circuit_terrain() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(DbspPlugin::per_frame())
+        .init_resource::<NavGrid>();
+    app.world_mut().spawn(Block {
+        id: 1,
+        x: 0,
+        y: 0,
+        z: 0,
+    });
+    let raised = app
+        .world_mut()
+        .spawn(Block {
+            id: 2,
+            x: 1,
+            y: 0,
+            z: 2,
+        })
+        .id();
+    app.world_mut().spawn((
+        Block {
+            id: 3,
+            x: 2,
+            y: 0,
+            z: 0,
+        },
+        BlockSlope {
+            block_id: 3,
+            grad_x: 1.0.into(),
+            grad_y: 0.0.into(),
+        },
+    ));
+    app.update();
+
+    let grid = app.world().resource::<NavGrid>();
+    assert_eq!(grid.height(0, 0), Some(1.0));
+    assert_eq!(grid.height(1, 0), Some(3.0));
+    assert_eq!(grid.highest_block(1, 0), Some(2));
+    assert_eq!(grid.height(2, 0), Some(1.5));
+    assert_eq!(grid.slope(2, 0), Some(DVec2::new(1.0, 0.0)));
+    let generation = grid.generation();
+
+    app.world_mut().entity_mut(raised).despawn();
+    app.update();
+
+    let updated = app.world().resource::<NavGrid>();
+    assert!(!updated.is_walkable(1, 0));
+    assert_eq!(updated.highest_block(1, 0), None);
+    assert!(updated.changed_since(1, 0, generation));
+    assert!(!updated.changed_since(0, 0, generation));
+    assert_eq!(updated.height(0, 0), Some(1.0));
+}
+
+#[rstest]
+fn snapshots_keep_their_surfaces_while_the_grid_changes() {
+    let mut grid = NavGrid::default();
+    grid.apply([(floor(-1, -1, 1.0, 0.0), 1), (floor(40, 0, 1.0, 0.0), 1)]);
+    let snapshot = grid.snapshot();
+
+    grid.apply([(floor(-1, -1, 1.0, 0.0), -1), (floor(40, 0, 3.0, 2.0), 1)]);
+
+    assert!(snapshot.contains_key((-1, -1)));
+    assert_eq!(snapshot.get((40, 0)).map(<[_]>::len), Some(1));
+    assert!(!grid.is_walkable(-1, -1));
+    assert_eq!(grid.surfaces(40, 0).len(), 2);
+    assert_eq!(grid.walkable_cells().collect::<Vec<_>>(), vec![(40, 0)]);
+}
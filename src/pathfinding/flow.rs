@@ -0,0 +1,319 @@
+//! Flow fields steering many units towards one destination.
+//!
+//! A* plans one path per unit, so two hundred units given the same order cost
+//! two hundred searches. A [`FlowField`] instead runs a single Dijkstra pass
+//! outwards from the destination and records, for every floor surface it
+//! reaches, the cost of the cheapest walk to the destination and the
+//! neighbouring surface that walk starts with. Every unit heading for the
+//! destination then reads its next waypoint straight off the field.
+//!
+//! [`FlowFields`] caches one field per destination cell and drops a field once
+//! the terrain it covers changes or no unit follows it any more. A field
+//! requested with different search limits is rebuilt.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use ordered_float::OrderedFloat;
+
+use crate::nav_grid::{
+    cell_centre, cell_of, step, supporting, FloorCells, NavGrid, NavNode, NEIGHBOURS,
+};
+
+use super::search::SearchLimits;
+
+/// Marks an entity that follows a shared [`FlowField`] towards its
+/// [`Target`](crate::components::Target) instead of planning its own A* path.
+///
+/// Suited to large groups given the same destination: every follower heading
+/// for the same cell reads from one cached field.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FlowFollower;
+
+/// Cheapest walk from a floor surface to the destination.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Flow {
+    /// Cost of the walk, counting one per orthogonal and `√2` per diagonal
+    /// move.
+    cost: f64,
+    /// Surface the walk moves onto first, or `None` in the destination cell.
+    next: Option<NavNode>,
+}
+
+/// Integration field leading every reachable floor surface to one destination
+/// cell.
+///
+/// Moves follow the same rules as [`find_path`](super::find_path): they land
+/// on the surface whose band holds the walker's height, rise or drop no more
+/// than the step height, and never cut the corner of a wall. Surfaces from
+/// which the destination cannot be reached, or which lie beyond
+/// [`SearchLimits::max_nodes`] settled surfaces, are absent from the field.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+///
+/// use bevy::math::{Vec2, Vec3};
+/// use lille::dbsp_circuit::FloorHeightAt;
+/// use lille::nav_grid::FloorCells;
+/// use lille::pathfinding::{FlowField, SearchLimits};
+///
+/// let mut cells = FloorCells::new();
+/// for x in 0..3 {
+///     let floor = FloorHeightAt {
+///         x,
+///         y: 0,
+///         z: 1.0.into(),
+///         grad_x: 0.0.into(),
+///         grad_y: 0.0.into(),
+///         base: 0.0.into(),
+///         ceiling: None,
+///     };
+///     cells.insert((x, 0), vec![floor]);
+/// }
+/// let limits = SearchLimits { max_step: 1.0, max_nodes: 64 };
+/// let field = FlowField::build(Arc::new(cells), (2, 0), limits);
+/// let target = Vec2::new(2.2, 0.7);
+/// assert_eq!(field.cost_at(Vec3::new(0.5, 0.5, 1.0)), Some(2.0));
+/// assert_eq!(
+///     field.waypoint(Vec3::new(0.5, 0.5, 1.0), target),
+///     Some(Vec2::new(1.5, 0.5))
+/// );
+/// assert_eq!(field.waypoint(Vec3::new(2.5, 0.5, 1.0), target), Some(target));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FlowField {
+    goal: (i32, i32),
+    cells: Arc<FloorCells>,
+    flows: HashMap<NavNode, Flow>,
+    covered: HashSet<(i32, i32)>,
+}
+
+impl FlowField {
+    /// Integrates the field for the destination cell `goal` over `cells`.
+    #[must_use]
+    pub fn build(cells: Arc<FloorCells>, goal: (i32, i32), limits: SearchLimits) -> Self {
+        let mut flows = HashMap::new();
+        let mut open = BinaryHeap::new();
+        for surface in cells.get(goal).into_iter().flatten() {
+            let node = NavNode {
+                cell: goal,
+                z: surface.z,
+            };
+            flows.insert(
+                node,
+                Flow {
+                    cost: 0.0,
+                    next: None,
+                },
+            );
+            open.push(Reverse((OrderedFloat(0.0), node)));
+        }
+        let mut settled = HashSet::new();
+        while let Some(Reverse((OrderedFloat(cost), node))) = open.pop() {
+            if !settled.insert(node) {
+                continue;
+            }
+            if settled.len() > limits.max_nodes {
+                break;
+            }
+            let improved = predecessors(&cells, node, limits.max_step)
+                .into_iter()
+                .map(|(from, move_cost)| (from, cost + move_cost))
+                .filter(|&(from, candidate)| relax(&mut flows, from, node, candidate));
+            open.extend(improved.map(|(from, candidate)| Reverse((OrderedFloat(candidate), from))));
+        }
+        // Surfaces discovered but never settled may not hold their cheapest
+        // cost yet, so only settled surfaces make it into the field.
+        flows.retain(|node, _| settled.contains(node));
+        // The destination always counts as covered, so a field built before
+        // its cell had a floor is rebuilt once the floor appears.
+        let covered = flows.keys().map(|node| node.cell).chain([goal]).collect();
+        Self {
+            goal,
+            cells,
+            flows,
+            covered,
+        }
+    }
+
+    /// Destination cell the field leads to.
+    #[must_use]
+    pub const fn goal(&self) -> (i32, i32) {
+        self.goal
+    }
+
+    /// Cost of the cheapest walk from `position` to the destination, or
+    /// `None` when the field does not reach it.
+    #[must_use]
+    pub fn cost_at(&self, position: Vec3) -> Option<f64> {
+        self.flow_at(position).map(|flow| flow.cost)
+    }
+
+    /// Point a unit at `position` should steer towards on its way to
+    /// `target`, which lies in the destination cell.
+    ///
+    /// This is the centre of the neighbouring cell the field points to, or
+    /// `target` itself once the unit reaches the destination cell. Returns
+    /// `None` when the unit stands on no surface the field reaches.
+    #[must_use]
+    pub fn waypoint(&self, position: Vec3, target: Vec2) -> Option<Vec2> {
+        let flow = self.flow_at(position)?;
+        Some(flow.next.map_or(target, |next| cell_centre(next.cell)))
+    }
+
+    fn flow_at(&self, position: Vec3) -> Option<&Flow> {
+        let cell = cell_of(position.truncate());
+        let surface = supporting(&self.cells, cell, f64::from(position.z))?;
+        self.flows.get(&NavNode { cell, z: surface.z })
+    }
+
+    /// Returns `true` when a change to `cell` could alter the field: the cell
+    /// or one of its neighbours lies within it.
+    fn touches(&self, (x, y): (i32, i32)) -> bool {
+        self.covered.contains(&(x, y))
+            || NEIGHBOURS
+                .iter()
+                .any(|(dx, dy)| self.covered.contains(&(x + dx, y + dy)))
+    }
+
+    /// Returns `true` when a cell the field leads through has lost its floor.
+    ///
+    /// [`NavGrid::changes_since`] does not list such cells, so they are
+    /// checked against the grid directly.
+    fn lost_floor(&self, grid: &NavGrid) -> bool {
+        self.flows
+            .keys()
+            .any(|node| !grid.is_walkable(node.cell.0, node.cell.1))
+    }
+}
+
+/// Records a walk from `from` onto `next` costing `cost` in total, returning
+/// `true` when it beats the cheapest walk known so far.
+fn relax(flows: &mut HashMap<NavNode, Flow>, from: NavNode, next: NavNode, cost: f64) -> bool {
+    if flows.get(&from).is_some_and(|flow| flow.cost <= cost) {
+        return false;
+    }
+    flows.insert(
+        from,
+        Flow {
+            cost,
+            next: Some(next),
+        },
+    );
+    true
+}
+
+/// Surfaces with a single move onto `node`, with the cost of that move.
+fn predecessors(cells: &FloorCells, node: NavNode, max_step: f64) -> Vec<(NavNode, f64)> {
+    NEIGHBOURS
+        .into_iter()
+        .flat_map(|(dx, dy)| {
+            let cell = (node.cell.0 - dx, node.cell.1 - dy);
+            cells
+                .get(cell)
+                .into_iter()
+                .flatten()
+                .map(move |surface| (NavNode { cell, z: surface.z }, (dx, dy)))
+        })
+        .filter_map(|(from, offset)| {
+            let edge = step(cells, from, offset, max_step)?;
+            (edge.to == node).then_some((from, edge.cost))
+        })
+        .collect()
+}
+
+/// A cached field, the limits it was built with and the navigation grid
+/// generation it was last checked against.
+#[derive(Debug, Clone)]
+struct CachedField {
+    field: FlowField,
+    limits: SearchLimits,
+    generation: u64,
+}
+
+impl CachedField {
+    fn build(goal: (i32, i32), grid: &NavGrid, limits: SearchLimits) -> Self {
+        Self {
+            field: FlowField::build(grid.snapshot(), goal, limits),
+            limits,
+            generation: grid.generation(),
+        }
+    }
+}
+
+/// Flow fields shared by every [`FlowFollower`], one per destination cell.
+///
+/// A field is built the first time a follower heads for its cell and reused
+/// until the terrain it covers changes, so a mass order costs a single field
+/// computation however many units receive it.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct FlowFields {
+    fields: HashMap<(i32, i32), CachedField>,
+}
+
+impl FlowFields {
+    /// Cached field leading to the cell `goal`, if one is held.
+    #[must_use]
+    pub fn get(&self, goal: (i32, i32)) -> Option<&FlowField> {
+        self.fields.get(&goal).map(|cached| &cached.field)
+    }
+
+    /// Number of cached fields.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Returns `true` when no field is cached.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Drops every field the terrain changed under since it was last checked.
+    ///
+    /// Fields the changes leave untouched adopt the grid's current surfaces,
+    /// so changes elsewhere, such as a moving platform, never force a rebuild.
+    pub fn invalidate(&mut self, grid: &NavGrid) {
+        self.fields.retain(|_, cached| {
+            if cached.generation == grid.generation() {
+                return true;
+            }
+            let stale = cached.field.lost_floor(grid)
+                || grid
+                    .changes_since(cached.generation)
+                    .any(|cell| cached.field.touches(cell));
+            if !stale {
+                cached.field.cells = grid.snapshot();
+                cached.generation = grid.generation();
+            }
+            !stale
+        });
+    }
+
+    /// Field leading to the cell `goal`, built against `grid` if none is
+    /// cached.
+    ///
+    /// A field cached under different `limits` is rebuilt, so changing the
+    /// step height or search budget takes effect on the next request.
+    pub fn field(&mut self, goal: (i32, i32), grid: &NavGrid, limits: SearchLimits) -> &FlowField {
+        &self
+            .fields
+            .entry(goal)
+            .and_modify(|cached| {
+                if cached.limits != limits {
+                    *cached = CachedField::build(goal, grid, limits);
+                }
+            })
+            .or_insert_with(|| CachedField::build(goal, grid, limits))
+            .field
+    }
+
+    /// Drops the fields whose destination is not in `goals`.
+    pub fn retain_goals(&mut self, goals: &HashSet<(i32, i32)>) {
+        self.fields.retain(|goal, _| goals.contains(goal));
+    }
+}
@@ -0,0 +1,27 @@
+//! A* pathfinding steering entities around terrain.
+//!
+//! The circuit moves entities in a straight line towards their goal, so on its
+//! own a unit walks into the first wall between it and its target. This module
+//! plans around them: worker threads search the shared
+//! [`NavGrid`](crate::nav_grid::NavGrid) for a path per entity, and the
+//! entity's next waypoint is fed back into the circuit as a
+//! [`PathGoal`](crate::components::PathGoal), which supersedes the raw target
+//! when movement is decided.
+//!
+//! Large groups sent to one destination use a shared [`FlowField`] instead:
+//! entities marked [`FlowFollower`] read their next waypoint from a single
+//! integration field per destination, cached in [`FlowFields`].
+
+mod flow;
+mod plugin;
+mod route;
+mod search;
+mod workers;
+
+pub use flow::{FlowField, FlowFields, FlowFollower};
+pub use plugin::{PathfindingPlugin, DEFAULT_MAX_FIELD_NODES, DEFAULT_MAX_SEARCH_NODES};
+pub use route::{Route, OFF_COURSE_DISTANCE};
+pub use search::{find_path, SearchLimits};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,273 @@
+//! Bevy plugin scheduling path searches ahead of each DBSP pass.
+
+use std::collections::HashSet;
+use std::num::NonZeroUsize;
+
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+
+use crate::components::{PathGoal, Target};
+use crate::dbsp_sync::DbspSet;
+use crate::nav_grid::{cell_of, NavGrid};
+use crate::PhysicsConfig;
+
+use super::flow::{FlowFields, FlowFollower};
+use super::route::Route;
+use super::search::SearchLimits;
+use super::workers::{PathJob, PathWorkers};
+
+/// Default cap on the nodes a single search expands.
+pub const DEFAULT_MAX_SEARCH_NODES: usize = 4096;
+
+/// Default cap on the surfaces a single flow field settles.
+pub const DEFAULT_MAX_FIELD_NODES: usize = 65_536;
+
+/// Search bounds shared by every request.
+#[derive(Resource, Debug, Clone, Copy)]
+struct SearchSettings {
+    max_nodes: usize,
+    max_field_nodes: usize,
+}
+
+/// Bevy plugin steering entities around obstacles on their way to a
+/// [`Target`].
+///
+/// The plugin initialises the shared [`NavGrid`] and searches it for a path
+/// for every entity with a target on a pool of worker threads. The next
+/// waypoint of each path is written to the entity's [`PathGoal`], which the
+/// circuit steers towards in place of the target.
+/// Until the first search reports, the entity heads straight for its target;
+/// when no path exists, it holds its position until the terrain changes where
+/// the search looked.
+///
+/// Entities marked [`FlowFollower`] skip the per-entity search and steer by a
+/// [`FlowField`](super::FlowField) shared with every follower bound for the
+/// same cell. The fields live in the [`FlowFields`] resource.
+///
+/// The systems run before [`DbspSet::Ingest`], so add the plugin alongside a
+/// [`DbspPlugin`](crate::DbspPlugin) using the same schedule, before the first
+/// pass runs.
+///
+/// # Examples
+/// ```no_run
+/// use bevy::prelude::*;
+/// use lille::pathfinding::PathfindingPlugin;
+/// use lille::DbspPlugin;
+///
+/// App::new()
+///     .add_plugins(MinimalPlugins)
+///     .add_plugins((DbspPlugin::default(), PathfindingPlugin::default()))
+///     .run();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathfindingPlugin {
+    /// Threads running path searches. Zero is treated as one.
+    pub workers: usize,
+    /// Nodes a search expands before it declares the target unreachable.
+    pub max_nodes: usize,
+    /// Surfaces a flow field settles before it stops growing.
+    pub max_field_nodes: usize,
+    /// Schedule running the pathfinding systems; match the
+    /// [`DbspPlugin::schedule`](crate::DbspPlugin::schedule).
+    pub schedule: InternedScheduleLabel,
+}
+
+impl Default for PathfindingPlugin {
+    fn default() -> Self {
+        Self {
+            workers: 1,
+            max_nodes: DEFAULT_MAX_SEARCH_NODES,
+            max_field_nodes: DEFAULT_MAX_FIELD_NODES,
+            schedule: FixedUpdate.intern(),
+        }
+    }
+}
+
+impl Plugin for PathfindingPlugin {
+    fn build(&self, app: &mut App) {
+        let workers = NonZeroUsize::new(self.workers).unwrap_or(NonZeroUsize::MIN);
+        app.init_resource::<NavGrid>()
+            .init_resource::<FlowFields>()
+            .init_resource::<PhysicsConfig>()
+            .insert_resource(PathWorkers::spawn(workers))
+            .insert_resource(SearchSettings {
+                max_nodes: self.max_nodes,
+                max_field_nodes: self.max_field_nodes,
+            })
+            .add_systems(
+                self.schedule,
+                (
+                    clear_routes_system,
+                    collect_paths_system,
+                    request_paths_system,
+                    follow_paths_system,
+                    follow_flow_fields_system,
+                )
+                    .chain()
+                    .before(DbspSet::Ingest),
+            );
+    }
+}
+
+/// Entities still steered by a route or waypoint but no longer targeting
+/// anything.
+type Untargeted = (Or<(With<Route>, With<PathGoal>)>, Without<Target>);
+
+/// Drops the route and waypoint of entities that lost their target.
+fn clear_routes_system(
+    mut commands: Commands,
+    mut removed: RemovedComponents<Target>,
+    routed: Query<(), Untargeted>,
+) {
+    for entity in removed.read() {
+        if routed.contains(entity) {
+            commands.entity(entity).try_remove::<(Route, PathGoal)>();
+        }
+    }
+}
+
+/// Hands finished searches to the routes that requested them.
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Bevy systems receive resources by value."
+)]
+fn collect_paths_system(
+    workers: Res<PathWorkers>,
+    mut routes: Query<(&Transform, &mut Route), Without<FlowFollower>>,
+) {
+    for result in workers.finished() {
+        let Ok((transform, mut route)) = routes.get_mut(result.entity) else {
+            continue;
+        };
+        route.settle(result.seq, result.outcome, transform.translation);
+    }
+}
+
+/// Queues a search for every route that is missing or out of date.
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Bevy systems receive resources by value."
+)]
+#[expect(
+    clippy::too_many_arguments,
+    reason = "System boundary requires multiple Bevy resources."
+)]
+fn request_paths_system(
+    mut commands: Commands,
+    workers: Res<PathWorkers>,
+    grid: Res<NavGrid>,
+    config: Res<PhysicsConfig>,
+    settings: Res<SearchSettings>,
+    mut seq: Local<u64>,
+    mut walkers: Query<(Entity, &Transform, &Target, Option<&mut Route>), Without<FlowFollower>>,
+) {
+    let limits = SearchLimits {
+        max_step: config.max_step_height.into_inner(),
+        max_nodes: settings.max_nodes,
+    };
+    for (entity, transform, target, mut existing) in &mut walkers {
+        let start = transform.translation;
+        let goal = target.0;
+        // Checking a route may only advance its bookkeeping, which is not a
+        // change worth reporting.
+        if existing
+            .as_mut()
+            .is_some_and(|r| !r.bypass_change_detection().needs_plan(goal, start, &grid))
+        {
+            continue;
+        }
+        *seq += 1;
+        if let Some(mut route) = existing {
+            route.request(goal, grid.generation(), *seq);
+        } else {
+            let mut route = Route::default();
+            route.request(goal, grid.generation(), *seq);
+            commands.entity(entity).insert(route);
+        }
+        workers.submit(PathJob {
+            entity,
+            seq: *seq,
+            cells: grid.snapshot(),
+            start,
+            goal,
+            limits,
+        });
+    }
+}
+
+/// Moves each entity's [`PathGoal`] along its route.
+///
+/// The component is only written when the waypoint changes, so the sync layer
+/// mirrors nothing for entities still walking towards the same waypoint.
+fn follow_paths_system(
+    mut commands: Commands,
+    mut walkers: Query<(Entity, &Transform, &mut Route, Option<&PathGoal>), Without<FlowFollower>>,
+) {
+    for (entity, transform, mut route, current) in &mut walkers {
+        let next = route
+            .bypass_change_detection()
+            .waypoint(transform.translation);
+        match (next, current) {
+            (Some(waypoint), Some(goal)) if goal.0 == waypoint => {}
+            (Some(waypoint), _) => {
+                commands.entity(entity).insert(PathGoal(waypoint));
+            }
+            (None, Some(_)) => {
+                commands.entity(entity).remove::<PathGoal>();
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+/// Steers every [`FlowFollower`] by the flow field of its target's cell.
+///
+/// Fields the terrain changed under are dropped first, missing fields are
+/// built, and fields no follower heads for any more are evicted afterwards.
+/// Followers the field does not reach keep their last waypoint, or hold where
+/// they stand if they have none. As with routes, the [`PathGoal`] is only
+/// written when the waypoint changes.
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Bevy systems receive resources by value."
+)]
+#[expect(
+    clippy::too_many_arguments,
+    reason = "System boundary requires multiple Bevy resources."
+)]
+fn follow_flow_fields_system(
+    mut commands: Commands,
+    grid: Res<NavGrid>,
+    config: Res<PhysicsConfig>,
+    settings: Res<SearchSettings>,
+    mut fields: ResMut<FlowFields>,
+    followers: Query<(Entity, &Transform, &Target, Option<&PathGoal>), With<FlowFollower>>,
+) {
+    let limits = SearchLimits {
+        max_step: config.max_step_height.into_inner(),
+        max_nodes: settings.max_field_nodes,
+    };
+    fields.invalidate(&grid);
+    let mut goals = HashSet::new();
+    for (entity, transform, target, current) in &followers {
+        let goal = cell_of(target.0);
+        goals.insert(goal);
+        let position = transform.translation;
+        let next = fields
+            .field(goal, &grid, limits)
+            .waypoint(position, target.0);
+        match (next, current) {
+            (Some(waypoint), Some(held)) if held.0 == waypoint => {}
+            (Some(waypoint), _) => {
+                commands.entity(entity).insert(PathGoal(waypoint));
+            }
+            (None, None) => {
+                commands
+                    .entity(entity)
+                    .insert(PathGoal(position.truncate()));
+            }
+            (None, Some(_)) => {}
+        }
+    }
+    fields.retain_goals(&goals);
+}
@@ -0,0 +1,174 @@
+//! Per-entity path state tracked between searches.
+
+use bevy::prelude::*;
+
+use crate::nav_grid::{cell_of, NavGrid};
+
+use super::search::CellBounds;
+
+/// Distance in blocks from the next waypoint beyond which an entity counts as
+/// knocked off its path and is replanned.
+///
+/// Consecutive waypoints are at most a diagonal apart, so an entity still
+/// walking the path never strays this far from the next one.
+pub const OFF_COURSE_DISTANCE: f32 = 2.5;
+
+/// Path an entity follows towards its [`Target`](crate::components::Target).
+///
+/// The pathfinding systems add this component to every entity with a target
+/// and keep it current: they request a new path when the target moves, when
+/// the terrain under the remaining waypoints changes, or when the entity
+/// strays from the path, and advance through the waypoints as the entity
+/// reaches their cells. An unreachable target is searched again only once the
+/// terrain changes within the cells the failed search looked at.
+#[derive(Component, Debug, Default, Clone, PartialEq)]
+pub struct Route {
+    goal: Vec2,
+    generation: u64,
+    pending: Option<u64>,
+    plan: Plan,
+}
+
+/// Outcome of the latest search for a [`Route`].
+#[derive(Debug, Default, Clone, PartialEq)]
+enum Plan {
+    /// No search has reported for the current goal yet.
+    #[default]
+    Awaiting,
+    /// Waypoints to walk, and the index of the next one.
+    Path { waypoints: Vec<Vec2>, next: usize },
+    /// The goal cannot be reached; the entity holds at `hold`. `searched`
+    /// covers the cells the failed search looked at.
+    Unreachable { hold: Vec2, searched: CellBounds },
+}
+
+impl Route {
+    /// Waypoints still to visit, ending at the target.
+    ///
+    /// Empty while the first search is running and when the target is
+    /// unreachable.
+    #[must_use]
+    pub fn waypoints(&self) -> &[Vec2] {
+        match &self.plan {
+            Plan::Path { waypoints, next } => waypoints.get(*next..).unwrap_or_default(),
+            Plan::Awaiting | Plan::Unreachable { .. } => &[],
+        }
+    }
+
+    /// Returns `true` when the last search found no way to the target.
+    #[must_use]
+    pub const fn is_unreachable(&self) -> bool {
+        matches!(self.plan, Plan::Unreachable { .. })
+    }
+
+    /// Returns `true` while a search for this route is running.
+    #[must_use]
+    pub const fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Returns `true` when the route should be searched again.
+    ///
+    /// An unreachable route that only saw changes outside its searched cells
+    /// catches up with the grid generation, so those changes are not scanned
+    /// again.
+    pub(crate) fn needs_plan(&mut self, target: Vec2, position: Vec3, grid: &NavGrid) -> bool {
+        if self.goal != target {
+            return true;
+        }
+        if self.pending.is_some() {
+            return false;
+        }
+        match &self.plan {
+            Plan::Awaiting => true,
+            Plan::Unreachable { searched, .. } => {
+                let area = *searched;
+                !area.contains(cell_of(position.truncate())) || self.searched_changes(area, grid)
+            }
+            Plan::Path { .. } => self.off_course(position) || self.crosses_changes(grid),
+        }
+    }
+
+    /// Records that search `seq` was requested for `target` against the grid
+    /// at `generation`.
+    ///
+    /// A new target drops the old plan, so the entity heads straight for the
+    /// target until the search reports.
+    pub(crate) fn request(&mut self, target: Vec2, generation: u64, seq: u64) {
+        if self.goal != target {
+            self.plan = Plan::Awaiting;
+        }
+        self.goal = target;
+        self.generation = generation;
+        self.pending = Some(seq);
+    }
+
+    /// Adopts the outcome of search `seq`, ignoring superseded searches.
+    pub(crate) fn settle(
+        &mut self,
+        seq: u64,
+        outcome: Result<Vec<Vec2>, CellBounds>,
+        position: Vec3,
+    ) {
+        if self.pending != Some(seq) {
+            return;
+        }
+        self.pending = None;
+        self.plan = outcome.map_or_else(
+            |searched| Plan::Unreachable {
+                hold: position.truncate(),
+                searched,
+            },
+            |path| Plan::Path {
+                waypoints: path,
+                next: 0,
+            },
+        );
+    }
+
+    /// Advances past waypoints whose cell the entity has reached and returns
+    /// the point to steer towards, if the route has one.
+    ///
+    /// The final waypoint is the target itself and is never passed.
+    pub(crate) fn waypoint(&mut self, position: Vec3) -> Option<Vec2> {
+        match &mut self.plan {
+            Plan::Awaiting => None,
+            Plan::Unreachable { hold, .. } => Some(*hold),
+            Plan::Path { waypoints, next } => {
+                let cell = cell_of(position.truncate());
+                let passable = waypoints.len().saturating_sub(1);
+                let reached = waypoints
+                    .get(*next..passable)
+                    .and_then(|ahead| ahead.iter().rposition(|w| cell_of(*w) == cell));
+                if let Some(offset) = reached {
+                    *next += offset + 1;
+                }
+                waypoints.get(*next).copied()
+            }
+        }
+    }
+
+    fn off_course(&self, position: Vec3) -> bool {
+        self.waypoints()
+            .first()
+            .is_some_and(|w| w.distance(position.truncate()) > OFF_COURSE_DISTANCE)
+    }
+
+    fn searched_changes(&mut self, searched: CellBounds, grid: &NavGrid) -> bool {
+        if grid.generation() <= self.generation {
+            return false;
+        }
+        let changed = grid
+            .changes_since(self.generation)
+            .any(|cell| searched.contains(cell));
+        self.generation = grid.generation();
+        changed
+    }
+
+    fn crosses_changes(&self, grid: &NavGrid) -> bool {
+        self.waypoints().iter().any(|w| {
+            let (x, y) = cell_of(*w);
+            grid.changed_since(x, y, self.generation)
+        })
+    }
+}
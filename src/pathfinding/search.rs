@@ -0,0 +1,172 @@
+//! A* search over the floor surfaces of the navigation grid.
+//!
+//! Nodes are floor surfaces rather than bare cells, so a path can pass under a
+//! bridge and later climb onto it. Moves between them follow
+//! [`nav_grid::edges`](crate::nav_grid::edges).
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::f64::consts::SQRT_2;
+
+use bevy::math::{Vec2, Vec3};
+use ordered_float::OrderedFloat;
+
+use crate::nav_grid::{cell_centre, cell_of, edges, supporting, FloorCells, NavNode};
+
+/// Rectangle of cells, inclusive of both corners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CellBounds {
+    min: (i32, i32),
+    max: (i32, i32),
+}
+
+impl CellBounds {
+    /// Bounds holding the single cell `cell`.
+    pub(crate) const fn cell(cell: (i32, i32)) -> Self {
+        Self {
+            min: cell,
+            max: cell,
+        }
+    }
+
+    /// Returns `true` when `cell` lies within the bounds.
+    pub(crate) const fn contains(&self, cell: (i32, i32)) -> bool {
+        self.min.0 <= cell.0 && cell.0 <= self.max.0 && self.min.1 <= cell.1 && cell.1 <= self.max.1
+    }
+
+    /// Grows the bounds to take in `cell`.
+    fn include(&mut self, cell: (i32, i32)) {
+        self.min = (self.min.0.min(cell.0), self.min.1.min(cell.1));
+        self.max = (self.max.0.max(cell.0), self.max.1.max(cell.1));
+    }
+
+    /// The bounds widened by one cell on every side.
+    const fn grown(self) -> Self {
+        Self {
+            min: (self.min.0 - 1, self.min.1 - 1),
+            max: (self.max.0 + 1, self.max.1 + 1),
+        }
+    }
+}
+
+/// Bounds applied to a single path search.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchLimits {
+    /// Largest rise or drop in blocks between neighbouring surfaces.
+    pub max_step: f64,
+    /// Number of nodes expanded before the goal is declared unreachable.
+    pub max_nodes: usize,
+}
+
+/// Finds a walkable path from `start` to `goal`.
+///
+/// Returns the waypoints to visit in order: the centre of every cell after the
+/// starting one, with the last replaced by `goal` itself. The list holds just
+/// `goal` when the walker already stands in the goal cell. Returns `None` when
+/// the walker has no floor beneath it, or when no path exists within
+/// [`SearchLimits::max_nodes`] expansions.
+///
+/// # Examples
+/// ```
+/// use bevy::math::{Vec2, Vec3};
+/// use lille::dbsp_circuit::FloorHeightAt;
+/// use lille::nav_grid::FloorCells;
+/// use lille::pathfinding::{find_path, SearchLimits};
+///
+/// let mut cells = FloorCells::new();
+/// for x in 0..3 {
+///     let floor = FloorHeightAt {
+///         x,
+///         y: 0,
+///         z: 1.0.into(),
+///         grad_x: 0.0.into(),
+///         grad_y: 0.0.into(),
+///         base: 0.0.into(),
+///         ceiling: None,
+///     };
+///     cells.insert((x, 0), vec![floor]);
+/// }
+/// let limits = SearchLimits { max_step: 1.0, max_nodes: 64 };
+/// let path = find_path(&cells, Vec3::new(0.5, 0.5, 1.0), Vec2::new(2.2, 0.7), limits);
+/// assert_eq!(path, Some(vec![Vec2::new(1.5, 0.5), Vec2::new(2.2, 0.7)]));
+/// ```
+#[must_use]
+pub fn find_path(
+    cells: &FloorCells,
+    start: Vec3,
+    goal: Vec2,
+    limits: SearchLimits,
+) -> Option<Vec<Vec2>> {
+    search_path(cells, start, goal, limits).ok()
+}
+
+/// Finds a walkable path like [`find_path`], reporting the cells a failed
+/// search looked at.
+///
+/// # Errors
+/// Returns the bounds of every cell whose surfaces the search read when no
+/// path exists. Terrain changes outside them cannot change the outcome of the
+/// same search.
+pub(crate) fn search_path(
+    cells: &FloorCells,
+    start: Vec3,
+    goal: Vec2,
+    limits: SearchLimits,
+) -> Result<Vec<Vec2>, CellBounds> {
+    let start_cell = cell_of(start.truncate());
+    let mut searched = CellBounds::cell(start_cell);
+    let floor = supporting(cells, start_cell, f64::from(start.z)).ok_or(searched)?;
+    let origin = NavNode {
+        cell: start_cell,
+        z: floor.z,
+    };
+    let goal_cell = cell_of(goal);
+    let mut costs = HashMap::from([(origin, 0.0)]);
+    let mut parents = HashMap::new();
+    let mut open = BinaryHeap::from([Reverse((OrderedFloat(0.0), origin))]);
+    let mut expanded = 0;
+    while let Some(Reverse((_, node))) = open.pop() {
+        if node.cell == goal_cell {
+            return Ok(waypoints(&parents, node, goal));
+        }
+        expanded += 1;
+        if expanded > limits.max_nodes {
+            break;
+        }
+        // Expanding a node reads the surfaces of its neighbours as well.
+        searched.include(node.cell);
+        let cost = costs.get(&node).copied().unwrap_or(f64::INFINITY);
+        for edge in edges(cells, node, limits.max_step) {
+            let (next, candidate) = (edge.to, cost + edge.cost);
+            if costs.get(&next).is_some_and(|&known| known <= candidate) {
+                continue;
+            }
+            costs.insert(next, candidate);
+            parents.insert(next, node);
+            let estimate = candidate + octile(next.cell, goal_cell);
+            open.push(Reverse((OrderedFloat(estimate), next)));
+        }
+    }
+    Err(searched.grown())
+}
+
+/// Octile distance between two cells, exact on an open grid.
+fn octile(from: (i32, i32), to: (i32, i32)) -> f64 {
+    let dx = f64::from(from.0.abs_diff(to.0));
+    let dy = f64::from(from.1.abs_diff(to.1));
+    dx.max(dy) + (SQRT_2 - 1.0) * dx.min(dy)
+}
+
+/// Waypoints leading to `end`, ending at the exact `goal`.
+fn waypoints(parents: &HashMap<NavNode, NavNode>, end: NavNode, goal: Vec2) -> Vec<Vec2> {
+    let mut route = vec![goal];
+    let mut node = end;
+    while let Some(&parent) = parents.get(&node) {
+        if parents.contains_key(&parent) {
+            route.push(cell_centre(parent.cell));
+        }
+        node = parent;
+    }
+    route.reverse();
+    route
+}
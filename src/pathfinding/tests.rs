@@ -0,0 +1,468 @@
+//! Tests for the path search, flow fields and the pathfinding plugin.
+
+use bevy::prelude::*;
+use rstest::rstest;
+
+use super::*;
+use crate::components::{Block, DdlogId, PathGoal, Target, VelocityComp};
+use crate::dbsp_circuit::FloorHeightAt;
+use crate::nav_grid::{cell_of, FloorCells, NavGrid};
+use crate::DbspPlugin;
+use search::CellBounds;
+
+/// Flat surface of the cell `(x, y)` resting on a run starting at `base`.
+fn floor(x: i32, y: i32, z: f64, base: f64) -> FloorHeightAt {
+    FloorHeightAt {
+        x,
+        y,
+        z: z.into(),
+        grad_x: 0.0.into(),
+        grad_y: 0.0.into(),
+        base: base.into(),
+        ceiling: None,
+    }
+}
+
+/// Grid of ground surfaces at height 1 over the given `x` and `y` ranges,
+/// with the listed cells raised to `wall_height`.
+fn terrain(
+    xs: std::ops::RangeInclusive<i32>,
+    ys: std::ops::RangeInclusive<i32>,
+    walls: &[(i32, i32)],
+    wall_height: f64,
+) -> FloorCells {
+    let mut cells = FloorCells::new();
+    for x in xs {
+        for y in ys.clone() {
+            let z = if walls.contains(&(x, y)) {
+                wall_height
+            } else {
+                1.0
+            };
+            cells.insert((x, y), vec![floor(x, y, z, 0.0)]);
+        }
+    }
+    cells
+}
+
+const LIMITS: SearchLimits = SearchLimits {
+    max_step: 1.0,
+    max_nodes: 1024,
+};
+
+/// Wall across `x == 3` leaving the rows `y == ±2` open.
+const WALL: [(i32, i32); 3] = [(3, -1), (3, 0), (3, 1)];
+
+#[rstest]
+fn path_routes_around_a_wall() {
+    let cells = terrain(0..=6, -2..=2, &WALL, 3.0);
+    let goal = Vec2::new(5.5, 0.5);
+    let path = find_path(&cells, Vec3::new(1.5, 0.5, 1.0), goal, LIMITS).expect("path exists");
+    assert_eq!(path.last(), Some(&goal));
+    let wall_cells = path
+        .iter()
+        .map(|w| cell_of(*w))
+        .filter(|c| WALL.contains(c));
+    assert_eq!(wall_cells.count(), 0);
+    assert!(path.iter().any(|w| cell_of(*w).1.abs() == 2));
+}
+
+#[rstest]
+#[case::step_up(2.0, true)]
+#[case::too_high(2.5, false)]
+fn path_respects_the_step_height(#[case] ledge: f64, #[case] reachable: bool) {
+    let mut cells = terrain(0..=1, 0..=0, &[], 1.0);
+    cells.insert((1, 0), vec![floor(1, 0, ledge, 0.0)]);
+    let path = find_path(
+        &cells,
+        Vec3::new(0.5, 0.5, 1.0),
+        Vec2::new(1.5, 0.5),
+        LIMITS,
+    );
+    assert_eq!(path.is_some(), reachable);
+}
+
+#[rstest]
+fn path_never_cuts_a_corner() {
+    let cells = terrain(0..=1, 0..=1, &[(1, 0)], 3.0);
+    let goal = Vec2::new(1.5, 1.5);
+    let path = find_path(&cells, Vec3::new(0.5, 0.5, 1.0), goal, LIMITS);
+    assert_eq!(path, Some(vec![Vec2::new(0.5, 1.5), goal]));
+}
+
+#[rstest]
+fn path_in_the_goal_cell_is_the_goal() {
+    let cells = terrain(0..=0, 0..=0, &[], 1.0);
+    let goal = Vec2::new(0.2, 0.8);
+    let path = find_path(&cells, Vec3::new(0.5, 0.5, 1.0), goal, LIMITS);
+    assert_eq!(path, Some(vec![goal]));
+}
+
+#[rstest]
+#[case::walled_in(&[(2, -1), (2, 0), (2, 1), (3, -1), (3, 1), (4, -1), (4, 0), (4, 1)], 1024)]
+#[case::node_limit(&[], 2)]
+fn unreachable_goal_yields_none(#[case] walls: &[(i32, i32)], #[case] max_nodes: usize) {
+    let cells = terrain(0..=6, -1..=1, walls, 3.0);
+    let limits = SearchLimits {
+        max_nodes,
+        ..LIMITS
+    };
+    let path = find_path(
+        &cells,
+        Vec3::new(0.5, 0.5, 1.0),
+        Vec2::new(3.5, 0.5),
+        limits,
+    );
+    assert_eq!(path, None);
+}
+
+#[rstest]
+fn route_ignores_superseded_searches() {
+    let grid = NavGrid::default();
+    let mut route = Route::default();
+    route.request(Vec2::new(5.5, 0.5), grid.generation(), 1);
+    route.request(Vec2::new(6.5, 0.5), grid.generation(), 2);
+    route.settle(1, Ok(vec![Vec2::new(5.5, 0.5)]), Vec3::ZERO);
+    assert!(route.is_pending());
+
+    let waypoints = vec![Vec2::new(1.5, 0.5), Vec2::new(6.5, 0.5)];
+    route.settle(2, Ok(waypoints.clone()), Vec3::ZERO);
+    assert!(!route.is_pending());
+    assert_eq!(route.waypoints(), waypoints.as_slice());
+}
+
+/// Navigation grid with ground at height 1 along `y == 0` over `xs`, so the
+/// cells a route plans across have a floor.
+fn ground_row(xs: std::ops::RangeInclusive<i32>) -> NavGrid {
+    let mut grid = NavGrid::default();
+    grid.apply(xs.map(|x| (floor(x, 0, 1.0, 0.0), 1)));
+    grid
+}
+
+#[rstest]
+fn route_advances_through_reached_cells() {
+    let grid = ground_row(0..=3);
+    let target = Vec2::new(3.2, 0.5);
+    let mut route = Route::default();
+    route.request(target, grid.generation(), 1);
+    let waypoints = vec![Vec2::new(1.5, 0.5), Vec2::new(2.5, 0.5), target];
+    route.settle(1, Ok(waypoints), Vec3::new(0.5, 0.5, 1.0));
+
+    assert_eq!(
+        route.waypoint(Vec3::new(0.5, 0.5, 1.0)),
+        Some(Vec2::new(1.5, 0.5))
+    );
+    assert_eq!(route.waypoint(Vec3::new(2.1, 0.5, 1.0)), Some(target));
+    assert_eq!(route.waypoint(Vec3::new(3.4, 0.5, 1.0)), Some(target));
+    assert!(!route.needs_plan(target, Vec3::new(3.4, 0.5, 1.0), &grid));
+}
+
+#[rstest]
+#[case::new_target(Vec2::new(9.5, 0.5), Vec3::new(0.5, 0.5, 1.0), true)]
+#[case::knocked_away(Vec2::new(3.5, 0.5), Vec3::new(0.5, 5.5, 1.0), true)]
+#[case::on_course(Vec2::new(3.5, 0.5), Vec3::new(0.9, 0.5, 1.0), false)]
+fn route_replans_when_out_of_date(
+    #[case] target: Vec2,
+    #[case] position: Vec3,
+    #[case] expected: bool,
+) {
+    let grid = ground_row(0..=3);
+    let mut route = Route::default();
+    route.request(Vec2::new(3.5, 0.5), grid.generation(), 1);
+    let waypoints = vec![
+        Vec2::new(1.5, 0.5),
+        Vec2::new(2.5, 0.5),
+        Vec2::new(3.5, 0.5),
+    ];
+    route.settle(1, Ok(waypoints), Vec3::new(0.5, 0.5, 1.0));
+    assert_eq!(route.needs_plan(target, position, &grid), expected);
+}
+
+#[rstest]
+fn route_replans_when_its_cells_change() {
+    let mut grid = NavGrid::default();
+    grid.apply([(floor(1, 0, 1.0, 0.0), 1), (floor(5, 5, 1.0, 0.0), 1)]);
+    let target = Vec2::new(1.5, 0.5);
+    let mut route = Route::default();
+    route.request(target, grid.generation(), 1);
+    route.settle(1, Ok(vec![target]), Vec3::new(0.5, 0.5, 1.0));
+
+    grid.apply([(floor(5, 5, 1.0, 0.0), -1)]);
+    assert!(!route.needs_plan(target, Vec3::new(0.5, 0.5, 1.0), &grid));
+    grid.apply([(floor(1, 0, 1.0, 0.0), -1), (floor(1, 0, 3.0, 0.0), 1)]);
+    assert!(route.needs_plan(target, Vec3::new(0.5, 0.5, 1.0), &grid));
+}
+
+#[rstest]
+#[case::outside_the_search(floor(9, 9, 1.0, 0.0), Vec3::new(0.5, 0.5, 1.0), false)]
+#[case::inside_the_search(floor(1, 1, 1.0, 0.0), Vec3::new(0.5, 0.5, 1.0), true)]
+#[case::walker_left_the_search(floor(9, 9, 1.0, 0.0), Vec3::new(6.5, 0.5, 1.0), true)]
+fn unreachable_route_replans_for_changes_it_searched(
+    #[case] change: FloorHeightAt,
+    #[case] position: Vec3,
+    #[case] expected: bool,
+) {
+    let mut grid = NavGrid::default();
+    grid.apply([(floor(0, 0, 1.0, 0.0), 1)]);
+    let target = Vec2::new(3.5, 0.5);
+    let start = Vec3::new(0.5, 0.5, 1.0);
+    let searched = search::search_path(&grid.snapshot(), start, target, LIMITS)
+        .expect_err("the lone cell has no way out");
+    assert!(searched.contains((1, 1)) && !searched.contains((2, 1)));
+    let mut route = Route::default();
+    route.request(target, grid.generation(), 1);
+    route.settle(1, Err(searched), start);
+
+    grid.apply([(change, 1)]);
+    assert_eq!(route.needs_plan(target, position, &grid), expected);
+}
+
+#[rstest]
+fn unreachable_route_holds_position() {
+    let grid = NavGrid::default();
+    let mut route = Route::default();
+    route.request(Vec2::new(3.5, 0.5), grid.generation(), 1);
+    route.settle(1, Err(CellBounds::cell((0, 0))), Vec3::new(0.7, 0.2, 1.0));
+    assert!(route.is_unreachable());
+    assert_eq!(
+        route.waypoint(Vec3::new(0.9, 0.2, 1.0)),
+        Some(Vec2::new(0.7, 0.2))
+    );
+    assert!(!route.needs_plan(Vec2::new(3.5, 0.5), Vec3::ZERO, &grid));
+}
+
+/// Spawns ground over `x` in `0..=6` and `y` in `-2..=2`, with a wall two
+/// blocks high across `x == 3`.
+fn spawn_walled_terrain(world: &mut World) {
+    let mut id = 100;
+    let mut spawn_block = |x, y, z| {
+        id += 1;
+        world.spawn(Block { id, x, y, z });
+    };
+    for x in 0..=6 {
+        for y in -2..=2 {
+            spawn_block(x, y, 0);
+        }
+    }
+    for (x, y) in WALL {
+        spawn_block(x, y, 1);
+        spawn_block(x, y, 2);
+    }
+}
+
+#[rstest]
+#[case::with_pathfinding(true)]
+#[case::straight_line(false)]
+fn unit_walks_around_a_wall(#[case] pathfinding: bool) {
+    let mut app = App::new();
+    let dbsp = DbspPlugin::per_frame();
+    app.add_plugins(MinimalPlugins).add_plugins(dbsp);
+    if pathfinding {
+        app.add_plugins(PathfindingPlugin {
+            schedule: dbsp.schedule,
+            ..PathfindingPlugin::default()
+        });
+    }
+    spawn_walled_terrain(app.world_mut());
+    let unit = app
+        .world_mut()
+        .spawn((
+            DdlogId(1),
+            Transform::from_xyz(1.5, 0.5, 1.0),
+            VelocityComp::default(),
+            Target(Vec2::new(5.5, 0.5)),
+        ))
+        .id();
+
+    let mut arrived = false;
+    for _ in 0..60 {
+        app.update();
+        let position = app
+            .world()
+            .entity(unit)
+            .get::<Transform>()
+            .map(|t| t.translation);
+        if position.is_some_and(|p| cell_of(p.truncate()) == (5, 0)) {
+            arrived = true;
+            break;
+        }
+    }
+    assert_eq!(arrived, pathfinding);
+    assert_eq!(app.world().entity(unit).contains::<PathGoal>(), pathfinding);
+}
+
+#[rstest]
+fn losing_the_target_clears_the_route() {
+    let mut app = App::new();
+    let dbsp = DbspPlugin::per_frame();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(dbsp)
+        .add_plugins(PathfindingPlugin {
+            schedule: dbsp.schedule,
+            ..PathfindingPlugin::default()
+        });
+    spawn_walled_terrain(app.world_mut());
+    let unit = app
+        .world_mut()
+        .spawn((
+            DdlogId(1),
+            Transform::from_xyz(1.5, 0.5, 1.0),
+            Target(Vec2::new(5.5, 0.5)),
+        ))
+        .id();
+    app.update();
+    assert!(app.world().entity(unit).contains::<Route>());
+
+    app.world_mut().entity_mut(unit).remove::<Target>();
+    app.update();
+    let entity = app.world().entity(unit);
+    assert!(!entity.contains::<Route>());
+    assert!(!entity.contains::<PathGoal>());
+}
+
+/// Follows `field` from `start` to its destination, returning the cells
+/// visited on the way.
+fn walk_field(field: &FlowField, start: Vec3, target: Vec2) -> Vec<(i32, i32)> {
+    let mut position = start;
+    let mut visited = vec![cell_of(position.truncate())];
+    while cell_of(position.truncate()) != field.goal() {
+        let waypoint = field
+            .waypoint(position, target)
+            .expect("field reaches the walker");
+        position = waypoint.extend(position.z);
+        visited.push(cell_of(waypoint));
+        assert!(visited.len() < 64, "field walk does not terminate");
+    }
+    visited
+}
+
+#[rstest]
+fn flow_field_routes_around_a_wall() {
+    let cells = terrain(0..=6, -2..=2, &WALL, 3.0);
+    let target = Vec2::new(5.5, 0.5);
+    let start = Vec3::new(1.5, 0.5, 1.0);
+    let field = FlowField::build(std::sync::Arc::new(cells.clone()), (5, 0), LIMITS);
+
+    let visited = walk_field(&field, start, target);
+    assert!(visited.iter().all(|c| !WALL.contains(c)));
+    assert!(visited.iter().any(|c| c.1.abs() == 2));
+
+    let path = find_path(&cells, start, target, LIMITS).expect("path exists");
+    assert_eq!(
+        visited.len(),
+        path.len() + 1,
+        "the field walk is as short as A*"
+    );
+    assert!(field.cost_at(Vec3::new(3.5, 0.5, 3.0)).is_none());
+}
+
+#[rstest]
+#[case::step_up(2.0, true)]
+#[case::too_high(2.5, false)]
+fn flow_field_respects_the_step_height(#[case] ledge: f64, #[case] reachable: bool) {
+    let mut cells = terrain(0..=1, 0..=0, &[], 1.0);
+    cells.insert((1, 0), vec![floor(1, 0, ledge, 0.0)]);
+    let field = FlowField::build(std::sync::Arc::new(cells), (1, 0), LIMITS);
+    assert_eq!(field.cost_at(Vec3::new(0.5, 0.5, 1.0)).is_some(), reachable);
+}
+
+#[rstest]
+fn flow_fields_are_dropped_only_when_their_terrain_changes() {
+    let mut grid = NavGrid::default();
+    grid.apply(
+        (0..=2)
+            .map(|x| (floor(x, 0, 1.0, 0.0), 1))
+            .chain([(floor(9, 9, 1.0, 0.0), 1)]),
+    );
+    let mut fields = FlowFields::default();
+    let _ = fields.field((2, 0), &grid, LIMITS);
+
+    grid.apply([(floor(9, 9, 1.0, 0.0), -1), (floor(9, 9, 2.0, 0.0), 1)]);
+    fields.invalidate(&grid);
+    assert!(
+        fields.get((2, 0)).is_some(),
+        "a distant change keeps the field"
+    );
+
+    grid.apply([(floor(3, 1, 1.0, 0.0), 1)]);
+    fields.invalidate(&grid);
+    assert!(
+        fields.get((2, 0)).is_none(),
+        "an adjacent change drops the field"
+    );
+
+    let _ = fields.field((2, 0), &grid, LIMITS);
+    grid.apply([(floor(0, 0, 1.0, 0.0), -1)]);
+    fields.invalidate(&grid);
+    assert!(
+        fields.get((2, 0)).is_none(),
+        "losing a floor the field covers drops the field"
+    );
+}
+
+#[rstest]
+fn flow_fields_are_rebuilt_when_the_limits_change() {
+    let mut grid = NavGrid::default();
+    grid.apply([(floor(0, 0, 1.0, 0.0), 1), (floor(1, 0, 2.0, 0.0), 1)]);
+    let mut fields = FlowFields::default();
+    let start = Vec3::new(0.5, 0.5, 1.0);
+
+    let low = SearchLimits {
+        max_step: 0.5,
+        ..LIMITS
+    };
+    assert!(fields.field((1, 0), &grid, low).cost_at(start).is_none());
+    assert!(
+        fields.field((1, 0), &grid, LIMITS).cost_at(start).is_some(),
+        "a higher step height rebuilds the field"
+    );
+}
+
+#[rstest]
+fn a_group_order_builds_one_field() {
+    let mut app = App::new();
+    let dbsp = DbspPlugin::per_frame();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(dbsp)
+        .add_plugins(PathfindingPlugin {
+            schedule: dbsp.schedule,
+            ..PathfindingPlugin::default()
+        });
+    spawn_walled_terrain(app.world_mut());
+    let units: Vec<Entity> = (-2_i16..=2)
+        .map(|y| {
+            app.world_mut()
+                .spawn((
+                    DdlogId(i64::from(y) + 10),
+                    Transform::from_xyz(0.5, f32::from(y) + 0.5, 1.0),
+                    VelocityComp::default(),
+                    Target(Vec2::new(5.5, 0.5)),
+                    FlowFollower,
+                ))
+                .id()
+        })
+        .collect();
+
+    let mut arrived = std::collections::HashSet::new();
+    for _ in 0..60 {
+        app.update();
+        assert!(app.world().resource::<FlowFields>().len() <= 1);
+        for &unit in &units {
+            let position = app.world().entity(unit).get::<Transform>();
+            if position.is_some_and(|t| cell_of(t.translation.truncate()) == (5, 0)) {
+                arrived.insert(unit);
+            }
+        }
+        if arrived.len() == units.len() {
+            break;
+        }
+    }
+    assert_eq!(
+        arrived.len(),
+        units.len(),
+        "every follower reaches the target"
+    );
+    assert!(units
+        .iter()
+        .all(|&unit| !app.world().entity(unit).contains::<Route>()));
+}
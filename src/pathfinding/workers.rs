@@ -0,0 +1,132 @@
+//! Worker threads running path searches off the main schedule.
+//!
+//! Requests go out over one shared channel, which every worker pulls from, and
+//! results come back over another, so a slow search never stalls a frame.
+
+use std::num::NonZeroUsize;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+
+use bevy::prelude::*;
+use log::error;
+
+use crate::nav_grid::FloorCells;
+
+use super::search::{search_path, CellBounds, SearchLimits};
+
+/// A path search queued for the workers.
+pub(crate) struct PathJob {
+    /// Entity the path is for.
+    pub(crate) entity: Entity,
+    /// Sequence number telling this request apart from later ones.
+    pub(crate) seq: u64,
+    /// Floor surfaces to search.
+    pub(crate) cells: Arc<FloorCells>,
+    /// Where the walker stands.
+    pub(crate) start: Vec3,
+    /// Where the walker is headed.
+    pub(crate) goal: Vec2,
+    /// Bounds of the search.
+    pub(crate) limits: SearchLimits,
+}
+
+/// The outcome of a [`PathJob`].
+pub(crate) struct PathResult {
+    /// Entity the path is for.
+    pub(crate) entity: Entity,
+    /// Sequence number of the request.
+    pub(crate) seq: u64,
+    /// Waypoints to follow, or the cells searched when the goal is
+    /// unreachable.
+    pub(crate) outcome: Result<Vec<Vec2>, CellBounds>,
+}
+
+/// Pool of threads running [`PathJob`]s.
+#[derive(Resource)]
+pub(crate) struct PathWorkers {
+    requests: Option<Sender<PathJob>>,
+    results: Mutex<Receiver<PathResult>>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl PathWorkers {
+    /// Spawns `workers` search threads.
+    ///
+    /// Threads that fail to spawn are logged and skipped.
+    pub(crate) fn spawn(workers: NonZeroUsize) -> Self {
+        let (requests, queued) = mpsc::channel::<PathJob>();
+        let (reply, results) = mpsc::channel();
+        let pending = Arc::new(Mutex::new(queued));
+        let threads = (0..workers.get())
+            .filter_map(|index| {
+                let queue = Arc::clone(&pending);
+                let sender = reply.clone();
+                thread::Builder::new()
+                    .name(format!("lille-path-{index}"))
+                    .spawn(move || serve(&queue, &sender))
+                    .inspect_err(|err| error!("failed to spawn path worker {index}: {err}"))
+                    .ok()
+            })
+            .collect();
+        Self {
+            requests: Some(requests),
+            results: Mutex::new(results),
+            threads,
+        }
+    }
+
+    /// Queues `job` for the next free worker.
+    pub(crate) fn submit(&self, job: PathJob) {
+        let Some(requests) = &self.requests else {
+            return;
+        };
+        if let Err(mpsc::SendError(dropped)) = requests.send(job) {
+            error!(
+                "path workers stopped; dropping request for {:?}",
+                dropped.entity
+            );
+        }
+    }
+
+    /// Results of every search finished since the last call.
+    pub(crate) fn finished(&self) -> Vec<PathResult> {
+        self.results
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .try_iter()
+            .collect()
+    }
+}
+
+impl Drop for PathWorkers {
+    fn drop(&mut self) {
+        // Closing the request channel ends every worker's loop.
+        self.requests.take();
+        for thread in self.threads.drain(..) {
+            if thread.join().is_err() {
+                error!("path worker panicked");
+            }
+        }
+    }
+}
+
+/// Runs jobs from `queue` until it closes or the results are dropped.
+fn serve(queue: &Mutex<Receiver<PathJob>>, reply: &Sender<PathResult>) {
+    loop {
+        // Hold the lock only while taking a job, so searches run in parallel.
+        let received = queue.lock().unwrap_or_else(PoisonError::into_inner).recv();
+        let Ok(job) = received else {
+            return;
+        };
+        let outcome = search_path(&job.cells, job.start, job.goal, job.limits);
+        let result = PathResult {
+            entity: job.entity,
+            seq: job.seq,
+            outcome,
+        };
+        if reply.send(result).is_err() {
+            return;
+        }
+    }
+}
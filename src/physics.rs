@@ -49,12 +49,54 @@ pub fn applied_acceleration(force: (f64, f64, f64), mass: Option<f64>) -> Option
     }
 }
 
-/// Applies ground friction to a horizontal velocity component.
+/// Scales a horizontal velocity component by `1 - friction`.
 ///
-/// The returned velocity is reduced by `GROUND_FRICTION` without reversing its
-/// direction. The friction constant is clamped to the range `[0.0, 1.0]` at
-/// runtime and checked in debug builds to avoid unintended amplification of
-/// motion.
+/// The coefficient is clamped to the range `[0.0, 1.0]` so friction never
+/// reverses or amplifies motion.
+///
+/// # Examples
+///
+/// ```rust
+/// use lille::physics::apply_friction;
+/// assert!((apply_friction(10.0, 0.25) - 7.5).abs() < 1e-12);
+/// assert!((apply_friction(10.0, 2.0)).abs() < 1e-12);
+/// ```
+#[must_use]
+pub fn apply_friction(v: f64, friction: f64) -> f64 {
+    let f = friction.clamp(0.0, 1.0);
+    v * (1.0 - f)
+}
+
+/// Applies `friction` to a horizontal velocity component for `dt` seconds.
+///
+/// `friction` is the fraction of speed lost per second, clamped as in
+/// [`apply_friction`]. The loss compounds over the interval, so stepping one
+/// second in small increments damps the velocity exactly as much as a single
+/// one-second step.
+///
+/// # Examples
+///
+/// ```rust
+/// use lille::physics::{apply_friction, apply_friction_over};
+/// assert!((apply_friction_over(10.0, 0.25, 1.0) - apply_friction(10.0, 0.25)).abs() < 1e-12);
+/// let halves = apply_friction_over(apply_friction_over(10.0, 0.25, 0.5), 0.25, 0.5);
+/// assert!((halves - 7.5).abs() < 1e-12);
+/// ```
+#[must_use]
+pub fn apply_friction_over(v: f64, friction: f64, dt: f64) -> f64 {
+    let f = friction.clamp(0.0, 1.0);
+    v * (1.0 - f).powf(dt)
+}
+
+/// Applies ground friction to a horizontal velocity component for one tick of
+/// [`DELTA_TIME`](crate::DELTA_TIME) seconds.
+///
+/// The returned velocity is reduced by `GROUND_FRICTION` per second without
+/// reversing its direction. The friction constant is clamped to the range
+/// `[0.0, 1.0]` at runtime and checked in debug builds to avoid unintended
+/// amplification of motion. The circuit applies the coefficient from
+/// [`PhysicsConfig`](crate::PhysicsConfig) instead, which defaults to this
+/// constant.
 ///
 /// # Examples
 ///
@@ -70,14 +112,13 @@ pub fn applied_acceleration(force: (f64, f64, f64), mass: Option<f64>) -> Option
 )]
 #[must_use]
 pub fn apply_ground_friction(v: f64) -> f64 {
-    use crate::GROUND_FRICTION;
+    use crate::{DELTA_TIME, GROUND_FRICTION};
 
     debug_assert!(
         GROUND_FRICTION >= 0.0 && GROUND_FRICTION <= 1.0,
         "GROUND_FRICTION must be within [0,1]",
     );
-    let f = GROUND_FRICTION.clamp(0.0, 1.0);
-    v * (1.0 - f)
+    apply_friction_over(v, GROUND_FRICTION, DELTA_TIME)
 }
 
 #[cfg(test)]
@@ -105,4 +146,10 @@ mod tests {
             f64::MIN * (1.0 - GROUND_FRICTION),
         );
     }
+
+    #[test]
+    fn friction_compounds_across_shorter_steps() {
+        let quarters = (0..4).fold(1.0, |v, _| apply_friction_over(v, GROUND_FRICTION, 0.25));
+        assert_relative_eq!(quarters, apply_friction_over(1.0, GROUND_FRICTION, 1.0));
+    }
 }
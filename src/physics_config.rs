@@ -0,0 +1,275 @@
+//! Runtime physics tuning shared between Bevy and the DBSP circuit.
+//!
+//! [`PhysicsConfig`] gathers the parameters that used to be compile-time
+//! constants so maps and tests can tune them without recompiling. The Bevy
+//! resource is forwarded to the circuit as a singleton input record, and a
+//! change takes effect on the next tick.
+//!
+//! Rates are given per second of game time. Each tick covers
+//! [`PhysicsConfig::delta_time`] seconds, and the helpers below scale the
+//! rates by it, so changing the tick rate leaves gameplay speed unchanged.
+
+use bevy::prelude::*;
+use ordered_float::OrderedFloat;
+use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
+use size_of::SizeOf;
+use thiserror::Error;
+
+use crate::physics::apply_friction_over;
+use crate::{
+    AIR_FRICTION, ARRIVAL_RADIUS, AVOIDANCE_WEIGHT, DELTA_TIME, FALL_DAMAGE_SCALE, FEAR_THRESHOLD,
+    GRAVITY_PULL, GROUND_FRICTION, KILL_PLANE_Z, LANDING_COOLDOWN, MAX_STEP_HEIGHT,
+    SAFE_LANDING_SPEED, TERMINAL_VELOCITY,
+};
+
+/// Tunable physics parameters applied by the DBSP circuit.
+///
+/// The defaults mirror the constants in [`crate::constants`]. Insert the
+/// resource with different values to change gravity, friction, step height,
+/// fall damage or the kill plane for a map, or the length of a tick; the
+/// [`DbspPlugin`](crate::DbspPlugin) forwards every change to the circuit.
+///
+/// # Examples
+/// ```
+/// use lille::PhysicsConfig;
+/// use ordered_float::OrderedFloat;
+///
+/// let moon = PhysicsConfig {
+///     gravity_pull: OrderedFloat(-0.2),
+///     ..PhysicsConfig::default()
+/// };
+/// assert_eq!(moon.safe_landing_speed, PhysicsConfig::default().safe_landing_speed);
+/// ```
+#[derive(
+    Archive,
+    RkyvSerialize,
+    RkyvDeserialize,
+    Resource,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    SizeOf,
+)]
+#[archive_attr(derive(Ord, PartialOrd, Eq, PartialEq, Hash))]
+pub struct PhysicsConfig {
+    /// Game time each tick advances the simulation by, in seconds.
+    ///
+    /// The [`DbspPlugin`](crate::DbspPlugin) steps the circuit once per
+    /// `delta_time` seconds, so this also sets the tick rate. Must be
+    /// positive.
+    pub delta_time: OrderedFloat<f64>,
+    /// Downward acceleration in block units per second squared.
+    pub gravity_pull: OrderedFloat<f64>,
+    /// Fraction of horizontal speed ground friction removes per second,
+    /// unitless.
+    pub ground_friction: OrderedFloat<f64>,
+    /// Default fraction of horizontal speed air drag removes from airborne
+    /// entities per second, unitless.
+    ///
+    /// Entities carrying a [`Drag`](crate::dbsp_circuit::Drag) record use
+    /// their own coefficient instead.
+    pub air_friction: OrderedFloat<f64>,
+    /// Height difference in blocks a standing entity can step across in one
+    /// move.
+    ///
+    /// Floors rising further than this are walls. Floors dropping further
+    /// leave the entity in mid-air, so it falls on the next tick.
+    pub max_step_height: OrderedFloat<f64>,
+    /// Maximum downward speed in block units per second.
+    pub terminal_velocity: OrderedFloat<f64>,
+    /// Impact speed up to which landings cause no damage, in block units per
+    /// second.
+    pub safe_landing_speed: OrderedFloat<f64>,
+    /// Damage per block per second of impact speed beyond the safe speed.
+    pub fall_damage_scale: OrderedFloat<f64>,
+    /// Minimum interval between fall damage applications, in seconds.
+    pub landing_cooldown: OrderedFloat<f64>,
+    /// Height below which entities falling through the void are killed, in
+    /// block units.
+    pub kill_plane_z: OrderedFloat<f64>,
+    /// Fear level above which an entity flees its target, unitless.
+    pub fear_threshold: OrderedFloat<f64>,
+    /// Distance from its target within which an approaching entity slows
+    /// down, in block units. Must be positive.
+    pub arrival_radius: OrderedFloat<f64>,
+    /// Weight of the turn away from walls ahead in a steering decision,
+    /// unitless.
+    pub avoidance_weight: OrderedFloat<f64>,
+}
+
+/// Reasons a [`PhysicsConfig`] cannot drive the simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum PhysicsConfigError {
+    /// The tick length is zero, negative or not a number.
+    #[error("tick length must be positive, got {0} seconds")]
+    NonPositiveDeltaTime(f64),
+    /// The arrival radius is zero, negative or not a number.
+    #[error("arrival radius must be positive, got {0} blocks")]
+    NonPositiveArrivalRadius(f64),
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self {
+            delta_time: OrderedFloat(DELTA_TIME),
+            gravity_pull: OrderedFloat(GRAVITY_PULL),
+            ground_friction: OrderedFloat(GROUND_FRICTION),
+            air_friction: OrderedFloat(AIR_FRICTION),
+            max_step_height: OrderedFloat(MAX_STEP_HEIGHT),
+            terminal_velocity: OrderedFloat(TERMINAL_VELOCITY),
+            safe_landing_speed: OrderedFloat(SAFE_LANDING_SPEED),
+            fall_damage_scale: OrderedFloat(FALL_DAMAGE_SCALE),
+            landing_cooldown: OrderedFloat(LANDING_COOLDOWN),
+            kill_plane_z: OrderedFloat(KILL_PLANE_Z),
+            fear_threshold: OrderedFloat(FEAR_THRESHOLD),
+            arrival_radius: OrderedFloat(ARRIVAL_RADIUS),
+            avoidance_weight: OrderedFloat(AVOIDANCE_WEIGHT),
+        }
+    }
+}
+
+impl PhysicsConfig {
+    /// Checks the parameters the simulation divides by.
+    ///
+    /// # Errors
+    /// Returns [`PhysicsConfigError`] when `delta_time` or `arrival_radius`
+    /// is not positive.
+    ///
+    /// # Examples
+    /// ```
+    /// use lille::{PhysicsConfig, PhysicsConfigError};
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// assert_eq!(PhysicsConfig::default().validate(), Ok(()));
+    /// let frozen = PhysicsConfig {
+    ///     delta_time: OrderedFloat(0.0),
+    ///     ..PhysicsConfig::default()
+    /// };
+    /// assert_eq!(
+    ///     frozen.validate(),
+    ///     Err(PhysicsConfigError::NonPositiveDeltaTime(0.0))
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<(), PhysicsConfigError> {
+        let delta_time = self.delta_time.into_inner();
+        if delta_time.is_nan() || delta_time <= 0.0 {
+            return Err(PhysicsConfigError::NonPositiveDeltaTime(delta_time));
+        }
+        let arrival_radius = self.arrival_radius.into_inner();
+        if arrival_radius.is_nan() || arrival_radius <= 0.0 {
+            return Err(PhysicsConfigError::NonPositiveArrivalRadius(arrival_radius));
+        }
+        Ok(())
+    }
+
+    /// Adds one tick of gravity to a vertical velocity and clamps it to the
+    /// terminal speed.
+    ///
+    /// # Examples
+    /// ```
+    /// use lille::PhysicsConfig;
+    ///
+    /// let config = PhysicsConfig::default();
+    /// assert_eq!(config.fall(-100.0), -config.terminal_velocity.into_inner());
+    /// ```
+    #[must_use]
+    pub fn fall(&self, vz: f64) -> f64 {
+        // Prevent unbounded acceleration by enforcing a maximum fall speed.
+        (vz + self.accelerate(self.gravity_pull.into_inner()))
+            .max(-self.terminal_velocity.into_inner())
+    }
+
+    /// Velocity change from one tick of `acceleration`.
+    #[must_use]
+    pub fn accelerate(&self, acceleration: f64) -> f64 {
+        acceleration * self.delta_time.into_inner()
+    }
+
+    /// Distance covered in one tick at `velocity`.
+    ///
+    /// # Examples
+    /// ```
+    /// use lille::PhysicsConfig;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let config = PhysicsConfig {
+    ///     delta_time: OrderedFloat(0.25),
+    ///     ..PhysicsConfig::default()
+    /// };
+    /// assert_eq!(config.displacement(2.0), 0.5);
+    /// assert_eq!(config.velocity(0.5), 2.0);
+    /// ```
+    #[must_use]
+    pub fn displacement(&self, velocity: f64) -> f64 {
+        velocity * self.delta_time.into_inner()
+    }
+
+    /// Velocity that covers `displacement` in one tick.
+    #[must_use]
+    pub fn velocity(&self, displacement: f64) -> f64 {
+        displacement / self.delta_time.into_inner()
+    }
+
+    /// Applies one tick of ground friction to a horizontal velocity component.
+    #[must_use]
+    pub fn apply_ground_friction(&self, v: f64) -> f64 {
+        self.apply_drag(v, self.ground_friction)
+    }
+
+    /// Applies one tick of drag with the given per-second `coefficient` to a
+    /// horizontal velocity component.
+    #[must_use]
+    pub fn apply_drag(&self, v: f64, coefficient: OrderedFloat<f64>) -> f64 {
+        apply_friction_over(v, coefficient.into_inner(), self.delta_time.into_inner())
+    }
+
+    /// Length of the landing cooldown in ticks, rounded up to whole ticks.
+    ///
+    /// # Examples
+    /// ```
+    /// use lille::PhysicsConfig;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let config = PhysicsConfig {
+    ///     delta_time: OrderedFloat(0.25),
+    ///     landing_cooldown: OrderedFloat(1.1),
+    ///     ..PhysicsConfig::default()
+    /// };
+    /// assert_eq!(config.landing_cooldown_ticks(), 5);
+    /// ```
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "The tick count is rounded and clamped at zero, and the cast saturates."
+    )]
+    #[must_use]
+    pub fn landing_cooldown_ticks(&self) -> u64 {
+        let ticks = (self.landing_cooldown.into_inner() / self.delta_time.into_inner()).ceil();
+        ticks.max(0.0) as u64
+    }
+
+    /// Fall damage for a landing at `speed` block units per second.
+    ///
+    /// The speed is clamped to the terminal velocity first. Returns `None`
+    /// for landings at or below the safe speed.
+    ///
+    /// # Examples
+    /// ```
+    /// use lille::PhysicsConfig;
+    ///
+    /// let config = PhysicsConfig::default();
+    /// assert_eq!(config.fall_damage(5.0), None);
+    /// assert_eq!(config.fall_damage(8.0), Some(8.0));
+    /// ```
+    #[must_use]
+    pub fn fall_damage(&self, speed: f64) -> Option<f64> {
+        let clamped_speed = speed.min(self.terminal_velocity.into_inner());
+        let excess = clamped_speed - self.safe_landing_speed.into_inner();
+        (excess > 0.0).then(|| excess * self.fall_damage_scale.into_inner())
+    }
+}
@@ -5,11 +5,20 @@
 //! a passive observer of simulation state: the DBSP circuit is the sole source
 //! of truth for inferred game behaviour.
 //!
+//! Simulated entities are drawn between fixed-timestep ticks by moving their
+//! `Transform` from [`PreviousSimulatedPosition`] towards [`SimulatedPosition`]
+//! just before transform propagation. The simulated translation is put back
+//! at the start of the next frame, so the DBSP circuit and gameplay systems
+//! only ever read the authoritative state.
+//!
 //! This module supersedes the temporary camera bootstrap in `LilleMapPlugin`.
 
+use bevy::ecs::schedule::ScheduleLabel;
 use bevy::prelude::*;
+use bevy::transform::TransformSystems;
 
-use crate::apply_dbsp_outputs_system;
+use crate::components::{DdlogId, MovingPlatform};
+use crate::{DbspSchedule, DbspSet};
 
 /// Marker component for the main presentation camera.
 ///
@@ -150,8 +159,8 @@ pub fn compute_pan_direction(input: PanInput) -> Vec2 {
 /// Large delta times are clamped to `CameraSettings.max_delta_seconds`
 /// to prevent extreme jumps during frame hitches.
 ///
-/// This system is ordered to run after `apply_dbsp_outputs_system` to
-/// ensure the camera observes the latest entity positions before panning.
+/// This system is ordered to run after [`DbspSet::Apply`] to ensure the
+/// camera observes the latest entity positions before panning.
 ///
 /// # Examples
 ///
@@ -159,7 +168,7 @@ pub fn compute_pan_direction(input: PanInput) -> Vec2 {
 /// manually with custom ordering:
 ///
 /// ```ignore
-/// app.add_systems(Update, camera_pan_system.after(apply_dbsp_outputs_system));
+/// app.add_systems(Update, camera_pan_system.after(DbspSet::Apply));
 /// ```
 #[expect(
     clippy::needless_pass_by_value,
@@ -198,6 +207,113 @@ pub fn camera_pan_system(
     transform.translation.y += velocity.y;
 }
 
+/// Simulated translation of an entity as of the previous fixed-timestep tick.
+///
+/// Owned by the presentation layer; rendering interpolates from this value
+/// towards [`SimulatedPosition`].
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct PreviousSimulatedPosition(pub Vec3);
+
+/// Simulated translation of an entity as of the latest fixed-timestep tick.
+///
+/// A presentation-side copy of `Transform.translation`, captured after the
+/// DBSP outputs have been applied.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct SimulatedPosition(pub Vec3);
+
+/// Query over simulated entities whose positions are recorded each tick.
+type RecordedQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Transform,
+        Option<&'static mut SimulatedPosition>,
+        Option<&'static mut PreviousSimulatedPosition>,
+    ),
+    Or<(With<DdlogId>, With<MovingPlatform>)>,
+>;
+
+/// Records simulated positions at the end of every simulation tick.
+///
+/// [`PresentationPlugin`] runs it after [`DbspSet::Apply`] in the schedule
+/// stepping the circuit, so each tick's outputs are captured once.
+///
+/// The last [`SimulatedPosition`] moves into [`PreviousSimulatedPosition`] and
+/// the current `Transform` translation becomes the new simulated position.
+/// Entities seen for the first time receive both components with the same
+/// value so they do not slide in from the origin.
+pub fn record_simulated_positions(mut commands: Commands, mut query: RecordedQuery) {
+    for (entity, transform, current, previous) in &mut query {
+        let translation = transform.translation;
+        if let (Some(mut current_pos), Some(mut previous_pos)) = (current, previous) {
+            previous_pos.0 = current_pos.0;
+            current_pos.0 = translation;
+        } else {
+            commands.entity(entity).insert((
+                PreviousSimulatedPosition(translation),
+                SimulatedPosition(translation),
+            ));
+        }
+    }
+}
+
+/// Query over simulated entities drawn between ticks.
+type InterpolatedQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Transform,
+        &'static mut PreviousSimulatedPosition,
+        &'static mut SimulatedPosition,
+    ),
+>;
+
+/// Interpolates the rendered position of simulated entities between ticks.
+///
+/// Runs just before transform propagation and moves each entity's
+/// `Transform` to the point between the previous and current simulated
+/// positions given by the fixed clock's overstep fraction, so the propagated
+/// `GlobalTransform`, and any children, follow it.
+/// [`restore_simulated_positions`] puts the simulated translation back before
+/// the next DBSP pass.
+///
+/// An entity whose `Transform` no longer holds its simulated position was
+/// moved outside the simulation since the last tick. It stays where it was
+/// put, and both recorded positions snap to it.
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "Bevy systems require parameters by value, not by reference."
+)]
+pub fn interpolate_rendered_positions(time: Res<Time<Fixed>>, mut rendered: InterpolatedQuery) {
+    let alpha = time.overstep_fraction();
+    for (mut transform, mut previous, mut current) in &mut rendered {
+        if transform.translation != current.0 {
+            previous.0 = transform.translation;
+            current.0 = transform.translation;
+            continue;
+        }
+        let interpolated = previous.0.lerp(current.0, alpha);
+        if transform.translation != interpolated {
+            transform.translation = interpolated;
+        }
+    }
+}
+
+/// Puts the simulated translation back into the `Transform` of every
+/// interpolated entity.
+///
+/// Runs at the start of each frame, ahead of any DBSP pass, so the circuit
+/// and gameplay systems read the authoritative state rather than the rendered
+/// one. Entities already at their simulated position are not marked changed.
+pub fn restore_simulated_positions(mut query: Query<(&mut Transform, &SimulatedPosition)>) {
+    for (mut transform, current) in &mut query {
+        if transform.translation != current.0 {
+            transform.translation = current.0;
+        }
+    }
+}
+
 /// Plugin owning camera setup and presentation layer systems.
 ///
 /// # Responsibilities
@@ -205,6 +321,11 @@ pub fn camera_pan_system(
 /// - Spawns the main `Camera2d` with `CameraController` marker at startup.
 /// - Registers `CameraController` for reflection.
 /// - Hosts the `camera_pan_system` for keyboard-based camera panning.
+/// - Records simulated positions after [`DbspSet::Apply`] in the schedule
+///   [`DbspPlugin`](crate::DbspPlugin) steps the circuit in, falling back to
+///   `FixedUpdate` without one.
+/// - Interpolates the `Transform` of simulated entities before transform
+///   propagation, and restores their simulated positions in `PreUpdate`.
 /// - Future: Hosts zoom and Y-sorting systems.
 ///
 /// # Dependencies
@@ -233,7 +354,27 @@ impl Plugin for PresentationPlugin {
         app.register_type::<CameraController>();
         app.init_resource::<CameraSettings>();
         app.add_systems(Startup, camera_setup);
-        app.add_systems(Update, camera_pan_system.after(apply_dbsp_outputs_system));
+        app.add_systems(Update, camera_pan_system.after(DbspSet::Apply));
+        app.add_systems(
+            PreUpdate,
+            restore_simulated_positions.before(DbspSet::Ingest),
+        );
+        app.add_systems(
+            PostUpdate,
+            interpolate_rendered_positions
+                .after(record_simulated_positions)
+                .before(TransformSystems::Propagate),
+        );
+    }
+
+    fn finish(&self, app: &mut App) {
+        // The DBSP plugin may be added after this one, so its schedule is only
+        // known once every plugin has been built.
+        let schedule = app
+            .world()
+            .get_resource::<DbspSchedule>()
+            .map_or_else(|| FixedUpdate.intern(), |dbsp| dbsp.0);
+        app.add_systems(schedule, record_simulated_positions.after(DbspSet::Apply));
     }
 }
 
@@ -362,4 +503,217 @@ mod tests {
             dir.y
         );
     }
+
+    // --- simulated position interpolation tests ---
+
+    /// Builds an app running the interpolation systems with a one-second
+    /// fixed clock that has accumulated `overstep_secs` past its last tick.
+    fn interpolation_app(overstep_secs: f64) -> App {
+        let mut app = App::new();
+        let mut fixed = Time::<Fixed>::from_seconds(1.0);
+        fixed.accumulate_overstep(std::time::Duration::from_secs_f64(overstep_secs));
+        app.insert_resource(fixed);
+        app.add_systems(
+            Update,
+            (record_simulated_positions, interpolate_rendered_positions).chain(),
+        );
+        app
+    }
+
+    #[test]
+    fn first_record_initialises_both_positions() {
+        let mut app = interpolation_app(0.0);
+        let entity = app
+            .world_mut()
+            .spawn((DdlogId(1), Transform::from_xyz(3.0, 4.0, 5.0)))
+            .id();
+
+        app.update();
+
+        let world = app.world();
+        let expected = Vec3::new(3.0, 4.0, 5.0);
+        assert_eq!(
+            world.get::<PreviousSimulatedPosition>(entity),
+            Some(&PreviousSimulatedPosition(expected))
+        );
+        assert_eq!(
+            world.get::<SimulatedPosition>(entity),
+            Some(&SimulatedPosition(expected))
+        );
+    }
+
+    #[test]
+    fn record_shifts_current_into_previous() {
+        let mut app = interpolation_app(0.0);
+        let entity = app
+            .world_mut()
+            .spawn((
+                DdlogId(1),
+                Transform::from_xyz(2.0, 0.0, 0.0),
+                PreviousSimulatedPosition(Vec3::ZERO),
+                SimulatedPosition(Vec3::X),
+            ))
+            .id();
+
+        app.update();
+
+        let world = app.world();
+        assert_eq!(
+            world.get::<PreviousSimulatedPosition>(entity),
+            Some(&PreviousSimulatedPosition(Vec3::X))
+        );
+        assert_eq!(
+            world.get::<SimulatedPosition>(entity),
+            Some(&SimulatedPosition(Vec3::new(2.0, 0.0, 0.0)))
+        );
+    }
+
+    #[test]
+    fn per_frame_circuit_records_after_apply() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<ButtonInput<KeyCode>>()
+            .add_plugins((PresentationPlugin, crate::DbspPlugin::per_frame()));
+        app.world_mut().spawn(crate::components::Block {
+            id: 1,
+            x: 0,
+            y: 0,
+            z: 0,
+        });
+        let entity = app
+            .world_mut()
+            .spawn((
+                DdlogId(1),
+                Transform::from_xyz(0.0, 0.0, 2.0),
+                crate::VelocityComp::default(),
+            ))
+            .id();
+
+        app.finish();
+        app.update();
+
+        let world = app.world();
+        let applied = world
+            .get::<Transform>(entity)
+            .expect("Transform should persist after the DBSP step")
+            .translation;
+        assert!(applied.z < 2.0, "the circuit should have moved the entity");
+        assert_eq!(
+            world.get::<SimulatedPosition>(entity),
+            Some(&SimulatedPosition(applied)),
+            "the recorder should capture the applied outputs in the same frame"
+        );
+    }
+
+    /// App propagating transforms, with the interpolation and restore systems
+    /// placed as [`PresentationPlugin`] places them.
+    fn render_app(overstep_secs: f64) -> App {
+        let mut app = App::new();
+        let mut fixed = Time::<Fixed>::from_seconds(1.0);
+        fixed.accumulate_overstep(std::time::Duration::from_secs_f64(overstep_secs));
+        app.insert_resource(fixed);
+        app.add_plugins(TransformPlugin);
+        app.add_systems(PreUpdate, restore_simulated_positions);
+        app.add_systems(
+            PostUpdate,
+            interpolate_rendered_positions.before(TransformSystems::Propagate),
+        );
+        app
+    }
+
+    fn rendered_x(app: &App, entity: Entity) -> (f32, f32) {
+        let world = app.world();
+        let local = world
+            .get::<Transform>(entity)
+            .expect("entity keeps its transform")
+            .translation;
+        let global = world
+            .get::<GlobalTransform>(entity)
+            .expect("Transform requires GlobalTransform")
+            .translation();
+        (local.x, global.x)
+    }
+
+    #[rstest]
+    #[case::start_of_tick(0.0, 0.0)]
+    #[case::half_overstep(0.5, 5.0)]
+    #[case::late_in_tick(0.75, 7.5)]
+    fn rendered_position_follows_overstep(#[case] overstep: f64, #[case] expected_x: f32) {
+        let mut app = render_app(overstep);
+        let entity = app
+            .world_mut()
+            .spawn((
+                Transform::from_xyz(10.0, 0.0, 0.0),
+                PreviousSimulatedPosition(Vec3::ZERO),
+                SimulatedPosition(Vec3::new(10.0, 0.0, 0.0)),
+            ))
+            .id();
+
+        app.update();
+
+        let (local, global) = rendered_x(&app, entity);
+        assert!(
+            (local - expected_x).abs() < 1e-5 && (global - expected_x).abs() < 1e-5,
+            "expected x={expected_x}, got local {local} and global {global}"
+        );
+        app.world_mut().run_schedule(PreUpdate);
+        assert_eq!(
+            app.world().get::<Transform>(entity),
+            Some(&Transform::from_xyz(10.0, 0.0, 0.0)),
+            "the simulated position is restored before the next pass"
+        );
+    }
+
+    #[test]
+    fn child_rendered_position_includes_parent_offset() {
+        let mut app = render_app(0.5);
+        let parent = app
+            .world_mut()
+            .spawn(Transform::from_xyz(100.0, 0.0, 0.0))
+            .id();
+        let child = app
+            .world_mut()
+            .spawn((
+                ChildOf(parent),
+                Transform::from_xyz(10.0, 0.0, 0.0),
+                PreviousSimulatedPosition(Vec3::ZERO),
+                SimulatedPosition(Vec3::new(10.0, 0.0, 0.0)),
+            ))
+            .id();
+
+        app.update();
+
+        let (_, global) = rendered_x(&app, child);
+        assert!(
+            (global - 105.0).abs() < 1e-5,
+            "expected parent offset plus midpoint, got {global}"
+        );
+    }
+
+    #[test]
+    fn moves_made_outside_the_simulation_are_kept() {
+        let mut app = render_app(0.5);
+        let entity = app
+            .world_mut()
+            .spawn((
+                Transform::from_xyz(3.0, 0.0, 0.0),
+                PreviousSimulatedPosition(Vec3::ZERO),
+                SimulatedPosition(Vec3::new(10.0, 0.0, 0.0)),
+            ))
+            .id();
+        // Skip the restore, as if the move happened after it this frame.
+        app.world_mut().run_schedule(PostUpdate);
+
+        let moved = Vec3::new(3.0, 0.0, 0.0);
+        let world = app.world();
+        assert_eq!(rendered_x(&app, entity), (3.0, 3.0));
+        assert_eq!(
+            world.get::<PreviousSimulatedPosition>(entity),
+            Some(&PreviousSimulatedPosition(moved))
+        );
+        assert_eq!(
+            world.get::<SimulatedPosition>(entity),
+            Some(&SimulatedPosition(moved))
+        );
+    }
 }
@@ -0,0 +1,148 @@
+//! Runtime terrain edits.
+//!
+//! Maps load their terrain as [`Block`] entities, and [`TerrainCommands`]
+//! places and removes such blocks during play, for example when a building is
+//! raised or a wall demolished. The DBSP synchronisation picks up the changed
+//! blocks, so an edit reaches the circuit as a weighted insertion or retraction
+//! on the next tick: floor heights update, and units standing on a removed
+//! block fall.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::components::{Block, BlockSlope};
+
+/// System parameter placing and removing terrain blocks.
+///
+/// Edits are queued as Bevy commands and take effect when the commands are
+/// applied, like spawning or despawning any other entity.
+///
+/// # Examples
+/// ```
+/// use bevy::prelude::*;
+/// use lille::components::Block;
+/// use lille::terrain::TerrainCommands;
+///
+/// fn raise_wall(mut terrain: TerrainCommands) {
+///     terrain.place(Block { id: 100, x: 4, y: 2, z: 0 });
+/// }
+///
+/// fn demolish_wall(mut terrain: TerrainCommands) {
+///     terrain.remove(100);
+/// }
+/// # let mut app = App::new();
+/// # app.add_systems(Update, (raise_wall, demolish_wall).chain());
+/// ```
+#[derive(SystemParam)]
+pub struct TerrainCommands<'w, 's> {
+    commands: Commands<'w, 's>,
+    blocks: Query<'w, 's, (Entity, &'static Block)>,
+}
+
+impl TerrainCommands<'_, '_> {
+    /// Spawns a flat block and returns its entity.
+    ///
+    /// `block.id` should be unique among the terrain blocks, as
+    /// [`remove`](Self::remove) and slope records refer to blocks by id.
+    pub fn place(&mut self, block: Block) -> Entity {
+        self.commands.spawn(block).id()
+    }
+
+    /// Spawns a sloped block and returns its entity.
+    ///
+    /// The slope's `block_id` is set to `block.id`, so the two always match.
+    pub fn place_sloped(&mut self, block: Block, mut slope: BlockSlope) -> Entity {
+        slope.block_id = block.id;
+        self.commands.spawn((block, slope)).id()
+    }
+
+    /// Despawns every block with identifier `id`, returning whether one was
+    /// found.
+    pub fn remove(&mut self, id: i64) -> bool {
+        self.remove_where(|block| block.id == id)
+    }
+
+    /// Despawns every block at grid position `(x, y, z)`, returning whether
+    /// one was found.
+    pub fn remove_at(&mut self, x: i32, y: i32, z: i32) -> bool {
+        self.remove_where(|block| (block.x, block.y, block.z) == (x, y, z))
+    }
+
+    fn remove_where(&mut self, matches: impl Fn(&Block) -> bool) -> bool {
+        let mut found = false;
+        for (entity, block) in &self.blocks {
+            if matches(block) {
+                self.commands.entity(entity).despawn();
+                found = true;
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for runtime terrain edits.
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use ordered_float::OrderedFloat;
+
+    fn blocks(world: &mut World) -> Vec<(Block, Option<BlockSlope>)> {
+        let mut query = world.query::<(&Block, Option<&BlockSlope>)>();
+        let mut found: Vec<_> = query
+            .iter(world)
+            .map(|(block, slope)| (block.clone(), slope.cloned()))
+            .collect();
+        found.sort();
+        found
+    }
+
+    fn block(id: i64, (x, y, z): (i32, i32, i32)) -> Block {
+        Block { id, x, y, z }
+    }
+
+    #[test]
+    fn place_sloped_ties_the_slope_to_the_block() {
+        let mut world = World::new();
+        world
+            .run_system_once(|mut terrain: TerrainCommands| {
+                terrain.place_sloped(
+                    block(7, (0, 0, 0)),
+                    BlockSlope {
+                        block_id: 99,
+                        grad_x: OrderedFloat(0.5),
+                        grad_y: OrderedFloat(0.0),
+                    },
+                );
+            })
+            .expect("place system should run");
+
+        let placed = blocks(&mut world);
+        let [(placed_block, Some(placed_slope))] = placed.as_slice() else {
+            panic!("expected one sloped block, got {placed:?}");
+        };
+        assert_eq!(placed_block.id, 7);
+        assert_eq!(placed_slope.block_id, 7);
+    }
+
+    #[test]
+    fn remove_despawns_matching_blocks() {
+        let mut world = World::new();
+        world.spawn(block(1, (0, 0, 0)));
+        world.spawn(block(2, (0, 0, 1)));
+        world.spawn(block(3, (1, 0, 0)));
+
+        let removed = world
+            .run_system_once(|mut terrain: TerrainCommands| {
+                (
+                    terrain.remove(2),
+                    terrain.remove_at(1, 0, 0),
+                    terrain.remove(42),
+                )
+            })
+            .expect("remove system should run");
+
+        assert_eq!(removed, (true, true, false));
+        assert_eq!(blocks(&mut world), vec![(block(1, (0, 0, 0)), None)]);
+    }
+}
@@ -42,8 +42,8 @@ impl Default for DdlogEntity {
 #[derive(Resource, Default)]
 /// Snapshot of ECS data mirrored for DBSP.
 pub struct WorldHandle {
-    /// Blocks forming the terrain grid.
-    pub(crate) blocks: Vec<Block>,
+    /// Blocks forming the terrain grid, keyed by the entity carrying them.
+    pub(crate) blocks: HashMap<Entity, Block>,
     /// Optional slopes associated with blocks.
     pub(crate) slopes: HashMap<i64, BlockSlope>,
     /// Active entities indexed by identifier.
@@ -64,7 +64,7 @@ impl WorldHandle {
 
     /// Returns the number of blocks in the world.
     #[must_use]
-    pub const fn block_count(&self) -> usize {
+    pub fn block_count(&self) -> usize {
         self.blocks.len()
     }
 }
@@ -1,13 +1,18 @@
-//! Exercises the ECS ↔ DBSP loop to ensure gravity persists through a full tick.
+//! Exercises the ECS ↔ DBSP loop to ensure gravity, impulses and moving
+//! platforms persist through a full tick.
 
 use approx::assert_relative_eq;
 use bevy::prelude::*;
-use lille::{components::Block, DbspPlugin, DdlogId, VelocityComp, GRAVITY_PULL};
+use lille::{
+    components::{Block, MovingPlatform},
+    DbspPlugin, DdlogId, ImpulseComp, VelocityComp, GRAVITY_PULL,
+};
 
 #[test]
 fn ecs_dbsp_round_trip_applies_gravity() {
     let mut app = App::new();
-    app.add_plugins(MinimalPlugins).add_plugins(DbspPlugin);
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(DbspPlugin::per_frame());
 
     app.world_mut().spawn(Block {
         id: 1,
@@ -39,3 +44,91 @@ fn ecs_dbsp_round_trip_applies_gravity() {
         .expect("Velocity component should persist after DBSP round trip");
     assert_relative_eq!(f64::from(vel.vz), GRAVITY_PULL);
 }
+
+#[test]
+fn impulse_lifts_a_standing_entity_once() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(DbspPlugin::per_frame());
+
+    app.world_mut().spawn(Block {
+        id: 1,
+        x: 0,
+        y: 0,
+        z: 0,
+    });
+
+    let entity = app
+        .world_mut()
+        .spawn((
+            DdlogId(1),
+            Transform::from_xyz(0.5, 0.5, 1.0),
+            VelocityComp::default(),
+            ImpulseComp {
+                dvz: 3.0,
+                ..ImpulseComp::default()
+            },
+        ))
+        .id();
+
+    app.update();
+
+    let transform = app
+        .world()
+        .get::<Transform>(entity)
+        .expect("Transform component should persist after DBSP round trip");
+    assert_relative_eq!(f64::from(transform.translation.z), 1.0 + 3.0 + GRAVITY_PULL);
+
+    let vel = app
+        .world()
+        .get::<VelocityComp>(entity)
+        .expect("Velocity component should persist after DBSP round trip");
+    assert_relative_eq!(f64::from(vel.vz), 3.0 + GRAVITY_PULL);
+
+    assert!(
+        app.world().get::<ImpulseComp>(entity).is_none(),
+        "the impulse should be consumed by the step"
+    );
+}
+
+#[test]
+fn moving_platform_carries_its_rider() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(DbspPlugin::per_frame());
+
+    let platform = app
+        .world_mut()
+        .spawn((
+            Transform::from_xyz(0.0, 0.0, 0.0),
+            MovingPlatform {
+                blocks: vec![[0, 0, 0]],
+                vx: 0.5,
+                ..MovingPlatform::default()
+            },
+        ))
+        .id();
+    let rider = app
+        .world_mut()
+        .spawn((
+            DdlogId(1),
+            Transform::from_xyz(0.5, 0.5, 1.0),
+            VelocityComp::default(),
+        ))
+        .id();
+
+    app.update();
+
+    let platform_transform = app
+        .world()
+        .get::<Transform>(platform)
+        .expect("platform Transform should persist after DBSP round trip");
+    assert_relative_eq!(platform_transform.translation.x, 0.5);
+
+    let rider_transform = app
+        .world()
+        .get::<Transform>(rider)
+        .expect("rider Transform should persist after DBSP round trip");
+    assert_relative_eq!(rider_transform.translation.x, 1.0);
+    assert_relative_eq!(rider_transform.translation.z, 1.0);
+}
@@ -15,7 +15,8 @@ impl DbspTestAppBuilder {
     #[must_use]
     pub fn new() -> Self {
         let mut app = App::new();
-        app.add_plugins(MinimalPlugins).add_plugins(DbspPlugin);
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(DbspPlugin::per_frame());
         Self { app }
     }
 
@@ -10,7 +10,7 @@ use rstest::rstest;
 #[case::position(Position { entity: 0, x: OrderedFloat(0.0), y: OrderedFloat(0.0), z: OrderedFloat(0.0) })]
 #[case::velocity(Velocity { entity: 0, vx: OrderedFloat(0.0), vy: OrderedFloat(0.0), vz: OrderedFloat(0.0) })]
 #[case::highest_block(HighestBlockAt { x: 0, y: 0, z: 0 })]
-#[case::floor_height(FloorHeightAt { x: 0, y: 0, z: OrderedFloat(0.0) })]
+#[case::floor_height(FloorHeightAt { x: 0, y: 0, z: OrderedFloat(0.0), grad_x: OrderedFloat(0.0), grad_y: OrderedFloat(0.0), base: OrderedFloat(0.0), ceiling: None })]
 #[case::target(Target { entity: 0, x: OrderedFloat(0.0), y: OrderedFloat(0.0) })]
 #[case::movement_decision(MovementDecision { entity: 0, dx: OrderedFloat(0.0), dy: OrderedFloat(0.0) })]
 fn copy_records_are_copy<T>(#[case] sample: T)
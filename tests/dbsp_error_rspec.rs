@@ -34,7 +34,7 @@ impl DbspErrorFixture {
         let mut app = App::new();
         app.add_plugins(MinimalPlugins);
         dbsp_test_support::install_error_observer(&mut app);
-        app.add_plugins(DbspPlugin);
+        app.add_plugins(DbspPlugin::per_frame());
         let entity = app
             .world_mut()
             .spawn((
@@ -18,7 +18,7 @@ use lille::DbspPlugin;
 fn build_app() -> App {
     let mut app = App::new();
     app.add_plugins(MinimalPlugins);
-    app.add_plugins(DbspPlugin);
+    app.add_plugins(DbspPlugin::per_frame());
     app
 }
 
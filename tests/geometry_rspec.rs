@@ -66,6 +66,10 @@ fn slope_block_outputs_height() -> Result<()> {
         x: 0,
         y: 0,
         z: 1.5.into(),
+        grad_x: 1.0.into(),
+        grad_y: 0.0.into(),
+        base: 0.0.into(),
+        ceiling: None,
     }];
     ensure!(
         out == expected,
@@ -27,7 +27,7 @@ fn trigger_pending_damage(mut commands: Commands, mut pending: ResMut<PendingDam
 fn app_with_entity() -> (App, Entity) {
     let mut app = App::new();
     app.add_plugins(MinimalPlugins);
-    app.add_plugins(DbspPlugin);
+    app.add_plugins(DbspPlugin::per_frame());
     let entity = app
         .world_mut()
         .spawn((
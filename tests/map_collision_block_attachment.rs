@@ -5,14 +5,14 @@
 #![cfg_attr(not(feature = "test-support"), doc = "Tests require `test-support`.")]
 #![cfg(feature = "test-support")]
 //! Verifies that `LilleMapPlugin` attaches `Block` and `BlockSlope` components to
-//! `Collidable` entities, with `BlockSlope` only attached when `SlopeProperties` is present.
+//! `Collidable` and `Door` entities, with `BlockSlope` only attached when `SlopeProperties` is present.
 
 #[path = "support/map_test_plugins.rs"]
 mod map_test_plugins;
 
 use bevy::prelude::*;
 use bevy_ecs_tiled::prelude::{MapCreated, TilePos, TiledEvent};
-use lille::components::{Block, BlockSlope};
+use lille::components::{Block, BlockSlope, Door};
 use lille::map::{Collidable, SlopeProperties};
 use lille::LilleMapPlugin;
 use rstest::{fixture, rstest};
@@ -82,6 +82,28 @@ fn attaches_block_to_collidable_entity(mut test_app: App) {
     assert_eq!(block.z, 0, "block z should be 0 for single-level maps");
 }
 
+#[rstest]
+fn attaches_block_to_door_entity(mut test_app: App) {
+    let entity = test_app
+        .world_mut()
+        .spawn((Door { open: true }, TilePos { x: 2, y: 4 }))
+        .id();
+    trigger_map_created(test_app.world_mut());
+
+    test_app.update();
+
+    let block = test_app
+        .world()
+        .get::<Block>(entity)
+        .expect("expected Block component on door entity");
+
+    assert_eq!(
+        (block.x, block.y),
+        (2, 4),
+        "door block should match TilePos"
+    );
+}
+
 #[rstest]
 fn does_not_attach_block_to_non_collidable_entity(mut test_app: App) {
     let entity = spawn_non_collidable_tile(test_app.world_mut(), 3, 7);
@@ -14,6 +14,7 @@ use std::any::TypeId;
 use bevy::ecs::reflect::ReflectComponent;
 use bevy::prelude::*;
 use bevy::reflect::{Reflect, TypeRegistry};
+use lille::components::Door;
 use lille::map::{Collidable, PlayerSpawn, SlopeProperties, SpawnPoint};
 use lille::LilleMapPlugin;
 use rstest::rstest;
@@ -31,6 +32,7 @@ struct NotRegistered;
 
 #[rstest]
 #[case::collidable(TypeId::of::<Collidable>(), "Collidable")]
+#[case::door(TypeId::of::<Door>(), "Door")]
 #[case::slope_properties(TypeId::of::<SlopeProperties>(), "SlopeProperties")]
 #[case::player_spawn(TypeId::of::<PlayerSpawn>(), "PlayerSpawn")]
 #[case::spawn_point(TypeId::of::<SpawnPoint>(), "SpawnPoint")]
@@ -54,7 +54,7 @@ impl MapCustomPropertiesFixture {
     fn bootstrap() -> Self {
         let mut app = App::new();
         map_test_plugins::add_map_test_plugins(&mut app);
-        app.add_plugins(DbspPlugin);
+        app.add_plugins(DbspPlugin::per_frame());
 
         map_test_plugins::install_map_error_capture(&mut app);
         app.insert_resource(LilleMapSettings {
@@ -48,7 +48,7 @@ impl MapPluginFixture {
     fn bootstrap() -> Self {
         let mut app = App::new();
         map_test_plugins::add_map_test_plugins(&mut app);
-        app.add_plugins(DbspPlugin);
+        app.add_plugins(DbspPlugin::per_frame());
 
         map_test_plugins::install_map_error_capture(&mut app);
         app.add_plugins(LilleMapPlugin);
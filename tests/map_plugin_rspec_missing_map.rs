@@ -48,7 +48,7 @@ impl MapPluginFixture {
     fn bootstrap_missing_map() -> Self {
         let mut app = App::new();
         map_test_plugins::add_map_test_plugins(&mut app);
-        app.add_plugins(DbspPlugin);
+        app.add_plugins(DbspPlugin::per_frame());
         app.insert_resource(LilleMapSettings {
             primary_map: MapAssetPath::from("maps/does-not-exist.tmx"),
             should_spawn_primary_map: true,
@@ -43,7 +43,7 @@ impl MapPluginFixture {
     fn bootstrap_with_settings(settings: LilleMapSettings) -> Self {
         let mut app = App::new();
         map_test_plugins::add_map_test_plugins(&mut app);
-        app.add_plugins(DbspPlugin);
+        app.add_plugins(DbspPlugin::per_frame());
         app.insert_resource(settings);
 
         map_test_plugins::install_map_error_capture(&mut app);
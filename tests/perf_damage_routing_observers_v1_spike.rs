@@ -83,7 +83,7 @@ fn routing_costs_are_reasonable() {
 
     let mut app = App::new();
     app.add_plugins(MinimalPlugins);
-    app.add_plugins(DbspPlugin);
+    app.add_plugins(DbspPlugin::per_frame());
 
     let events = vec![sample_event(); N];
 
@@ -38,7 +38,7 @@ impl fmt::Debug for TestWorld {
 impl Default for TestWorld {
     fn default() -> Self {
         let mut app = App::new();
-        app.add_plugins(MinimalPlugins).add_plugins(DbspPlugin);
+        app.add_plugins(MinimalPlugins).add_plugins(DbspPlugin::per_frame());
         Self {
             app: Arc::new(Mutex::new(ThreadSafeApp(app))),
             entity: None,
@@ -1,12 +1,15 @@
 //! Behavioural tests for reactive agent movement decisions.
 //!
-//! Verifies that DBSP-derived movement responds to fear and target inputs,
+//! Verifies that DBSP-derived movement responds to fear and target inputs, to
+//! the fear civilians derive from nearby baddies, and to arrival at a target,
 //! ensuring the circuit remains the source of truth for agent behaviour.
 
 use anyhow::{ensure, Context, Result};
 use approx::relative_eq;
 use lille::components::Block;
-use lille::dbsp_circuit::{DbspCircuit, FearLevel, NewPosition, Position, Target, Velocity};
+use lille::dbsp_circuit::{
+    Arrived, Baddie, Civvy, DbspCircuit, FearLevel, NewPosition, Position, Target, Velocity,
+};
 use rstest::rstest;
 use test_utils::{block, fear, pos, step, vel};
 
@@ -30,6 +33,11 @@ impl Env {
         self.circuit.position_in().push(p, 1);
     }
 
+    fn move_position(&mut self, from: Position, to: Position) {
+        self.circuit.position_in().push(from, -1);
+        self.circuit.position_in().push(to, 1);
+    }
+
     fn push_velocity(&mut self, v: Velocity) {
         self.circuit.velocity_in().push(v, 1);
     }
@@ -42,10 +50,27 @@ impl Env {
         self.circuit.fear_in().push(f, 1);
     }
 
+    fn push_civvy(&mut self, c: Civvy) {
+        self.circuit.civvy_in().push(c, 1);
+    }
+
+    fn push_baddie(&mut self, b: Baddie) {
+        self.circuit.baddie_in().push(b, 1);
+    }
+
     fn step(&mut self) {
         step(&mut self.circuit);
     }
 
+    fn drain_arrivals(&mut self) -> Vec<(Arrived, i64)> {
+        self.circuit
+            .arrived_out()
+            .consolidate()
+            .iter()
+            .map(|(arrived, (), weight)| (arrived, weight))
+            .collect()
+    }
+
     fn drain_output(&mut self) -> Vec<NewPosition> {
         let vals: Vec<NewPosition> = self
             .circuit
@@ -238,3 +263,92 @@ fn handles_multiple_entities_with_mixed_states() -> Result<()> {
     ];
     assert_positions_match_tuples(&out, &expected)
 }
+
+#[rstest]
+#[case::baddie_nearby(1.0, true, (-1.0, 0.0))]
+#[case::baddie_nearby_without_target(1.0, false, (-1.0, 0.0))]
+#[case::baddie_far_away(
+    4.0,
+    true,
+    (std::f64::consts::FRAC_1_SQRT_2, std::f64::consts::FRAC_1_SQRT_2)
+)]
+fn civvies_flee_nearby_baddies(
+    #[case] baddie_x: f64,
+    #[case] has_target: bool,
+    #[case] expected: (f64, f64),
+) -> Result<()> {
+    let mut env = Env::new()?;
+    for (id, x) in (1..).zip(-1..=4) {
+        env.push_block(block(id, (x, 0, 0)));
+    }
+
+    env.push_position(pos(1, (0.0, 0.0, 1.0)));
+    env.push_velocity(vel(1, (0.0, 0.0, 0.0)));
+    if has_target {
+        env.push_target(Target {
+            entity: 1,
+            x: 1.0.into(),
+            y: 1.0.into(),
+        });
+    }
+    env.push_civvy(Civvy {
+        entity: 1,
+        fraidiness: 0.8.into(),
+    });
+
+    env.push_position(pos(2, (baddie_x, 0.0, 1.0)));
+    env.push_velocity(vel(2, (0.0, 0.0, 0.0)));
+    env.push_baddie(Baddie {
+        entity: 2,
+        meanness: 0.5.into(),
+    });
+
+    env.step();
+    let mut out = env.drain_output();
+    out.sort_by_key(|p| p.entity);
+    let (x, y) = expected;
+    assert_positions_match_tuples(&out, &[(1, x, y, 1.0), (2, baddie_x, 0.0, 1.0)])
+}
+
+#[rstest]
+fn entities_settle_on_near_targets() -> Result<()> {
+    let mut env = Env::new()?;
+    env.push_block(block(1, (0, 0, 0)));
+    let start = pos(1, (0.0, 0.0, 1.0));
+    env.push_position(start);
+    env.push_velocity(vel(1, (0.0, 0.0, 0.0)));
+    env.push_target(Target {
+        entity: 1,
+        x: 0.3.into(),
+        y: 0.0.into(),
+    });
+
+    env.step();
+    ensure!(env.drain_arrivals().is_empty(), "arrived before moving");
+    let landed = env.drain_output();
+    assert_positions_match_tuples(&landed, &[(1, 0.3, 0.0, 1.0)])?;
+
+    // Feed the landed position back: the entity stays on its target rather
+    // than overshooting and turning back.
+    env.move_position(start, pos(1, (0.3, 0.0, 1.0)));
+    env.step();
+    let arrivals = env.drain_arrivals();
+    let settled = env.drain_output();
+    ensure!(
+        settled.is_empty(),
+        "settled entity moved again: {settled:?}"
+    );
+    ensure!(
+        arrivals
+            == vec![(
+                Arrived {
+                    entity: 1,
+                    x: 0.3.into(),
+                    y: 0.0.into(),
+                },
+                1
+            )],
+        "expected one arrival, observed {arrivals:?}"
+    );
+    Ok(())
+}